@@ -0,0 +1,499 @@
+//! Changes made on GitHub on the user's behalf: reviews, comments and check re-runs.
+//! Each action is planned as the requests it would send, so a dry run can show them
+//! without sending anything, and every attempt lands in the audit log.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::audit::{self, AuditEntry, AuditOutcome};
+use crate::auth::{get_token, AuthError};
+use crate::github::{fetch_head_sha, GitHubError};
+use crate::http::{api_url, HttpClient, HttpError};
+
+/// Every action by name, as used for MCP tools and `[mcp] enabled_tools`
+pub const ACTION_NAMES: [&str; 3] = ["submit_review", "add_comment", "rerun_failed_checks"];
+
+/// Whether `value` can be a GitHub owner or repository name. Names are spliced into
+/// API paths, so anything that could change the path, like `..`, is refused.
+pub fn is_valid_name(value: &str) -> bool {
+    !value.is_empty()
+        && value != "."
+        && value != ".."
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// Check run conclusions that count as failed
+const FAILED_CONCLUSIONS: [&str; 4] = ["failure", "timed_out", "cancelled", "startup_failure"];
+
+#[derive(Debug, Error)]
+pub enum ActionError {
+    #[error("Invalid action: {0}")]
+    Invalid(String),
+    #[error(transparent)]
+    GitHub(#[from] GitHubError),
+}
+
+impl Serialize for ActionError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl From<AuthError> for ActionError {
+    fn from(e: AuthError) -> Self {
+        ActionError::GitHub(e.into())
+    }
+}
+
+impl From<HttpError> for ActionError {
+    fn from(e: HttpError) -> Self {
+        ActionError::GitHub(e.into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReviewEvent {
+    Approve,
+    Comment,
+    RequestChanges,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    SubmitReview {
+        owner: String,
+        repo: String,
+        number: i32,
+        event: ReviewEvent,
+        /// Required unless approving
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        body: Option<String>,
+    },
+    AddComment {
+        owner: String,
+        repo: String,
+        number: i32,
+        body: String,
+    },
+    /// Re-run the failed check runs of the pull request's head commit
+    RerunFailedChecks {
+        owner: String,
+        repo: String,
+        number: i32,
+    },
+}
+
+impl Action {
+    /// One of `ACTION_NAMES`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::SubmitReview { .. } => ACTION_NAMES[0],
+            Action::AddComment { .. } => ACTION_NAMES[1],
+            Action::RerunFailedChecks { .. } => ACTION_NAMES[2],
+        }
+    }
+
+    /// The owner, repository and number of the pull request acted on
+    fn pull_request(&self) -> (&str, &str, i32) {
+        match self {
+            Action::SubmitReview {
+                owner,
+                repo,
+                number,
+                ..
+            }
+            | Action::AddComment {
+                owner,
+                repo,
+                number,
+                ..
+            }
+            | Action::RerunFailedChecks {
+                owner,
+                repo,
+                number,
+            } => (owner, repo, *number),
+        }
+    }
+
+    /// The pull request acted on, e.g. `octo-org/api#1`
+    pub fn target(&self) -> String {
+        let (owner, repo, number) = self.pull_request();
+        format!("{}/{}#{}", owner, repo, number)
+    }
+
+    pub fn validate(&self) -> Result<(), ActionError> {
+        let (owner, repo, _) = self.pull_request();
+        for (key, value) in [("owner", owner), ("repo", repo)] {
+            if !is_valid_name(value) {
+                return Err(ActionError::Invalid(format!(
+                    "{} must be a GitHub name, got {:?}",
+                    key, value
+                )));
+            }
+        }
+        let blank = |body: Option<&String>| body.is_none_or(|body| body.trim().is_empty());
+        match self {
+            Action::SubmitReview {
+                event: ReviewEvent::Comment | ReviewEvent::RequestChanges,
+                body,
+                ..
+            } if blank(body.as_ref()) => Err(ActionError::Invalid(
+                "a review that comments or requests changes needs a body".to_string(),
+            )),
+            Action::AddComment { body, .. } if blank(Some(body)) => Err(ActionError::Invalid(
+                "comment body must not be empty".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A request an action sends; dry runs return these as-is
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedRequest {
+    pub description: String,
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+impl PlannedRequest {
+    fn post(description: String, path: &str, body: Option<Value>) -> Self {
+        Self {
+            description,
+            method: "POST".to_string(),
+            url: api_url(path),
+            body,
+        }
+    }
+}
+
+/// What an action sent and got back, or would have sent in a dry run
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionReport {
+    pub action: Action,
+    pub dry_run: bool,
+    pub requests: Vec<PlannedRequest>,
+    /// Response bodies, one per request; empty for dry runs
+    pub responses: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCheckRuns {
+    check_runs: Vec<ApiCheckRun>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCheckRun {
+    id: i64,
+    name: String,
+    conclusion: Option<String>,
+}
+
+async fn fetch_failed_check_runs(
+    client: &HttpClient,
+    token: &str,
+    full_name: &str,
+    sha: &str,
+) -> Result<Vec<ApiCheckRun>, GitHubError> {
+    let url = api_url(&format!("/repos/{}/commits/{}/check-runs", full_name, sha));
+    let runs: ApiCheckRuns = client
+        .github_get(&url, token)
+        .query(&[("per_page", "100")])
+        .fetch("Failed to fetch check runs")
+        .await?
+        .json()?;
+    Ok(runs
+        .check_runs
+        .into_iter()
+        .filter(|run| {
+            run.conclusion
+                .as_deref()
+                .is_some_and(|conclusion| FAILED_CONCLUSIONS.contains(&conclusion))
+        })
+        .collect())
+}
+
+/// The requests `action` would send. Only re-running checks needs to look anything
+/// up first, to find which check runs failed.
+pub async fn plan(action: &Action) -> Result<Vec<PlannedRequest>, ActionError> {
+    action.validate()?;
+    let target = action.target();
+    match action {
+        Action::SubmitReview {
+            owner,
+            repo,
+            number,
+            event,
+            body,
+        } => {
+            let verb = match event {
+                ReviewEvent::Approve => "Approve",
+                ReviewEvent::Comment => "Comment on",
+                ReviewEvent::RequestChanges => "Request changes on",
+            };
+            let mut payload = json!({ "event": event });
+            if let Some(body) = body {
+                payload["body"] = json!(body);
+            }
+            Ok(vec![PlannedRequest::post(
+                format!("{} {}", verb, target),
+                &format!("/repos/{}/{}/pulls/{}/reviews", owner, repo, number),
+                Some(payload),
+            )])
+        }
+        Action::AddComment {
+            owner,
+            repo,
+            number,
+            body,
+        } => Ok(vec![PlannedRequest::post(
+            format!("Comment on {}", target),
+            &format!("/repos/{}/{}/issues/{}/comments", owner, repo, number),
+            Some(json!({ "body": body })),
+        )]),
+        Action::RerunFailedChecks {
+            owner,
+            repo,
+            number,
+        } => {
+            let token = get_token().await?;
+            let client = HttpClient::from_env();
+            let full_name = format!("{}/{}", owner, repo);
+            let sha = fetch_head_sha(&client, &token, &full_name, *number).await?;
+            let runs = fetch_failed_check_runs(&client, &token, &full_name, &sha).await?;
+            Ok(runs
+                .into_iter()
+                .map(|run| {
+                    PlannedRequest::post(
+                        format!("Re-run {} on {}", run.name, target),
+                        &format!("/repos/{}/check-runs/{}/rerequest", full_name, run.id),
+                        None,
+                    )
+                })
+                .collect())
+        }
+    }
+}
+
+/// Send planned requests in order, stopping at the first that fails
+pub async fn perform(requests: &[PlannedRequest]) -> Result<Vec<Value>, ActionError> {
+    let token = get_token().await?;
+    let client = HttpClient::from_env();
+
+    let mut responses = Vec::new();
+    for request in requests {
+        let mut builder = client.github_post(&request.url, &token);
+        if let Some(body) = &request.body {
+            builder = builder.json(body);
+        }
+        let response = builder
+            .fetch(&format!("{} failed", request.description))
+            .await?;
+        responses.push(serde_json::from_str(response.text()).unwrap_or(Value::Null));
+    }
+    Ok(responses)
+}
+
+/// Plan `action` and, unless `dry_run`, send it, recording the outcome in the audit log
+/// under `source` (e.g. `mcp`)
+pub async fn run(action: Action, dry_run: bool, source: &str) -> Result<ActionReport, ActionError> {
+    let mut entry = AuditEntry::new(source, action.clone(), AuditOutcome::Failed);
+    let result: Result<ActionReport, ActionError> = async {
+        let requests = plan(&action).await?;
+        entry.requests = requests.clone();
+        let responses = if dry_run {
+            Vec::new()
+        } else {
+            perform(&requests).await?
+        };
+        Ok(ActionReport {
+            action,
+            dry_run,
+            requests,
+            responses,
+        })
+    }
+    .await;
+
+    match &result {
+        Ok(_) if dry_run => entry.outcome = AuditOutcome::DryRun,
+        Ok(_) => entry.outcome = AuditOutcome::Performed,
+        Err(e) => entry.error = Some(e.to_string()),
+    }
+    if let Err(e) = audit::record(&entry) {
+        tracing::error!(
+            action = entry.action.name(),
+            "Failed to write audit log: {}",
+            e
+        );
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FakeGitHubEnv;
+    use ghview_fake_github::{FakePull, Scenario};
+    use serial_test::serial;
+
+    fn scenario() -> Scenario {
+        Scenario::new()
+            .token("gho_test", "octocat", &["repo"])
+            .pull(
+                FakePull::new("octo-org/api", 1, "Add endpoint")
+                    .check_run("build", "success")
+                    .check_run("test", "failure")
+                    .check_run("lint", "timed_out"),
+            )
+    }
+
+    fn review(event: ReviewEvent, body: Option<&str>) -> Action {
+        Action::SubmitReview {
+            owner: "octo-org".to_string(),
+            repo: "api".to_string(),
+            number: 1,
+            event,
+            body: body.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_validate_requires_bodies() {
+        review(ReviewEvent::Approve, None).validate().unwrap();
+        assert!(review(ReviewEvent::RequestChanges, Some("  "))
+            .validate()
+            .is_err());
+        let comment = Action::AddComment {
+            owner: "octo-org".to_string(),
+            repo: "api".to_string(),
+            number: 1,
+            body: String::new(),
+        };
+        assert!(comment.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_names_outside_github_syntax() {
+        assert!(is_valid_name("octo-org"));
+        assert!(is_valid_name("my_repo.rs"));
+        for name in ["", ".", "..", "a/b", "a b", "a?b", "a%2Fb", "ré"] {
+            assert!(!is_valid_name(name), "accepted {:?}", name);
+        }
+        let rerun = Action::RerunFailedChecks {
+            owner: "octo-org".to_string(),
+            repo: "..".to_string(),
+            number: 1,
+        };
+        assert!(rerun.validate().is_err());
+    }
+
+    #[test]
+    fn test_action_serializes_with_its_name() {
+        let action = review(ReviewEvent::RequestChanges, Some("Needs tests"));
+        assert_eq!(
+            serde_json::to_value(&action).unwrap(),
+            json!({
+                "action": "submit_review",
+                "owner": "octo-org",
+                "repo": "api",
+                "number": 1,
+                "event": "REQUEST_CHANGES",
+                "body": "Needs tests",
+            })
+        );
+        assert_eq!(action.name(), "submit_review");
+        assert_eq!(action.target(), "octo-org/api#1");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_dry_run_sends_nothing() {
+        let fake = FakeGitHubEnv::start(scenario())
+            .await
+            .with_env_token("gho_test");
+
+        let report = run(review(ReviewEvent::Approve, None), true, "test")
+            .await
+            .unwrap();
+        assert_eq!(report.requests.len(), 1);
+        assert_eq!(report.requests[0].description, "Approve octo-org/api#1");
+        assert_eq!(report.requests[0].body, Some(json!({"event": "APPROVE"})));
+        assert!(fake.server.requests().is_empty());
+
+        let entries = audit::read_entries(None).unwrap();
+        assert_eq!(entries[0].outcome, AuditOutcome::DryRun);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_rerun_failed_checks() {
+        let fake = FakeGitHubEnv::start(scenario())
+            .await
+            .with_env_token("gho_test");
+        let action = Action::RerunFailedChecks {
+            owner: "octo-org".to_string(),
+            repo: "api".to_string(),
+            number: 1,
+        };
+
+        let report = run(action, false, "test").await.unwrap();
+        let descriptions: Vec<&str> = report
+            .requests
+            .iter()
+            .map(|request| request.description.as_str())
+            .collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                "Re-run test on octo-org/api#1",
+                "Re-run lint on octo-org/api#1"
+            ]
+        );
+        let rerequested = fake
+            .server
+            .requests()
+            .iter()
+            .filter(|request| request.method == "POST")
+            .count();
+        assert_eq!(rerequested, 2);
+        assert_eq!(
+            audit::read_entries(None).unwrap()[0].outcome,
+            AuditOutcome::Performed
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_failed_action_is_audited() {
+        let _fake = FakeGitHubEnv::start(scenario())
+            .await
+            .with_env_token("gho_test");
+        let action = Action::AddComment {
+            owner: "octo-org".to_string(),
+            repo: "api".to_string(),
+            number: 404,
+            body: "Ping".to_string(),
+        };
+
+        let error = run(action, false, "test").await.unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Comment on octo-org/api#404 failed"));
+        let entry = &audit::read_entries(None).unwrap()[0];
+        assert_eq!(entry.outcome, AuditOutcome::Failed);
+        assert_eq!(entry.requests.len(), 1);
+        assert!(entry.error.is_some());
+    }
+}
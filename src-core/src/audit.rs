@@ -0,0 +1,159 @@
+//! Append-only log of actions taken on GitHub on the user's behalf, one JSON object
+//! per line in `audit.jsonl` in the config directory
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use thiserror::Error;
+
+use crate::actions::{Action, PlannedRequest};
+use crate::config::{self, ConfigError};
+
+const AUDIT_FILE_NAME: &str = "audit.jsonl";
+
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("Failed to access audit log: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid audit entry: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+impl Serialize for AuditError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Performed,
+    /// Planned and shown, but nothing was sent
+    DryRun,
+    /// Not attempted, e.g. because the action is disabled
+    Refused,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    /// What asked for the action, e.g. `mcp`
+    pub source: String,
+    pub action: Action,
+    pub outcome: AuditOutcome,
+    /// The requests sent, or that would have been for a dry run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requests: Vec<PlannedRequest>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(source: &str, action: Action, outcome: AuditOutcome) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            source: source.to_string(),
+            action,
+            outcome,
+            requests: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+pub fn audit_path() -> Result<PathBuf, AuditError> {
+    Ok(config::config_dir()?.join(AUDIT_FILE_NAME))
+}
+
+/// Append `entry` to the audit log
+pub fn record(entry: &AuditEntry) -> Result<(), AuditError> {
+    let path = audit_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // One write per line, so entries from the app and the MCP server don't interleave
+    let line = format!("{}\n", serde_json::to_string(entry)?);
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?
+        .write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Audit entries, newest first, at most `limit` of them. Lines that can't be parsed
+/// are skipped.
+pub fn read_entries(limit: Option<usize>) -> Result<Vec<AuditEntry>, AuditError> {
+    let content = match std::fs::read_to_string(audit_path()?) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let entries = content
+        .lines()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::warn!("Skipping invalid audit entry: {}", e);
+                None
+            }
+        })
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::IsolatedEnv;
+    use serial_test::serial;
+
+    fn comment(body: &str) -> Action {
+        Action::AddComment {
+            owner: "octo-org".to_string(),
+            repo: "api".to_string(),
+            number: 1,
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_record_and_read_newest_first() {
+        let _env = IsolatedEnv::new();
+        assert!(read_entries(None).unwrap().is_empty());
+
+        record(&AuditEntry::new(
+            "mcp",
+            comment("first"),
+            AuditOutcome::DryRun,
+        ))
+        .unwrap();
+        let mut refused = AuditEntry::new("mcp", comment("second"), AuditOutcome::Refused);
+        refused.error = Some("disabled".to_string());
+        record(&refused).unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(audit_path().unwrap())
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        let entries = read_entries(None).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], refused);
+        assert_eq!(entries[1].action, comment("first"));
+        assert_eq!(read_entries(Some(1)).unwrap(), vec![refused]);
+    }
+}
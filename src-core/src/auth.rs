@@ -0,0 +1,730 @@
+use chrono::{DateTime, Duration, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::oneshot;
+
+use crate::auth_provider::{missing_scopes, parse_scopes, resolve_token, AuthSource};
+use crate::credential::{delete_credentials, load_credentials, save_credentials, Credentials};
+use crate::debug::{log_request, log_response, log_response_error};
+use crate::device_flow::{
+    poll_device_flow, DeviceFlowOutcome, DeviceFlowProgress, OAuthEndpoints, PollTiming,
+};
+use crate::http::{api_url, HttpClient, HttpError};
+
+// GitHub OAuth App credentials for ghview
+// Device flow doesn't require a client secret
+pub(crate) const GITHUB_CLIENT_ID: &str = "Iv23li78KgNyGR5C061j";
+
+/// Scopes ghview needs to list private pull requests and their checks
+pub const REQUIRED_SCOPES: [&str; 1] = ["repo"];
+
+/// Refresh expiring tokens this long before they actually expire
+const REFRESH_MARGIN_MINUTES: i64 = 5;
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("Credential error: {0}")]
+    Credential(String),
+    #[error(transparent)]
+    Http(#[from] HttpError),
+    #[error("OAuth error: {0}")]
+    OAuth(String),
+    #[error("Not authenticated")]
+    NotAuthenticated,
+    #[error("Invalid token: GitHub rejected it")]
+    InvalidToken,
+    #[error("GitHub is unavailable (HTTP {0}), try again later")]
+    Unavailable(u16),
+}
+
+impl Serialize for AuthError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthStatus {
+    pub authenticated: bool,
+    pub username: Option<String>,
+    /// Which provider supplied the token
+    #[serde(default)]
+    pub source: Option<AuthSource>,
+    /// Classic OAuth/PAT scopes; `None` for fine-grained tokens, which don't report them
+    #[serde(default)]
+    pub scopes: Option<Vec<String>>,
+    /// Entries of `REQUIRED_SCOPES` the token was not granted
+    #[serde(default)]
+    pub missing_scopes: Vec<String>,
+}
+
+impl AuthStatus {
+    fn unauthenticated() -> Self {
+        Self {
+            authenticated: false,
+            username: None,
+            source: None,
+            scopes: None,
+            missing_scopes: Vec::new(),
+        }
+    }
+
+    fn authenticated(user: TokenUser, source: AuthSource) -> Self {
+        let missing_scopes = user
+            .scopes
+            .as_deref()
+            .map(|scopes| missing_scopes(scopes, &REQUIRED_SCOPES))
+            .unwrap_or_default();
+        Self {
+            authenticated: true,
+            username: Some(user.login),
+            source: Some(source),
+            scopes: user.scopes,
+            missing_scopes,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    expires_in: u32,
+    interval: u32,
+}
+
+impl From<DeviceCodeResponse> for DeviceFlowInit {
+    fn from(response: DeviceCodeResponse) -> Self {
+        DeviceFlowInit {
+            user_code: response.user_code,
+            verification_uri: response.verification_uri,
+            device_code: response.device_code,
+            expires_in: response.expires_in,
+            interval: response.interval,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub(crate) struct AccessTokenResponse {
+    pub(crate) access_token: Option<String>,
+    token_type: Option<String>,
+    scope: Option<String>,
+    /// Seconds until `access_token` expires (expiring GitHub App user tokens only)
+    expires_in: Option<i64>,
+    refresh_token: Option<String>,
+    refresh_token_expires_in: Option<i64>,
+    /// Polling interval GitHub asks for along with a `slow_down` error
+    pub(crate) interval: Option<u64>,
+    pub(crate) error: Option<String>,
+    pub(crate) error_description: Option<String>,
+}
+
+impl AccessTokenResponse {
+    pub(crate) fn into_credentials(self, now: DateTime<Utc>) -> Option<Credentials> {
+        Some(Credentials {
+            access_token: self.access_token?,
+            refresh_token: self.refresh_token,
+            expires_at: self.expires_in.map(|secs| now + Duration::seconds(secs)),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceFlowInit {
+    pub user_code: String,
+    pub verification_uri: String,
+    pub device_code: String,
+    pub expires_in: u32,
+    pub interval: u32,
+}
+
+/// How a device flow ended
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum DeviceFlowResult {
+    Authorized { status: AuthStatus },
+    Expired,
+    Denied,
+    Cancelled,
+    Error { message: String },
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubUser {
+    login: String,
+}
+
+/// The owner of a token and the scopes GitHub reports for it
+struct TokenUser {
+    login: String,
+    scopes: Option<Vec<String>>,
+}
+
+/// Map a non-success `/user` status to an error; only 401 means the token itself is bad
+fn user_status_error(status: StatusCode) -> AuthError {
+    if status == StatusCode::UNAUTHORIZED {
+        AuthError::InvalidToken
+    } else {
+        AuthError::Unavailable(status.as_u16())
+    }
+}
+
+/// Fetch `/user` with `token`
+#[tracing::instrument(level = "debug", skip_all)]
+async fn fetch_token_user(client: &HttpClient, token: &str) -> Result<TokenUser, AuthError> {
+    let url = &api_url("/user");
+    let response = client.github_get(url, token).send().await?;
+
+    if !response.status().is_success() {
+        return Err(user_status_error(response.status()));
+    }
+
+    let scopes = parse_scopes(response.header("X-OAuth-Scopes"));
+    let user: GitHubUser = response.json()?;
+    Ok(TokenUser {
+        login: user.login,
+        scopes,
+    })
+}
+
+/// Login of the user `token` belongs to
+pub(crate) async fn fetch_login(client: &HttpClient, token: &str) -> Result<String, AuthError> {
+    Ok(fetch_token_user(client, token).await?.login)
+}
+
+#[tracing::instrument]
+pub async fn check_auth_status() -> Result<AuthStatus, AuthError> {
+    let Some(resolved) = resolve_token() else {
+        return Ok(AuthStatus::unauthenticated());
+    };
+
+    let client = HttpClient::from_env();
+    let token = if resolved.source == AuthSource::Stored {
+        match refresh_stored_token_if_needed(&client).await {
+            Ok(token) => token,
+            Err(AuthError::InvalidToken) => return Ok(logged_out_stored()),
+            Err(e) => return Err(e),
+        }
+    } else {
+        resolved.token
+    };
+
+    // Verify token by fetching user info
+    match fetch_token_user(&client, &token).await {
+        Ok(user) => Ok(AuthStatus::authenticated(user, resolved.source)),
+        // Revoked; only remove the token if ghview stored it
+        Err(AuthError::InvalidToken) if resolved.source == AuthSource::Stored => {
+            Ok(logged_out_stored())
+        }
+        Err(AuthError::InvalidToken) => Ok(AuthStatus::unauthenticated()),
+        // Server errors and network failures keep the credentials for the next attempt
+        Err(e) => Err(e),
+    }
+}
+
+fn logged_out_stored() -> AuthStatus {
+    let _ = delete_credentials();
+    AuthStatus::unauthenticated()
+}
+
+/// Log in with a pasted personal access token after checking it against `/user`
+#[tracing::instrument(skip_all)]
+pub async fn login_with_token(token: String) -> Result<AuthStatus, AuthError> {
+    let token = token.trim().to_string();
+    if token.is_empty() {
+        return Err(AuthError::InvalidToken);
+    }
+
+    let client = HttpClient::from_env();
+    let user = fetch_token_user(&client, &token).await?;
+
+    save_credentials(&Credentials {
+        access_token: token,
+        ..Default::default()
+    })
+    .map_err(|e| AuthError::Credential(e.to_string()))?;
+
+    Ok(AuthStatus::authenticated(user, AuthSource::Stored))
+}
+
+/// Ask GitHub for a device code; pass the result to `complete_device_flow`
+#[tracing::instrument(skip_all)]
+pub async fn request_device_code() -> Result<DeviceFlowInit, AuthError> {
+    let client = HttpClient::from_env();
+    let url = &OAuthEndpoints::from_env().device_code_url;
+
+    log_request("POST", url);
+    let response = client
+        .post(url)
+        .header("Accept", "application/json")
+        .form(&[("client_id", GITHUB_CLIENT_ID), ("scope", "repo")])
+        .send()
+        .await?;
+    log_response(url, response.status());
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().to_string();
+        log_response_error(url, status, &error_text);
+        return Err(AuthError::OAuth(format!(
+            "Failed to start device flow: {}",
+            error_text
+        )));
+    }
+
+    let device_code_response: DeviceCodeResponse = response.json()?;
+    Ok(device_code_response.into())
+}
+
+/// Poll for the token of a requested device code until the flow ends, storing it
+/// once authorized
+///
+/// `on_progress` is called after each pending poll; the flow is cancelled when
+/// `cancel` fires or its sender is dropped.
+pub async fn complete_device_flow(
+    init: &DeviceFlowInit,
+    cancel: oneshot::Receiver<()>,
+    on_progress: impl Fn(DeviceFlowProgress),
+) -> DeviceFlowResult {
+    let client = HttpClient::from_env();
+    let outcome = poll_device_flow(
+        &client,
+        &OAuthEndpoints::from_env(),
+        &init.device_code,
+        PollTiming::from_secs(init.interval, init.expires_in),
+        cancel,
+        on_progress,
+    )
+    .await;
+    finish_device_flow(&client, outcome).await
+}
+
+/// Run the device flow to completion in the foreground, for the CLI; `on_code`
+/// receives the code to enter at the verification URI
+pub async fn run_device_flow(
+    on_code: impl FnOnce(&DeviceFlowInit),
+    on_progress: impl Fn(DeviceFlowProgress),
+) -> DeviceFlowResult {
+    let init = match request_device_code().await {
+        Ok(init) => init,
+        Err(e) => {
+            return DeviceFlowResult::Error {
+                message: e.to_string(),
+            }
+        }
+    };
+    on_code(&init);
+
+    // Keep the sender alive; dropping it would cancel the flow
+    let (_cancel_tx, cancel_rx) = oneshot::channel();
+    complete_device_flow(&init, cancel_rx, on_progress).await
+}
+
+/// Store the token of an authorized flow and describe the outcome for the UI
+async fn finish_device_flow(
+    client: &HttpClient,
+    outcome: Result<DeviceFlowOutcome, AuthError>,
+) -> DeviceFlowResult {
+    let authorized = async {
+        match outcome? {
+            DeviceFlowOutcome::Authorized(credentials) => {
+                save_credentials(&credentials).map_err(|e| AuthError::Credential(e.to_string()))?;
+                let user = fetch_token_user(client, &credentials.access_token).await?;
+                Ok(DeviceFlowResult::Authorized {
+                    status: AuthStatus::authenticated(user, AuthSource::Stored),
+                })
+            }
+            DeviceFlowOutcome::Expired => Ok(DeviceFlowResult::Expired),
+            DeviceFlowOutcome::Denied => Ok(DeviceFlowResult::Denied),
+            DeviceFlowOutcome::Cancelled => Ok(DeviceFlowResult::Cancelled),
+        }
+    };
+    authorized
+        .await
+        .unwrap_or_else(|e: AuthError| DeviceFlowResult::Error {
+            message: e.to_string(),
+        })
+}
+
+pub async fn logout() -> Result<(), AuthError> {
+    delete_credentials().map_err(|e| AuthError::Credential(e.to_string()))?;
+    Ok(())
+}
+
+/// Whether `credentials` expire within the refresh margin and can be refreshed
+fn needs_refresh(credentials: &Credentials, now: DateTime<Utc>) -> bool {
+    match (&credentials.refresh_token, credentials.expires_at) {
+        (Some(_), Some(expires_at)) => {
+            expires_at - Duration::minutes(REFRESH_MARGIN_MINUTES) <= now
+        }
+        _ => false,
+    }
+}
+
+/// Exchange a refresh token for a new access token
+#[tracing::instrument(skip_all)]
+async fn refresh_access_token(
+    client: &HttpClient,
+    refresh_token: &str,
+) -> Result<Credentials, AuthError> {
+    let endpoints = OAuthEndpoints::from_env();
+    let url = &endpoints.access_token_url;
+
+    log_request("POST", url);
+    let response = client
+        .post(url)
+        .header("Accept", "application/json")
+        .form(&[
+            ("client_id", GITHUB_CLIENT_ID),
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ])
+        .send()
+        .await?;
+    log_response(url, response.status());
+
+    if !response.status().is_success() {
+        return Err(AuthError::Unavailable(response.status().as_u16()));
+    }
+
+    let token_response: AccessTokenResponse = response.json()?;
+    if let Some(error) = token_response.error {
+        // bad_refresh_token: the refresh token expired or was revoked
+        tracing::warn!("Token refresh failed: {}", error);
+        return Err(AuthError::InvalidToken);
+    }
+    token_response
+        .into_credentials(Utc::now())
+        .ok_or_else(|| AuthError::OAuth("Refresh response had no access token".to_string()))
+}
+
+/// The stored access token, refreshed first if it is about to expire
+async fn refresh_stored_token_if_needed(client: &HttpClient) -> Result<String, AuthError> {
+    let credentials = load_credentials().map_err(|_| AuthError::NotAuthenticated)?;
+    if !needs_refresh(&credentials, Utc::now()) {
+        return Ok(credentials.access_token);
+    }
+
+    let refresh_token = credentials.refresh_token.as_deref().unwrap_or_default();
+    let refreshed = refresh_access_token(client, refresh_token).await?;
+    save_credentials(&refreshed).map_err(|e| AuthError::Credential(e.to_string()))?;
+    tracing::info!("Refreshed expiring GitHub token");
+    Ok(refreshed.access_token)
+}
+
+/// The token to use for API requests, from the highest-precedence provider
+pub async fn get_token() -> Result<String, AuthError> {
+    let resolved = resolve_token().ok_or(AuthError::NotAuthenticated)?;
+    if resolved.source != AuthSource::Stored {
+        return Ok(resolved.token);
+    }
+    refresh_stored_token_if_needed(&HttpClient::from_env()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credential::get_credentials_path;
+    use crate::test_support::FakeGitHubEnv;
+    use ghview_fake_github::{DevicePoll, Scenario, DEVICE_CODE};
+    use serial_test::serial;
+
+    fn expiring(expires_at: Option<DateTime<Utc>>, refresh: bool) -> Credentials {
+        Credentials {
+            access_token: "ghu_access".to_string(),
+            refresh_token: refresh.then(|| "ghr_refresh".to_string()),
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_user_status_error_distinguishes_revoked_tokens() {
+        assert!(matches!(
+            user_status_error(StatusCode::UNAUTHORIZED),
+            AuthError::InvalidToken
+        ));
+        assert!(matches!(
+            user_status_error(StatusCode::INTERNAL_SERVER_ERROR),
+            AuthError::Unavailable(500)
+        ));
+        assert!(matches!(
+            user_status_error(StatusCode::FORBIDDEN),
+            AuthError::Unavailable(403)
+        ));
+    }
+
+    #[test]
+    fn test_needs_refresh() {
+        let now = Utc::now();
+        assert!(!needs_refresh(&expiring(None, false), now));
+        assert!(!needs_refresh(
+            &expiring(Some(now + Duration::hours(8)), true),
+            now
+        ));
+        assert!(needs_refresh(
+            &expiring(Some(now + Duration::minutes(2)), true),
+            now
+        ));
+        assert!(needs_refresh(
+            &expiring(Some(now - Duration::minutes(1)), true),
+            now
+        ));
+        // Without a refresh token there is nothing to do but let it expire
+        assert!(!needs_refresh(
+            &expiring(Some(now - Duration::minutes(1)), false),
+            now
+        ));
+    }
+
+    #[test]
+    fn test_token_response_into_credentials() {
+        let now = Utc::now();
+        let response: AccessTokenResponse = serde_json::from_str(
+            r#"{
+                "access_token": "ghu_abc",
+                "expires_in": 28800,
+                "refresh_token": "ghr_def",
+                "refresh_token_expires_in": 15811200,
+                "token_type": "bearer",
+                "scope": ""
+            }"#,
+        )
+        .unwrap();
+        let credentials = response.into_credentials(now).unwrap();
+        assert_eq!(credentials.access_token, "ghu_abc");
+        assert_eq!(credentials.refresh_token.as_deref(), Some("ghr_def"));
+        assert_eq!(credentials.expires_at, Some(now + Duration::hours(8)));
+
+        let response: AccessTokenResponse =
+            serde_json::from_str(r#"{"access_token": "gho_abc", "scope": "repo"}"#).unwrap();
+        let credentials = response.into_credentials(now).unwrap();
+        assert!(credentials.refresh_token.is_none());
+        assert!(credentials.expires_at.is_none());
+    }
+
+    #[test]
+    fn test_auth_status_reports_missing_scopes() {
+        let user = |scopes: Option<Vec<&str>>| TokenUser {
+            login: "octocat".to_string(),
+            scopes: scopes.map(|s| s.into_iter().map(str::to_string).collect()),
+        };
+
+        let status = AuthStatus::authenticated(user(Some(vec!["read:org"])), AuthSource::Stored);
+        assert_eq!(status.missing_scopes, vec!["repo".to_string()]);
+
+        let status = AuthStatus::authenticated(user(Some(vec!["repo"])), AuthSource::Stored);
+        assert!(status.missing_scopes.is_empty());
+
+        // Fine-grained tokens don't report scopes, so nothing can be flagged
+        let status = AuthStatus::authenticated(user(None), AuthSource::Environment);
+        assert!(status.missing_scopes.is_empty());
+    }
+
+    fn scenario() -> Scenario {
+        Scenario::new()
+            .token("gho_valid", "octocat", &["repo", "read:org"])
+            .token("gho_no_repo", "octocat", &["read:org"])
+            .fine_grained_token("github_pat_fine", "octocat")
+    }
+
+    fn store(credentials: Credentials) {
+        save_credentials(&credentials).unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_login_with_token_saves_valid_token() {
+        let _fake = FakeGitHubEnv::start(scenario()).await;
+        let status = login_with_token("  gho_valid\n".to_string()).await.unwrap();
+
+        assert!(status.authenticated);
+        assert_eq!(status.username.as_deref(), Some("octocat"));
+        assert_eq!(status.source, Some(AuthSource::Stored));
+        assert!(status.missing_scopes.is_empty());
+        assert_eq!(load_credentials().unwrap().access_token, "gho_valid");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_login_with_token_reports_scopes() {
+        let _fake = FakeGitHubEnv::start(scenario()).await;
+        let status = login_with_token("gho_no_repo".to_string()).await.unwrap();
+        assert_eq!(status.missing_scopes, vec!["repo".to_string()]);
+
+        let status = login_with_token("github_pat_fine".to_string())
+            .await
+            .unwrap();
+        assert_eq!(status.scopes, None);
+        assert!(status.missing_scopes.is_empty());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_login_with_token_errors() {
+        let fake = FakeGitHubEnv::start(scenario()).await;
+        assert!(matches!(
+            login_with_token("gho_unknown".to_string()).await,
+            Err(AuthError::InvalidToken)
+        ));
+        assert!(matches!(
+            login_with_token(" ".to_string()).await,
+            Err(AuthError::InvalidToken)
+        ));
+
+        fake.server.update(|s| *s = s.clone().fail("/user", 503));
+        assert!(matches!(
+            login_with_token("gho_valid".to_string()).await,
+            Err(AuthError::Unavailable(503))
+        ));
+        assert!(load_credentials().is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_check_auth_status_with_environment_token() {
+        let _fake = FakeGitHubEnv::start(scenario())
+            .await
+            .with_env_token("gho_valid");
+        let status = check_auth_status().await.unwrap();
+        assert!(status.authenticated);
+        assert_eq!(status.source, Some(AuthSource::Environment));
+        assert_eq!(
+            status.scopes,
+            Some(vec!["repo".to_string(), "read:org".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_check_auth_status_unauthenticated() {
+        let _fake = FakeGitHubEnv::start(scenario()).await;
+        assert!(!check_auth_status().await.unwrap().authenticated);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_check_auth_status_deletes_revoked_stored_token() {
+        let _fake = FakeGitHubEnv::start(scenario()).await;
+        store(Credentials {
+            access_token: "gho_revoked".to_string(),
+            ..Default::default()
+        });
+
+        assert!(!check_auth_status().await.unwrap().authenticated);
+        assert!(!get_credentials_path().unwrap().exists());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_check_auth_status_keeps_token_on_server_error() {
+        let _fake = FakeGitHubEnv::start(scenario().fail("/user", 500)).await;
+        store(Credentials {
+            access_token: "gho_valid".to_string(),
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            check_auth_status().await,
+            Err(AuthError::Unavailable(500))
+        ));
+        assert_eq!(load_credentials().unwrap().access_token, "gho_valid");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_check_auth_status_refreshes_expiring_token() {
+        let fake = FakeGitHubEnv::start(scenario().refresh_token("ghr_valid", "octocat")).await;
+        store(Credentials {
+            access_token: "ghu_expired".to_string(),
+            refresh_token: Some("ghr_valid".to_string()),
+            expires_at: Some(Utc::now() - Duration::minutes(1)),
+        });
+
+        let status = check_auth_status().await.unwrap();
+        assert!(status.authenticated);
+
+        let refreshed = load_credentials().unwrap();
+        assert_ne!(refreshed.access_token, "ghu_expired");
+        assert!(refreshed.expires_at.unwrap() > Utc::now());
+        let refresh_request = fake
+            .server
+            .requests()
+            .into_iter()
+            .find(|r| r.path == "/login/oauth/access_token")
+            .unwrap();
+        assert!(refresh_request.body.contains("grant_type=refresh_token"));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_check_auth_status_logs_out_on_bad_refresh_token() {
+        let _fake = FakeGitHubEnv::start(scenario()).await;
+        store(Credentials {
+            access_token: "ghu_expired".to_string(),
+            refresh_token: Some("ghr_revoked".to_string()),
+            expires_at: Some(Utc::now() - Duration::minutes(1)),
+        });
+
+        assert!(!check_auth_status().await.unwrap().authenticated);
+        assert!(load_credentials().is_err());
+    }
+
+    /// Poll the fake server's device flow with short intervals and finish it
+    async fn poll_fake_device_flow() -> DeviceFlowResult {
+        let client = HttpClient::from_env();
+        let (_cancel_tx, cancel_rx) = oneshot::channel();
+        let timing = PollTiming {
+            interval: std::time::Duration::from_millis(10),
+            expires_in: std::time::Duration::from_secs(5),
+            slow_down_step: std::time::Duration::from_millis(10),
+        };
+        let outcome = poll_device_flow(
+            &client,
+            &OAuthEndpoints::from_env(),
+            DEVICE_CODE,
+            timing,
+            cancel_rx,
+            |_| {},
+        )
+        .await;
+        finish_device_flow(&client, outcome).await
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_device_flow_against_fake_server() {
+        let _fake = FakeGitHubEnv::start(scenario().device_flow(vec![
+            DevicePoll::Pending,
+            DevicePoll::Authorize {
+                login: "hubot".to_string(),
+                expires_in: Some(28800),
+            },
+        ]))
+        .await;
+        match poll_fake_device_flow().await {
+            DeviceFlowResult::Authorized { status } => {
+                assert_eq!(status.username.as_deref(), Some("hubot"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(load_credentials().unwrap().refresh_token.is_some());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_device_flow_denied() {
+        let _fake = FakeGitHubEnv::start(scenario().device_flow(vec![DevicePoll::Denied])).await;
+        assert!(matches!(
+            poll_fake_device_flow().await,
+            DeviceFlowResult::Denied
+        ));
+        assert!(load_credentials().is_err());
+    }
+}
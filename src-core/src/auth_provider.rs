@@ -0,0 +1,323 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::credential::load_credentials;
+
+/// Environment variables checked for a token, in order (same precedence as the gh CLI)
+pub const TOKEN_ENV_VARS: [&str; 2] = ["GH_TOKEN", "GITHUB_TOKEN"];
+
+const GITHUB_HOST: &str = "github.com";
+
+/// Where the token in use came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthSource {
+    /// `GH_TOKEN` or `GITHUB_TOKEN`
+    Environment,
+    /// Saved by ghview after a device-flow login or a pasted personal access token
+    Stored,
+    /// `oauth_token` from the gh CLI hosts file
+    GhCli,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedToken {
+    pub token: String,
+    pub source: AuthSource,
+}
+
+/// A place a token can be obtained from without user interaction
+pub trait AuthProvider {
+    fn source(&self) -> AuthSource;
+    fn token(&self) -> Option<String>;
+}
+
+/// Reads the first non-empty variable of `TOKEN_ENV_VARS`
+pub struct EnvironmentProvider;
+
+impl AuthProvider for EnvironmentProvider {
+    fn source(&self) -> AuthSource {
+        AuthSource::Environment
+    }
+
+    fn token(&self) -> Option<String> {
+        TOKEN_ENV_VARS.iter().find_map(|name| {
+            std::env::var(name)
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        })
+    }
+}
+
+/// Credentials saved by ghview in the configured credential store
+pub struct StoredProvider;
+
+impl AuthProvider for StoredProvider {
+    fn source(&self) -> AuthSource {
+        AuthSource::Stored
+    }
+
+    fn token(&self) -> Option<String> {
+        load_credentials().ok().map(|c| c.access_token)
+    }
+}
+
+/// The github.com login of the gh CLI
+pub struct GhCliProvider;
+
+#[derive(Debug, Deserialize)]
+struct GhHost {
+    oauth_token: Option<String>,
+}
+
+impl GhCliProvider {
+    /// `$GH_CONFIG_DIR/hosts.yml`, else `$XDG_CONFIG_HOME/gh/hosts.yml`, else `~/.config/gh/hosts.yml`
+    pub fn hosts_path() -> Option<PathBuf> {
+        let non_empty = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let config_dir = match non_empty("GH_CONFIG_DIR") {
+            Some(dir) => PathBuf::from(dir),
+            None => match non_empty("XDG_CONFIG_HOME") {
+                Some(dir) => PathBuf::from(dir).join("gh"),
+                None => PathBuf::from(non_empty("HOME")?).join(".config").join("gh"),
+            },
+        };
+        Some(config_dir.join("hosts.yml"))
+    }
+}
+
+/// Extract the github.com token from the contents of a gh hosts file
+///
+/// Recent gh versions keep the token in the OS keyring and omit `oauth_token`,
+/// in which case there is nothing to import.
+pub fn parse_gh_hosts(content: &str) -> Option<String> {
+    let hosts: HashMap<String, GhHost> = serde_yaml::from_str(content).ok()?;
+    hosts
+        .get(GITHUB_HOST)?
+        .oauth_token
+        .clone()
+        .filter(|token| !token.is_empty())
+}
+
+impl AuthProvider for GhCliProvider {
+    fn source(&self) -> AuthSource {
+        AuthSource::GhCli
+    }
+
+    fn token(&self) -> Option<String> {
+        let content = std::fs::read_to_string(Self::hosts_path()?).ok()?;
+        parse_gh_hosts(&content)
+    }
+}
+
+/// Providers in precedence order: an explicit environment token overrides ghview's own
+/// login, which in turn overrides the gh CLI
+pub fn providers() -> Vec<Box<dyn AuthProvider>> {
+    vec![
+        Box::new(EnvironmentProvider),
+        Box::new(StoredProvider),
+        Box::new(GhCliProvider),
+    ]
+}
+
+/// The token of the highest-precedence provider that has one
+pub fn resolve_token() -> Option<ResolvedToken> {
+    providers().into_iter().find_map(|provider| {
+        provider.token().map(|token| ResolvedToken {
+            token,
+            source: provider.source(),
+        })
+    })
+}
+
+/// Parse the comma-separated `X-OAuth-Scopes` header
+///
+/// Fine-grained tokens and GitHub App tokens don't send the header at all, which
+/// is reported as `None` rather than as an empty scope list.
+pub fn parse_scopes(header: Option<&str>) -> Option<Vec<String>> {
+    header.map(|value| {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|scope| !scope.is_empty())
+            .map(str::to_string)
+            .collect()
+    })
+}
+
+/// Entries of `required` not covered by `granted`
+///
+/// A parent scope covers its children, so `repo` satisfies `repo:status`.
+pub fn missing_scopes(granted: &[String], required: &[&str]) -> Vec<String> {
+    required
+        .iter()
+        .filter(|scope| {
+            !granted.iter().any(|g| {
+                g == *scope || scope.split_once(':').is_some_and(|(parent, _)| g == parent)
+            })
+        })
+        .map(|scope| scope.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credential::{save_credentials, Credentials};
+    use crate::credential_store::STORE_ENV;
+    use serial_test::serial;
+    use std::env;
+    use std::fs;
+
+    const ISOLATED_VARS: [&str; 6] = [
+        "HOME",
+        "GH_TOKEN",
+        "GITHUB_TOKEN",
+        "GH_CONFIG_DIR",
+        "XDG_CONFIG_HOME",
+        STORE_ENV,
+    ];
+
+    /// Temporary HOME with all token-related variables cleared
+    struct TestEnv {
+        original: Vec<(&'static str, Option<String>)>,
+        temp_dir: PathBuf,
+    }
+
+    impl TestEnv {
+        fn new() -> Self {
+            let temp_dir = env::temp_dir().join(format!("ghview_test_{}", rand::random::<u64>()));
+            fs::create_dir_all(&temp_dir).unwrap();
+            let original = ISOLATED_VARS
+                .iter()
+                .map(|name| (*name, env::var(name).ok()))
+                .collect();
+            for name in ISOLATED_VARS {
+                env::remove_var(name);
+            }
+            env::set_var("HOME", &temp_dir);
+            TestEnv { original, temp_dir }
+        }
+
+        fn write_gh_hosts(&self, content: &str) {
+            let dir = self.temp_dir.join(".config").join("gh");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("hosts.yml"), content).unwrap();
+        }
+    }
+
+    impl Drop for TestEnv {
+        fn drop(&mut self) {
+            for (name, value) in &self.original {
+                match value {
+                    Some(value) => env::set_var(name, value),
+                    None => env::remove_var(name),
+                }
+            }
+            let _ = fs::remove_dir_all(&self.temp_dir);
+        }
+    }
+
+    const GH_HOSTS: &str = "\
+github.com:
+    users:
+        octocat:
+            oauth_token: gho_from_gh
+    git_protocol: https
+    user: octocat
+    oauth_token: gho_from_gh
+";
+
+    #[test]
+    fn test_parse_gh_hosts() {
+        assert_eq!(parse_gh_hosts(GH_HOSTS), Some("gho_from_gh".to_string()));
+        // Token kept in the keyring by newer gh versions
+        assert_eq!(parse_gh_hosts("github.com:\n    user: octocat\n"), None);
+        assert_eq!(
+            parse_gh_hosts("ghe.example.com:\n    oauth_token: gho_enterprise\n"),
+            None
+        );
+        assert_eq!(parse_gh_hosts("not: [valid"), None);
+    }
+
+    #[test]
+    fn test_parse_scopes() {
+        assert_eq!(
+            parse_scopes(Some("repo, read:org")),
+            Some(vec!["repo".to_string(), "read:org".to_string()])
+        );
+        assert_eq!(parse_scopes(Some("")), Some(vec![]));
+        assert_eq!(parse_scopes(None), None);
+    }
+
+    #[test]
+    fn test_missing_scopes() {
+        let granted = vec!["repo".to_string(), "read:org".to_string()];
+        assert!(missing_scopes(&granted, &["repo", "repo:status"]).is_empty());
+        assert_eq!(
+            missing_scopes(&granted, &["repo", "workflow"]),
+            vec!["workflow".to_string()]
+        );
+        assert_eq!(
+            missing_scopes(&["public_repo".to_string()], &["repo"]),
+            vec!["repo".to_string()]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_gh_hosts_path_precedence() {
+        let env = TestEnv::new();
+        assert_eq!(
+            GhCliProvider::hosts_path().unwrap(),
+            env.temp_dir.join(".config/gh/hosts.yml")
+        );
+        env::set_var("XDG_CONFIG_HOME", "/xdg");
+        assert_eq!(
+            GhCliProvider::hosts_path().unwrap(),
+            PathBuf::from("/xdg/gh/hosts.yml")
+        );
+        env::set_var("GH_CONFIG_DIR", "/ghconfig");
+        assert_eq!(
+            GhCliProvider::hosts_path().unwrap(),
+            PathBuf::from("/ghconfig/hosts.yml")
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_resolve_token_precedence() {
+        let env = TestEnv::new();
+        assert!(resolve_token().is_none());
+
+        env.write_gh_hosts(GH_HOSTS);
+        let resolved = resolve_token().unwrap();
+        assert_eq!(resolved.source, AuthSource::GhCli);
+        assert_eq!(resolved.token, "gho_from_gh");
+
+        save_credentials(&Credentials {
+            access_token: "gho_stored".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let resolved = resolve_token().unwrap();
+        assert_eq!(resolved.source, AuthSource::Stored);
+        assert_eq!(resolved.token, "gho_stored");
+
+        env::set_var("GITHUB_TOKEN", "github_pat_env");
+        assert_eq!(resolve_token().unwrap().token, "github_pat_env");
+        env::set_var("GH_TOKEN", "gh_token_env");
+        let resolved = resolve_token().unwrap();
+        assert_eq!(resolved.source, AuthSource::Environment);
+        assert_eq!(resolved.token, "gh_token_env");
+    }
+
+    #[test]
+    #[serial]
+    fn test_blank_environment_token_is_ignored() {
+        let _env = TestEnv::new();
+        env::set_var("GH_TOKEN", "  ");
+        assert!(resolve_token().is_none());
+    }
+}
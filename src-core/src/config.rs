@@ -0,0 +1,696 @@
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, RwLock};
+use thiserror::Error;
+use toml::{Table, Value};
+
+use crate::actions::ACTION_NAMES;
+use crate::credential_store::CredentialBackend;
+use crate::filters::FilterRule;
+use crate::logging;
+use crate::view::{GroupBy, SortField, SortKey};
+
+/// Version written to new config files; older files are migrated on load
+pub const CONFIG_VERSION: u32 = 1;
+
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Shorter intervals would burn through the search API rate limit
+const MIN_REFRESH_INTERVAL_SECS: u64 = 30;
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid config file {path}: {message}")]
+    Parse { path: String, message: String },
+    #[error("Invalid setting `{key}`: {message}")]
+    Invalid { key: &'static str, message: String },
+    #[error("Config version {found} is newer than this ghview supports ({supported}); upgrade ghview or remove the file")]
+    UnsupportedVersion { found: i64, supported: u32 },
+    #[error("Failed to write config: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("Config directory not found")]
+    ConfigDirNotFound,
+}
+
+impl Serialize for ConfigError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+fn invalid(key: &'static str, message: impl Into<String>) -> ConfigError {
+    ConfigError::Invalid {
+        key,
+        message: message.into(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub version: u32,
+    pub github: GitHubSettings,
+    pub pull_requests: PullRequestSettings,
+    pub ipc: IpcSettings,
+    pub auth: AuthSettings,
+    pub logging: LoggingSettings,
+    pub http: HttpSettings,
+    pub view: ViewSettings,
+    pub sla: SlaSettings,
+    pub mcp: McpSettings,
+    /// `[[filters]]` rules applied to every pull request list
+    pub filters: Vec<FilterRule>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            github: GitHubSettings::default(),
+            pull_requests: PullRequestSettings::default(),
+            ipc: IpcSettings::default(),
+            auth: AuthSettings::default(),
+            logging: LoggingSettings::default(),
+            http: HttpSettings::default(),
+            view: ViewSettings::default(),
+            sla: SlaSettings::default(),
+            mcp: McpSettings::default(),
+            filters: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GitHubSettings {
+    /// REST API base URL; set for GitHub Enterprise Server (`https://HOST/api/v3`)
+    pub api_url: String,
+    /// Base URL of the OAuth device flow endpoints
+    pub oauth_url: String,
+}
+
+impl Default for GitHubSettings {
+    fn default() -> Self {
+        Self {
+            api_url: "https://api.github.com".to_string(),
+            oauth_url: "https://github.com".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PullRequestSettings {
+    /// Seconds between automatic refreshes of the list; 0 turns them off
+    pub refresh_interval_secs: u64,
+    /// Search queries whose results are merged into the list
+    pub queries: Vec<String>,
+}
+
+impl Default for PullRequestSettings {
+    fn default() -> Self {
+        Self {
+            refresh_interval_secs: 300,
+            queries: vec![
+                "is:pr is:open review-requested:@me".to_string(),
+                "is:pr is:open assignee:@me".to_string(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IpcSettings {
+    /// `unix` or `tcp`
+    pub transport: String,
+    pub socket_path: PathBuf,
+    /// Loopback listen address for `tcp`; port 0 lets the OS pick a free port
+    pub tcp_addr: String,
+}
+
+impl Default for IpcSettings {
+    fn default() -> Self {
+        Self {
+            transport: if cfg!(unix) { "unix" } else { "tcp" }.to_string(),
+            socket_path: PathBuf::from("/tmp/ghview.sock"),
+            tcp_addr: "127.0.0.1:0".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthSettings {
+    /// `plaintext`, `keychain` or `encrypted`
+    pub credential_store: String,
+}
+
+impl Default for AuthSettings {
+    fn default() -> Self {
+        Self {
+            credential_store: "plaintext".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingSettings {
+    /// Level plus optional per-module levels, e.g. `info,ghview_core::github=debug`
+    pub filter: String,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            filter: "info".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ViewSettings {
+    pub group_by: GroupBy,
+    /// Applied in order, e.g. `["checks", "created"]`; prefix `-` for descending
+    pub sort: Vec<SortKey>,
+}
+
+impl Default for ViewSettings {
+    fn default() -> Self {
+        Self {
+            group_by: GroupBy::None,
+            sort: vec![SortKey {
+                field: SortField::Updated,
+                descending: true,
+            }],
+        }
+    }
+}
+
+/// Review turnaround targets, measured from when the review was requested
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SlaSettings {
+    /// Fetch PR timelines to find review request times
+    pub enabled: bool,
+    pub warning_hours: u32,
+    pub breach_hours: u32,
+}
+
+impl Default for SlaSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            warning_hours: 18,
+            breach_hours: 24,
+        }
+    }
+}
+
+/// What the MCP server may change on GitHub; it only reads unless tools are enabled here
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct McpSettings {
+    /// Write tools clients may call: `submit_review`, `add_comment`, `rerun_failed_checks`
+    pub enabled_tools: Vec<String>,
+    /// Only return what write tools would send, whatever the client asks for
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpSettings {
+    /// `live`, `record` or `replay`
+    pub mode: String,
+    /// Fixture directory for record and replay; defaults to `fixtures` in the config dir
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixtures_dir: Option<PathBuf>,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            mode: "live".to_string(),
+            fixtures_dir: None,
+        }
+    }
+}
+
+fn validate_base_url(key: &'static str, value: &str) -> Result<(), ConfigError> {
+    match url::Url::parse(value) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+        _ => Err(invalid(
+            key,
+            format!("expected an http(s) URL, got {:?}", value),
+        )),
+    }
+}
+
+impl Settings {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        validate_base_url("github.api_url", &self.github.api_url)?;
+        validate_base_url("github.oauth_url", &self.github.oauth_url)?;
+
+        let interval = self.pull_requests.refresh_interval_secs;
+        if interval != 0 && interval < MIN_REFRESH_INTERVAL_SECS {
+            return Err(invalid(
+                "pull_requests.refresh_interval_secs",
+                format!(
+                    "must be 0 (off) or at least {} seconds, got {}",
+                    MIN_REFRESH_INTERVAL_SECS, interval
+                ),
+            ));
+        }
+        if self.pull_requests.queries.is_empty() {
+            return Err(invalid(
+                "pull_requests.queries",
+                "at least one search query is required",
+            ));
+        }
+        if self
+            .pull_requests
+            .queries
+            .iter()
+            .any(|q| q.trim().is_empty())
+        {
+            return Err(invalid(
+                "pull_requests.queries",
+                "queries must not be empty",
+            ));
+        }
+
+        match self.ipc.transport.as_str() {
+            "tcp" => {}
+            "unix" if cfg!(unix) => {}
+            "unix" => {
+                return Err(invalid(
+                    "ipc.transport",
+                    "unix sockets are not available on this platform, use \"tcp\"",
+                ))
+            }
+            other => {
+                return Err(invalid(
+                    "ipc.transport",
+                    format!("expected \"unix\" or \"tcp\", got {:?}", other),
+                ))
+            }
+        }
+        let addr: SocketAddr = self.ipc.tcp_addr.parse().map_err(|_| {
+            invalid(
+                "ipc.tcp_addr",
+                format!("expected IP:PORT, got {:?}", self.ipc.tcp_addr),
+            )
+        })?;
+        if !addr.ip().is_loopback() {
+            return Err(invalid(
+                "ipc.tcp_addr",
+                format!("must be a loopback address, got {}", addr),
+            ));
+        }
+
+        CredentialBackend::parse(&self.auth.credential_store)
+            .map_err(|e| invalid("auth.credential_store", e.to_string()))?;
+        logging::validate_filter(&self.logging.filter)
+            .map_err(|message| invalid("logging.filter", message))?;
+
+        if !matches!(self.http.mode.as_str(), "live" | "record" | "replay") {
+            return Err(invalid(
+                "http.mode",
+                format!(
+                    "expected \"live\", \"record\" or \"replay\", got {:?}",
+                    self.http.mode
+                ),
+            ));
+        }
+
+        if self.sla.breach_hours == 0 {
+            return Err(invalid("sla.breach_hours", "must be at least 1"));
+        }
+        if self.sla.warning_hours >= self.sla.breach_hours {
+            return Err(invalid(
+                "sla.warning_hours",
+                format!(
+                    "must be less than sla.breach_hours ({}), got {}",
+                    self.sla.breach_hours, self.sla.warning_hours
+                ),
+            ));
+        }
+
+        if let Some(tool) = self
+            .mcp
+            .enabled_tools
+            .iter()
+            .find(|tool| !ACTION_NAMES.contains(&tool.as_str()))
+        {
+            return Err(invalid(
+                "mcp.enabled_tools",
+                format!(
+                    "unknown tool {:?}, expected one of {}",
+                    tool,
+                    ACTION_NAMES.join(", ")
+                ),
+            ));
+        }
+
+        for (i, rule) in self.filters.iter().enumerate() {
+            rule.validate()
+                .map_err(|message| invalid("filters", format!("rule {}: {}", i + 1, message)))?;
+        }
+        Ok(())
+    }
+}
+
+/// ghview's directory under `$XDG_CONFIG_HOME`, falling back to `~/.config/ghview`
+/// on Unix and the platform config directory (e.g. `%APPDATA%\ghview`) elsewhere
+pub fn config_dir() -> Result<PathBuf, ConfigError> {
+    if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        return Ok(PathBuf::from(xdg).join("ghview"));
+    }
+    // macOS included, where ghview has always used ~/.config
+    #[cfg(unix)]
+    if let Some(home) = std::env::var_os("HOME").filter(|v| !v.is_empty()) {
+        return Ok(PathBuf::from(home).join(".config").join("ghview"));
+    }
+    dirs::config_dir()
+        .map(|dir| dir.join("ghview"))
+        .ok_or(ConfigError::ConfigDirNotFound)
+}
+
+pub fn config_path() -> Result<PathBuf, ConfigError> {
+    Ok(config_dir()?.join(CONFIG_FILE_NAME))
+}
+
+/// Files written before `version` existed kept every setting at the top level
+fn migrate_v0(table: &mut Table) {
+    const MOVED: [(&str, &str, &str); 5] = [
+        ("refresh_interval", "pull_requests", "refresh_interval_secs"),
+        ("queries", "pull_requests", "queries"),
+        ("api_url", "github", "api_url"),
+        ("socket_path", "ipc", "socket_path"),
+        ("log_level", "logging", "filter"),
+    ];
+    for (old_key, section, new_key) in MOVED {
+        if let Some(value) = table.remove(old_key) {
+            let section = table
+                .entry(section)
+                .or_insert_with(|| Value::Table(Table::new()));
+            if let Value::Table(section) = section {
+                section.entry(new_key).or_insert(value);
+            }
+        }
+    }
+}
+
+/// `MIGRATIONS[n]` upgrades a version `n` table to version `n + 1`
+const MIGRATIONS: [fn(&mut Table); CONFIG_VERSION as usize] = [migrate_v0];
+
+/// Parse and validate a config file, migrating it to `CONFIG_VERSION` first.
+/// Returns the version the file was migrated from, if it was.
+fn parse_settings(content: &str, path: &Path) -> Result<(Settings, Option<i64>), ConfigError> {
+    let parse_error = |message: String| ConfigError::Parse {
+        path: path.display().to_string(),
+        message,
+    };
+    let mut table: Table = toml::from_str(content).map_err(|e| parse_error(e.to_string()))?;
+
+    let version = match table.get("version") {
+        None => 0,
+        Some(Value::Integer(version)) if *version >= 0 => *version,
+        Some(other) => {
+            return Err(invalid(
+                "version",
+                format!("expected a positive integer, got {}", other),
+            ))
+        }
+    };
+    if version > CONFIG_VERSION as i64 {
+        return Err(ConfigError::UnsupportedVersion {
+            found: version,
+            supported: CONFIG_VERSION,
+        });
+    }
+    for migrate in &MIGRATIONS[version as usize..] {
+        migrate(&mut table);
+    }
+    table.insert("version".to_string(), Value::Integer(CONFIG_VERSION as i64));
+
+    let settings: Settings = table.try_into().map_err(|e| parse_error(e.to_string()))?;
+    settings.validate()?;
+    let migrated_from = (version < CONFIG_VERSION as i64).then_some(version);
+    Ok((settings, migrated_from))
+}
+
+/// Where the original of a file migrated from `version` is kept, e.g. `config.toml.v0.bak`
+fn backup_path(path: &Path, version: i64) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{}.bak", version));
+    path.with_file_name(name)
+}
+
+/// Load settings from `path`, using the defaults if it doesn't exist
+///
+/// A migrated file is rewritten in the current format, keeping the original
+/// next to it as `config.toml.v<N>.bak`.
+pub fn load_from(path: &Path) -> Result<Settings, ConfigError> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Settings::default()),
+        Err(e) => return Err(e.into()),
+    };
+    let (settings, migrated_from) = parse_settings(&content, path)?;
+    if let Some(version) = migrated_from {
+        let backup = backup_path(path, version);
+        std::fs::write(&backup, &content)?;
+        save_to(path, &settings)?;
+        tracing::info!(backup = %backup.display(), "Migrated config to version {}", CONFIG_VERSION);
+    }
+    Ok(settings)
+}
+
+/// Load settings from `path` like `load_from`, but migrate only in memory and
+/// never write, for readers that poll a file the app owns
+pub fn read_from(path: &Path) -> Result<Settings, ConfigError> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => parse_settings(&content, path).map(|(settings, _)| settings),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Settings::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn save_to(path: &Path, settings: &Settings) -> Result<(), ConfigError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = format!(
+        "# ghview settings; changes are picked up while ghview is running\n{}",
+        toml::to_string_pretty(settings)?
+    );
+    // Write then rename so the watcher never reads a half-written file
+    let temp = path.with_extension("toml.tmp");
+    std::fs::write(&temp, content)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+static CURRENT: LazyLock<RwLock<Arc<Settings>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(Settings::default())));
+
+/// Settings in effect; the defaults until `init` has loaded the config file
+pub fn current() -> Arc<Settings> {
+    CURRENT.read().unwrap().clone()
+}
+
+/// Replace the settings in effect, e.g. after the config file changed
+pub fn set_current(settings: Settings) {
+    *CURRENT.write().unwrap() = Arc::new(settings);
+}
+
+/// Load the config file into `current()`. On error the defaults stay in effect.
+pub fn init() -> Result<(), ConfigError> {
+    let settings = load_from(&config_path()?)?;
+    set_current(settings);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+
+    fn temp_path() -> PathBuf {
+        env::temp_dir()
+            .join(format!("ghview_config_{}", rand::random::<u64>()))
+            .join(CONFIG_FILE_NAME)
+    }
+
+    fn parse(content: &str) -> Result<Settings, ConfigError> {
+        parse_settings(content, Path::new("config.toml")).map(|(settings, _)| settings)
+    }
+
+    #[test]
+    fn test_missing_file_uses_defaults() {
+        let settings = load_from(&temp_path()).unwrap();
+        assert_eq!(settings, Settings::default());
+        settings.validate().unwrap();
+    }
+
+    #[test]
+    fn test_partial_file_fills_in_defaults() {
+        let settings = parse(
+            r#"
+version = 1
+
+[pull_requests]
+refresh_interval_secs = 60
+"#,
+        )
+        .unwrap();
+        assert_eq!(settings.pull_requests.refresh_interval_secs, 60);
+        assert_eq!(settings.pull_requests.queries.len(), 2);
+        assert_eq!(settings.github, GitHubSettings::default());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let path = temp_path();
+        let mut settings = Settings::default();
+        settings.github.api_url = "https://ghe.example.com/api/v3".to_string();
+        settings.pull_requests.queries = vec!["is:pr is:open author:@me".to_string()];
+        settings.filters = vec![FilterRule {
+            name: Some("Bots".to_string()),
+            author: Some("*[bot]".to_string()),
+            ..Default::default()
+        }];
+        save_to(&path, &settings).unwrap();
+
+        assert_eq!(load_from(&path).unwrap(), settings);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_validation_errors_name_the_setting() {
+        let error = |content: &str| parse(content).unwrap_err().to_string();
+
+        assert!(error("[pull_requests]\nrefresh_interval_secs = 5")
+            .contains("`pull_requests.refresh_interval_secs`: must be 0 (off) or at least 30"));
+        assert!(error("[github]\napi_url = \"api.github.com\"").contains("`github.api_url`"));
+        assert!(error("[pull_requests]\nqueries = []").contains("`pull_requests.queries`"));
+        assert!(error("[ipc]\ntcp_addr = \"0.0.0.0:80\"").contains("loopback"));
+        assert!(error("[auth]\ncredential_store = \"vault\"").contains("`auth.credential_store`"));
+        assert!(error("[http]\nmode = \"offline\"").contains("`http.mode`"));
+        assert!(error("[sla]\nwarning_hours = 30").contains("`sla.warning_hours`"));
+        assert!(error("[[filters]]\naction = \"exclude\"").contains("`filters`: rule 1"));
+        assert!(error("[mcp]\nenabled_tools = [\"merge\"]").contains("`mcp.enabled_tools`"));
+    }
+
+    #[test]
+    fn test_parse_errors_are_reported_with_location() {
+        let message = parse("[github]\napi_urll = \"x\"").unwrap_err().to_string();
+        assert!(message.starts_with("Invalid config file config.toml:"));
+        assert!(message.contains("unknown field `api_urll`"));
+        let message = parse("[view]\nsort = [\"-stars\"]")
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("unknown sort key \"stars\""));
+
+        assert!(matches!(
+            parse("version = 99"),
+            Err(ConfigError::UnsupportedVersion { found: 99, .. })
+        ));
+    }
+
+    #[test]
+    fn test_migrates_unversioned_file() {
+        let path = temp_path();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            "refresh_interval = 120\nlog_level = \"debug\"\nqueries = [\"is:pr author:@me\"]\n",
+        )
+        .unwrap();
+
+        let settings = load_from(&path).unwrap();
+        assert_eq!(settings.version, CONFIG_VERSION);
+        assert_eq!(settings.pull_requests.refresh_interval_secs, 120);
+        assert_eq!(settings.pull_requests.queries, vec!["is:pr author:@me"]);
+        assert_eq!(settings.logging.filter, "debug");
+
+        // Rewritten in the current format, original kept as a backup
+        let (_, migrated_from) =
+            parse_settings(&std::fs::read_to_string(&path).unwrap(), &path).unwrap();
+        assert_eq!(migrated_from, None);
+        assert!(backup_path(&path, 0).exists());
+        assert!(backup_path(&path, 0).ends_with("config.toml.v0.bak"));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_read_from_leaves_old_files_alone() {
+        let path = temp_path();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let content = "refresh_interval = 120\n";
+        std::fs::write(&path, content).unwrap();
+
+        let settings = read_from(&path).unwrap();
+        assert_eq!(settings.version, CONFIG_VERSION);
+        assert_eq!(settings.pull_requests.refresh_interval_secs, 120);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+        assert!(!backup_path(&path, 0).exists());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_backup_path_names_the_loaded_version() {
+        let path = Path::new("/home/octocat/.config/ghview/config.toml");
+        assert_eq!(
+            backup_path(path, 1),
+            PathBuf::from("/home/octocat/.config/ghview/config.toml.v1.bak")
+        );
+        assert_eq!(
+            backup_path(path, 12),
+            PathBuf::from("/home/octocat/.config/ghview/config.toml.v12.bak")
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_config_dir_respects_xdg() {
+        let original = (env::var("XDG_CONFIG_HOME").ok(), env::var("HOME").ok());
+        env::set_var("HOME", "/home/octocat");
+        env::remove_var("XDG_CONFIG_HOME");
+        #[cfg(unix)]
+        assert_eq!(
+            config_path().unwrap(),
+            PathBuf::from("/home/octocat/.config/ghview/config.toml")
+        );
+
+        // Without HOME, as is usual on Windows, the platform directory is used
+        env::remove_var("HOME");
+        let dir = config_dir().unwrap();
+        assert!(dir.ends_with("ghview"));
+        assert!(dir.is_absolute());
+
+        env::set_var("XDG_CONFIG_HOME", "/xdg");
+        assert_eq!(
+            config_path().unwrap(),
+            PathBuf::from("/xdg/ghview/config.toml")
+        );
+
+        for (name, value) in [("XDG_CONFIG_HOME", original.0), ("HOME", original.1)] {
+            match value {
+                Some(value) => env::set_var(name, value),
+                None => env::remove_var(name),
+            }
+        }
+    }
+}
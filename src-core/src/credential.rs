@@ -0,0 +1,277 @@
+use crate::config::{self, ConfigError};
+use crate::credential_store::{
+    credential_store, credential_store_for_delete, load_with_migration, CredentialStore,
+    PlaintextFileStore,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CredentialError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Credential not found")]
+    NotFound,
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("Unknown credential store: {0} (expected plaintext, keychain or encrypted)")]
+    UnknownBackend(String),
+    #[error("Keychain error: {0}")]
+    Keychain(String),
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+    #[error("GHVIEW_CREDENTIAL_PASSPHRASE must be set to use the encrypted credential store")]
+    PassphraseMissing,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Credentials {
+    pub access_token: String,
+    /// Only issued for GitHub App user tokens with expiration enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// When `access_token` stops working; `None` for tokens that don't expire
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Get the path to the credentials file in the config directory
+pub fn get_credentials_path() -> Result<PathBuf, CredentialError> {
+    Ok(config::config_dir()?.join("credentials.json"))
+}
+
+/// Save credentials to the configured store
+pub fn save_credentials(credentials: &Credentials) -> Result<(), CredentialError> {
+    credential_store()?.save(credentials)
+}
+
+/// Load credentials from the configured store, migrating a plaintext token into it if needed
+pub fn load_credentials() -> Result<Credentials, CredentialError> {
+    let store = credential_store()?;
+    load_with_migration(store.as_ref(), &PlaintextFileStore::default_location()?)
+}
+
+/// Delete credentials from the configured store and any leftover plaintext file
+pub fn delete_credentials() -> Result<(), CredentialError> {
+    // Clear every location before reporting a failure, so one error can't leave a token behind
+    let results = [
+        credential_store_for_delete().and_then(|store| store.delete()),
+        PlaintextFileStore::default_location().and_then(|store| store.delete()),
+    ];
+    results.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credential_store::{PASSPHRASE_ENV, STORE_ENV};
+    use serial_test::serial;
+    use std::env;
+    use std::fs;
+
+    struct TestEnv {
+        original_home: Option<String>,
+        original_xdg: Option<String>,
+        temp_dir: PathBuf,
+    }
+
+    impl TestEnv {
+        fn new() -> Self {
+            let temp_dir = env::temp_dir().join(format!("ghview_test_{}", rand::random::<u64>()));
+            fs::create_dir_all(&temp_dir).unwrap();
+            let original_home = env::var("HOME").ok();
+            let original_xdg = env::var("XDG_CONFIG_HOME").ok();
+            env::set_var("HOME", &temp_dir);
+            env::remove_var("XDG_CONFIG_HOME");
+            env::remove_var(STORE_ENV);
+            env::remove_var(PASSPHRASE_ENV);
+            TestEnv {
+                original_home,
+                original_xdg,
+                temp_dir,
+            }
+        }
+    }
+
+    impl Drop for TestEnv {
+        fn drop(&mut self) {
+            if let Some(ref home) = self.original_home {
+                env::set_var("HOME", home);
+            } else {
+                env::remove_var("HOME");
+            }
+            match &self.original_xdg {
+                Some(xdg) => env::set_var("XDG_CONFIG_HOME", xdg),
+                None => env::remove_var("XDG_CONFIG_HOME"),
+            }
+            env::remove_var(STORE_ENV);
+            env::remove_var(PASSPHRASE_ENV);
+            let _ = fs::remove_dir_all(&self.temp_dir);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_get_credentials_path() {
+        let _env = TestEnv::new();
+        let path = get_credentials_path().unwrap();
+        assert!(path.ends_with(".config/ghview/credentials.json"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_credentials_follow_xdg_config_home() {
+        let env = TestEnv::new();
+        let xdg = env.temp_dir.join("xdg");
+        env::set_var("XDG_CONFIG_HOME", &xdg);
+        assert_eq!(
+            get_credentials_path().unwrap(),
+            xdg.join("ghview").join("credentials.json")
+        );
+        assert_eq!(
+            crate::logging::log_dir().unwrap(),
+            xdg.join("ghview").join("logs")
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_save_and_load_credentials() {
+        let _env = TestEnv::new();
+        let credentials = Credentials {
+            access_token: "test_token_123".to_string(),
+            ..Default::default()
+        };
+
+        save_credentials(&credentials).unwrap();
+        let loaded = load_credentials().unwrap();
+        assert_eq!(loaded.access_token, "test_token_123");
+    }
+
+    #[test]
+    #[serial]
+    fn test_load_credentials_not_found() {
+        let _env = TestEnv::new();
+        let result = load_credentials();
+        assert!(matches!(result, Err(CredentialError::NotFound)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_delete_credentials() {
+        let _env = TestEnv::new();
+        let credentials = Credentials {
+            access_token: "test_token".to_string(),
+            ..Default::default()
+        };
+        save_credentials(&credentials).unwrap();
+
+        delete_credentials().unwrap();
+
+        let result = load_credentials();
+        assert!(matches!(result, Err(CredentialError::NotFound)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_delete_credentials_not_found() {
+        let _env = TestEnv::new();
+        // Should not error if file doesn't exist
+        let result = delete_credentials();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    #[serial]
+    fn test_delete_encrypted_credentials_without_passphrase() {
+        let _env = TestEnv::new();
+        env::set_var(STORE_ENV, "encrypted");
+        env::set_var(PASSPHRASE_ENV, "passphrase");
+        save_credentials(&Credentials {
+            access_token: "gho_encrypted".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let encrypted = config::config_dir().unwrap().join("credentials.enc.json");
+        assert!(encrypted.exists());
+        // A plaintext file left over from before the switch goes too
+        PlaintextFileStore::default_location()
+            .unwrap()
+            .save(&Credentials::default())
+            .unwrap();
+
+        env::remove_var(PASSPHRASE_ENV);
+        delete_credentials().unwrap();
+        assert!(!encrypted.exists());
+        assert!(!get_credentials_path().unwrap().exists());
+    }
+
+    #[test]
+    #[serial]
+    fn test_delete_credentials_removes_plaintext_despite_store_error() {
+        let _env = TestEnv::new();
+        save_credentials(&Credentials::default()).unwrap();
+
+        env::set_var(STORE_ENV, "vault");
+        assert!(matches!(
+            delete_credentials(),
+            Err(CredentialError::UnknownBackend(_))
+        ));
+        assert!(!get_credentials_path().unwrap().exists());
+    }
+
+    #[test]
+    #[serial]
+    fn test_credentials_json_format() {
+        let _env = TestEnv::new();
+        let credentials = Credentials {
+            access_token: "gho_test123".to_string(),
+            ..Default::default()
+        };
+        save_credentials(&credentials).unwrap();
+
+        let path = get_credentials_path().unwrap();
+        let content = fs::read_to_string(path).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(json["access_token"], "gho_test123");
+    }
+
+    #[test]
+    #[serial]
+    fn test_load_migrates_plaintext_to_encrypted_store() {
+        let _env = TestEnv::new();
+        save_credentials(&Credentials {
+            access_token: "gho_migrate".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        env::set_var(STORE_ENV, "encrypted");
+        env::set_var(PASSPHRASE_ENV, "passphrase");
+        assert_eq!(load_credentials().unwrap().access_token, "gho_migrate");
+        assert!(!get_credentials_path().unwrap().exists());
+        assert!(config::config_dir()
+            .unwrap()
+            .join("credentials.enc.json")
+            .exists());
+
+        // Subsequent loads come from the encrypted store
+        assert_eq!(load_credentials().unwrap().access_token, "gho_migrate");
+    }
+
+    #[test]
+    #[serial]
+    fn test_encrypted_store_requires_passphrase() {
+        let _env = TestEnv::new();
+        env::set_var(STORE_ENV, "encrypted");
+        assert!(matches!(
+            load_credentials(),
+            Err(CredentialError::PassphraseMissing)
+        ));
+    }
+}
@@ -0,0 +1,472 @@
+use crate::config;
+use crate::credential::{get_credentials_path, CredentialError, Credentials};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable selecting the credential backend
+pub const STORE_ENV: &str = "GHVIEW_CREDENTIAL_STORE";
+
+/// Environment variable holding the passphrase for the encrypted backend
+pub const PASSPHRASE_ENV: &str = "GHVIEW_CREDENTIAL_PASSPHRASE";
+
+/// Keychain service name, shared with the `keychain` provider in fnox.toml
+const KEYCHAIN_SERVICE: &str = "ghview";
+const KEYCHAIN_ACCOUNT: &str = "github";
+
+/// Where the GitHub token is persisted
+pub trait CredentialStore: Send + Sync {
+    /// Short name used in logs and error messages
+    fn name(&self) -> &'static str;
+    fn load(&self) -> Result<Credentials, CredentialError>;
+    fn save(&self, credentials: &Credentials) -> Result<(), CredentialError>;
+    /// Remove stored credentials; succeeds if there were none
+    fn delete(&self) -> Result<(), CredentialError>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CredentialBackend {
+    /// JSON file readable only by the owner (the original behavior)
+    #[default]
+    Plaintext,
+    /// macOS Keychain, Windows Credential Manager or the Secret Service on Linux
+    Keychain,
+    /// JSON file encrypted with a key derived from a passphrase
+    Encrypted,
+}
+
+impl CredentialBackend {
+    pub fn parse(value: &str) -> Result<Self, CredentialError> {
+        match value.trim().to_lowercase().as_str() {
+            "plaintext" | "file" => Ok(Self::Plaintext),
+            "keychain" | "keyring" => Ok(Self::Keychain),
+            "encrypted" => Ok(Self::Encrypted),
+            other => Err(CredentialError::UnknownBackend(other.to_string())),
+        }
+    }
+
+    /// Read the backend from `GHVIEW_CREDENTIAL_STORE`, falling back to
+    /// `auth.credential_store` in config.toml (plaintext by default)
+    pub fn from_env() -> Result<Self, CredentialError> {
+        match std::env::var(STORE_ENV) {
+            Ok(value) if !value.trim().is_empty() => Self::parse(&value),
+            _ => Self::parse(&config::current().auth.credential_store),
+        }
+    }
+
+    pub fn open(self) -> Result<Box<dyn CredentialStore>, CredentialError> {
+        Ok(match self {
+            Self::Plaintext => Box::new(PlaintextFileStore::default_location()?),
+            Self::Keychain => Box::new(KeychainStore::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)),
+            Self::Encrypted => {
+                let passphrase = std::env::var(PASSPHRASE_ENV)
+                    .ok()
+                    .filter(|p| !p.is_empty())
+                    .ok_or(CredentialError::PassphraseMissing)?;
+                Box::new(EncryptedFileStore::new(encrypted_path()?, passphrase))
+            }
+        })
+    }
+}
+
+fn encrypted_path() -> Result<PathBuf, CredentialError> {
+    Ok(config::config_dir()?.join("credentials.enc.json"))
+}
+
+/// The configured credential store
+pub fn credential_store() -> Result<Box<dyn CredentialStore>, CredentialError> {
+    CredentialBackend::from_env()?.open()
+}
+
+/// The configured credential store, opened only for `delete`; removing the
+/// encrypted file doesn't need the passphrase, so logging out works without it
+pub fn credential_store_for_delete() -> Result<Box<dyn CredentialStore>, CredentialError> {
+    match CredentialBackend::from_env()? {
+        CredentialBackend::Encrypted => Ok(Box::new(EncryptedFileStore::new(
+            encrypted_path()?,
+            String::new(),
+        ))),
+        backend => backend.open(),
+    }
+}
+
+/// Load from `store`, moving a token found in the legacy plaintext file into it
+pub fn load_with_migration(
+    store: &dyn CredentialStore,
+    legacy: &PlaintextFileStore,
+) -> Result<Credentials, CredentialError> {
+    match store.load() {
+        Err(CredentialError::NotFound) if store.name() != legacy.name() => {
+            let credentials = legacy.load()?;
+            store.save(&credentials)?;
+            legacy.delete()?;
+            tracing::info!(
+                "Migrated plaintext credentials to the {} store",
+                store.name()
+            );
+            Ok(credentials)
+        }
+        result => result,
+    }
+}
+
+fn write_private_file(path: &Path, content: &str) -> Result<(), CredentialError> {
+    // Create parent directories if they don't exist
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, content)?;
+
+    // Set file permissions to 600 (owner read/write only) on Unix
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let permissions = fs::Permissions::from_mode(0o600);
+        fs::set_permissions(path, permissions)?;
+    }
+
+    Ok(())
+}
+
+fn remove_file_if_exists(path: &Path) -> Result<(), CredentialError> {
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Plaintext JSON file (~/.config/ghview/credentials.json)
+pub struct PlaintextFileStore {
+    path: PathBuf,
+}
+
+impl PlaintextFileStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn default_location() -> Result<Self, CredentialError> {
+        Ok(Self::new(get_credentials_path()?))
+    }
+
+    #[cfg(test)]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl CredentialStore for PlaintextFileStore {
+    fn name(&self) -> &'static str {
+        "plaintext"
+    }
+
+    fn load(&self) -> Result<Credentials, CredentialError> {
+        if !self.path.exists() {
+            return Err(CredentialError::NotFound);
+        }
+        let content = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn save(&self, credentials: &Credentials) -> Result<(), CredentialError> {
+        write_private_file(&self.path, &serde_json::to_string_pretty(credentials)?)
+    }
+
+    fn delete(&self) -> Result<(), CredentialError> {
+        remove_file_if_exists(&self.path)
+    }
+}
+
+/// OS keychain entry holding the credentials as JSON
+pub struct KeychainStore {
+    service: String,
+    account: String,
+}
+
+impl KeychainStore {
+    pub fn new(service: &str, account: &str) -> Self {
+        Self {
+            service: service.to_string(),
+            account: account.to_string(),
+        }
+    }
+
+    fn entry(&self) -> Result<keyring::Entry, CredentialError> {
+        keyring::Entry::new(&self.service, &self.account)
+            .map_err(|e| CredentialError::Keychain(e.to_string()))
+    }
+}
+
+impl CredentialStore for KeychainStore {
+    fn name(&self) -> &'static str {
+        "keychain"
+    }
+
+    fn load(&self) -> Result<Credentials, CredentialError> {
+        match self.entry()?.get_password() {
+            Ok(secret) => Ok(serde_json::from_str(&secret)?),
+            Err(keyring::Error::NoEntry) => Err(CredentialError::NotFound),
+            Err(e) => Err(CredentialError::Keychain(e.to_string())),
+        }
+    }
+
+    fn save(&self, credentials: &Credentials) -> Result<(), CredentialError> {
+        self.entry()?
+            .set_password(&serde_json::to_string(credentials)?)
+            .map_err(|e| CredentialError::Keychain(e.to_string()))
+    }
+
+    fn delete(&self) -> Result<(), CredentialError> {
+        match self.entry()?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(CredentialError::Keychain(e.to_string())),
+        }
+    }
+}
+
+/// On-disk format of the encrypted credentials file
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedFile {
+    version: u32,
+    kdf: String,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+const ENCRYPTED_FILE_VERSION: u32 = 1;
+const KDF_ARGON2ID: &str = "argon2id";
+
+/// Credentials encrypted with XChaCha20-Poly1305 under an Argon2id-derived key
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    passphrase: String,
+}
+
+impl EncryptedFileStore {
+    pub fn new(path: PathBuf, passphrase: String) -> Self {
+        Self { path, passphrase }
+    }
+
+    fn cipher(&self, salt: &[u8]) -> Result<XChaCha20Poly1305, CredentialError> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(self.passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| CredentialError::Encryption(e.to_string()))?;
+        Ok(XChaCha20Poly1305::new(Key::from_slice(&key)))
+    }
+}
+
+impl CredentialStore for EncryptedFileStore {
+    fn name(&self) -> &'static str {
+        "encrypted"
+    }
+
+    fn load(&self) -> Result<Credentials, CredentialError> {
+        if !self.path.exists() {
+            return Err(CredentialError::NotFound);
+        }
+        let file: EncryptedFile = serde_json::from_str(&fs::read_to_string(&self.path)?)?;
+        if file.version != ENCRYPTED_FILE_VERSION || file.kdf != KDF_ARGON2ID {
+            return Err(CredentialError::Encryption(format!(
+                "unsupported encrypted file (version {}, kdf {})",
+                file.version, file.kdf
+            )));
+        }
+
+        let decode = |value: &str| {
+            BASE64
+                .decode(value)
+                .map_err(|e| CredentialError::Encryption(e.to_string()))
+        };
+        let salt = decode(&file.salt)?;
+        let nonce = decode(&file.nonce)?;
+        let ciphertext = decode(&file.ciphertext)?;
+        if nonce.len() != 24 {
+            return Err(CredentialError::Encryption("invalid nonce".to_string()));
+        }
+
+        let plaintext = self
+            .cipher(&salt)?
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| {
+                CredentialError::Encryption(
+                    "wrong passphrase or corrupted credentials file".to_string(),
+                )
+            })?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    fn save(&self, credentials: &Credentials) -> Result<(), CredentialError> {
+        let salt: [u8; 16] = rand::random();
+        let nonce: [u8; 24] = rand::random();
+        let plaintext = serde_json::to_vec(credentials)?;
+        let ciphertext = self
+            .cipher(&salt)?
+            .encrypt(XNonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|e| CredentialError::Encryption(e.to_string()))?;
+
+        let file = EncryptedFile {
+            version: ENCRYPTED_FILE_VERSION,
+            kdf: KDF_ARGON2ID.to_string(),
+            salt: BASE64.encode(salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        write_private_file(&self.path, &serde_json::to_string_pretty(&file)?)
+    }
+
+    fn delete(&self) -> Result<(), CredentialError> {
+        remove_file_if_exists(&self.path)
+    }
+}
+
+/// Process-local store for tests
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryStore {
+    credentials: std::sync::Mutex<Option<Credentials>>,
+}
+
+#[cfg(test)]
+impl CredentialStore for MemoryStore {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn load(&self) -> Result<Credentials, CredentialError> {
+        self.credentials
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or(CredentialError::NotFound)
+    }
+
+    fn save(&self, credentials: &Credentials) -> Result<(), CredentialError> {
+        *self.credentials.lock().unwrap() = Some(credentials.clone());
+        Ok(())
+    }
+
+    fn delete(&self) -> Result<(), CredentialError> {
+        *self.credentials.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let dir = env::temp_dir().join(format!("ghview_store_{}", rand::random::<u64>()));
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn token(value: &str) -> Credentials {
+        Credentials {
+            access_token: value.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_backend_parse() {
+        assert_eq!(
+            CredentialBackend::parse("plaintext").unwrap(),
+            CredentialBackend::Plaintext
+        );
+        assert_eq!(
+            CredentialBackend::parse(" Keychain ").unwrap(),
+            CredentialBackend::Keychain
+        );
+        assert_eq!(
+            CredentialBackend::parse("encrypted").unwrap(),
+            CredentialBackend::Encrypted
+        );
+        assert!(matches!(
+            CredentialBackend::parse("vault"),
+            Err(CredentialError::UnknownBackend(name)) if name == "vault"
+        ));
+    }
+
+    #[test]
+    fn test_memory_store_roundtrip() {
+        let store = MemoryStore::default();
+        assert!(matches!(store.load(), Err(CredentialError::NotFound)));
+        store.save(&token("gho_memory")).unwrap();
+        assert_eq!(store.load().unwrap().access_token, "gho_memory");
+        store.delete().unwrap();
+        assert!(matches!(store.load(), Err(CredentialError::NotFound)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_keychain_store_inside_runtime() {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        let store = KeychainStore::new("ghview-test", "github");
+        assert!(matches!(store.load(), Err(CredentialError::NotFound)));
+        store.save(&token("gho_keychain")).unwrap();
+        store.delete().unwrap();
+    }
+
+    #[test]
+    fn test_encrypted_store_roundtrip() {
+        let dir = TempDir::new();
+        let path = dir.0.join("credentials.enc.json");
+        let store = EncryptedFileStore::new(path.clone(), "correct horse".to_string());
+        store.save(&token("gho_secret")).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("gho_secret"));
+        assert_eq!(store.load().unwrap().access_token, "gho_secret");
+
+        let wrong = EncryptedFileStore::new(path, "battery staple".to_string());
+        assert!(matches!(wrong.load(), Err(CredentialError::Encryption(_))));
+    }
+
+    #[test]
+    fn test_migration_moves_plaintext_token() {
+        let dir = TempDir::new();
+        let legacy = PlaintextFileStore::new(dir.0.join("credentials.json"));
+        legacy.save(&token("gho_legacy")).unwrap();
+        let store = MemoryStore::default();
+
+        let loaded = load_with_migration(&store, &legacy).unwrap();
+        assert_eq!(loaded.access_token, "gho_legacy");
+        assert_eq!(store.load().unwrap().access_token, "gho_legacy");
+        assert!(!legacy.path().exists());
+    }
+
+    #[test]
+    fn test_migration_prefers_existing_store_entry() {
+        let dir = TempDir::new();
+        let legacy = PlaintextFileStore::new(dir.0.join("credentials.json"));
+        legacy.save(&token("gho_old")).unwrap();
+        let store = MemoryStore::default();
+        store.save(&token("gho_new")).unwrap();
+
+        assert_eq!(
+            load_with_migration(&store, &legacy).unwrap().access_token,
+            "gho_new"
+        );
+        // The legacy file is left alone when the store already has a token
+        assert!(legacy.path().exists());
+    }
+}
@@ -1,18 +1,22 @@
 use reqwest::StatusCode;
 
-/// Log an HTTP request to stderr
+/// Log an outgoing HTTP request
 pub fn log_request(method: &str, url: &str) {
-    eprintln!("[DEBUG] {} {}", method, url);
+    tracing::debug!(method, url, "HTTP request");
 }
 
-/// Log an HTTP response to stderr
+/// Log an HTTP response status
 pub fn log_response(url: &str, status: StatusCode) {
-    eprintln!("[DEBUG] {} -> {}", url, status);
+    if status.is_success() {
+        tracing::debug!(url, status = status.as_u16(), "HTTP response");
+    } else {
+        tracing::warn!(url, status = status.as_u16(), "HTTP response");
+    }
 }
 
 /// Log an HTTP response with body preview (for errors)
 pub fn log_response_error(url: &str, status: StatusCode, body: &str) {
-    eprintln!("[DEBUG] {} -> {} | {}", url, status, body);
+    tracing::warn!(url, status = status.as_u16(), body, "HTTP error response");
 }
 
 #[cfg(test)]
@@ -0,0 +1,400 @@
+use serde::Serialize;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+use crate::auth::{AccessTokenResponse, AuthError, GITHUB_CLIENT_ID};
+use crate::config;
+use crate::credential::Credentials;
+use crate::debug::{log_request, log_response};
+use crate::http::HttpClient;
+
+/// Environment variable overriding the OAuth host, e.g. to point at a mock server
+pub const OAUTH_URL_ENV: &str = "GHVIEW_GITHUB_OAUTH_URL";
+
+/// Extra delay GitHub asks for on each `slow_down` response
+const SLOW_DOWN_STEP: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct OAuthEndpoints {
+    pub device_code_url: String,
+    pub access_token_url: String,
+}
+
+impl OAuthEndpoints {
+    pub fn new(base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        Self {
+            device_code_url: format!("{}/login/device/code", base_url),
+            access_token_url: format!("{}/login/oauth/access_token", base_url),
+        }
+    }
+
+    /// `GHVIEW_GITHUB_OAUTH_URL`, falling back to `github.oauth_url` in config.toml
+    pub fn from_env() -> Self {
+        match std::env::var(OAUTH_URL_ENV) {
+            Ok(url) if !url.is_empty() => Self::new(&url),
+            _ => Self::new(&config::current().github.oauth_url),
+        }
+    }
+}
+
+/// How often to poll and for how long, from the device code response
+#[derive(Debug, Clone, Copy)]
+pub struct PollTiming {
+    pub interval: Duration,
+    pub expires_in: Duration,
+    pub slow_down_step: Duration,
+}
+
+impl PollTiming {
+    pub fn from_secs(interval: u32, expires_in: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(interval.into()),
+            expires_in: Duration::from_secs(expires_in.into()),
+            slow_down_step: SLOW_DOWN_STEP,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PollState {
+    Pending,
+    SlowDown,
+}
+
+/// Emitted to the UI after every poll that didn't finish the flow
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceFlowProgress {
+    pub state: PollState,
+    pub interval_secs: u64,
+    pub remaining_secs: u64,
+}
+
+/// How a device flow ended, other than with an error
+#[derive(Debug)]
+pub enum DeviceFlowOutcome {
+    Authorized(Credentials),
+    /// The device code expired before the user approved it
+    Expired,
+    /// The user declined the authorization request
+    Denied,
+    Cancelled,
+}
+
+/// A single response from the access token endpoint
+#[derive(Debug)]
+enum PollResponse {
+    Pending,
+    /// Poll less often; carries the interval GitHub asks for, if any
+    SlowDown(Option<u64>),
+    Expired,
+    Denied,
+    Authorized(Credentials),
+}
+
+fn classify(response: AccessTokenResponse) -> Result<PollResponse, AuthError> {
+    match response.error.as_deref() {
+        Some("authorization_pending") => Ok(PollResponse::Pending),
+        Some("slow_down") => Ok(PollResponse::SlowDown(response.interval)),
+        Some("expired_token") => Ok(PollResponse::Expired),
+        Some("access_denied") => Ok(PollResponse::Denied),
+        Some(error) => Err(AuthError::OAuth(
+            response
+                .error_description
+                .unwrap_or_else(|| error.to_string()),
+        )),
+        None => response
+            .into_credentials(chrono::Utc::now())
+            .map(PollResponse::Authorized)
+            .ok_or_else(|| AuthError::OAuth("Failed to obtain access token".to_string())),
+    }
+}
+
+async fn poll_once(
+    client: &HttpClient,
+    endpoints: &OAuthEndpoints,
+    device_code: &str,
+) -> Result<PollResponse, AuthError> {
+    let url = &endpoints.access_token_url;
+    log_request("POST", url);
+    let response = client
+        .post(url)
+        .header("Accept", "application/json")
+        .form(&[
+            ("client_id", GITHUB_CLIENT_ID),
+            ("device_code", device_code),
+            ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+        ])
+        .send()
+        .await?;
+    log_response(url, response.status());
+
+    classify(response.json()?)
+}
+
+/// Poll the access token endpoint until the user approves, declines, the code expires,
+/// or `cancel` fires (or its sender is dropped)
+pub async fn poll_device_flow(
+    client: &HttpClient,
+    endpoints: &OAuthEndpoints,
+    device_code: &str,
+    timing: PollTiming,
+    mut cancel: oneshot::Receiver<()>,
+    on_progress: impl Fn(DeviceFlowProgress),
+) -> Result<DeviceFlowOutcome, AuthError> {
+    let deadline = Instant::now() + timing.expires_in;
+    let mut interval = timing.interval;
+
+    loop {
+        // Don't start a wait that would end after the code has expired
+        if Instant::now() + interval >= deadline {
+            return Ok(DeviceFlowOutcome::Expired);
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = &mut cancel => return Ok(DeviceFlowOutcome::Cancelled),
+        }
+
+        let state = match poll_once(client, endpoints, device_code).await? {
+            PollResponse::Pending => PollState::Pending,
+            PollResponse::SlowDown(requested) => {
+                interval += timing.slow_down_step;
+                if let Some(secs) = requested {
+                    interval = interval.max(Duration::from_secs(secs));
+                }
+                PollState::SlowDown
+            }
+            PollResponse::Expired => return Ok(DeviceFlowOutcome::Expired),
+            PollResponse::Denied => return Ok(DeviceFlowOutcome::Denied),
+            PollResponse::Authorized(credentials) => {
+                return Ok(DeviceFlowOutcome::Authorized(credentials))
+            }
+        };
+
+        on_progress(DeviceFlowProgress {
+            state,
+            interval_secs: interval.as_secs(),
+            remaining_secs: deadline.saturating_duration_since(Instant::now()).as_secs(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Minimal HTTP server answering each request with the next canned JSON body
+    async fn mock_oauth_server(responses: Vec<&'static str>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+
+        tokio::spawn(async move {
+            for body in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 1024];
+                // Read until the end of the form body
+                loop {
+                    let n = stream.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                    let text = String::from_utf8_lossy(&buf);
+                    if let Some((head, rest)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|l| {
+                                l.to_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if rest.len() >= length || n == 0 {
+                            seen.lock().unwrap().push(text.to_string());
+                            break;
+                        }
+                    }
+                }
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (format!("http://{}", addr), requests)
+    }
+
+    fn fast_timing() -> PollTiming {
+        PollTiming {
+            interval: Duration::from_millis(10),
+            expires_in: Duration::from_secs(5),
+            slow_down_step: Duration::from_millis(20),
+        }
+    }
+
+    async fn run(
+        base_url: &str,
+        timing: PollTiming,
+    ) -> (
+        Result<DeviceFlowOutcome, AuthError>,
+        Vec<DeviceFlowProgress>,
+    ) {
+        let (_cancel_tx, cancel_rx) = oneshot::channel();
+        let progress = Mutex::new(Vec::new());
+        let outcome = poll_device_flow(
+            &HttpClient::default(),
+            &OAuthEndpoints::new(base_url),
+            "device123",
+            timing,
+            cancel_rx,
+            |p| progress.lock().unwrap().push(p),
+        )
+        .await;
+        (outcome, progress.into_inner().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_device_flow_authorizes_after_pending_and_slow_down() {
+        let (base_url, requests) = mock_oauth_server(vec![
+            r#"{"error": "authorization_pending"}"#,
+            r#"{"error": "slow_down"}"#,
+            r#"{"access_token": "gho_abc", "token_type": "bearer", "scope": "repo"}"#,
+        ])
+        .await;
+
+        let (outcome, progress) = run(&base_url, fast_timing()).await;
+        match outcome.unwrap() {
+            DeviceFlowOutcome::Authorized(credentials) => {
+                assert_eq!(credentials.access_token, "gho_abc")
+            }
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+        let states: Vec<PollState> = progress.iter().map(|p| p.state).collect();
+        assert_eq!(states, vec![PollState::Pending, PollState::SlowDown]);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("POST /login/oauth/access_token"));
+        assert!(requests[0].contains("device_code=device123"));
+    }
+
+    #[tokio::test]
+    async fn test_device_flow_slow_down_increases_interval() {
+        let (base_url, _) = mock_oauth_server(vec![
+            r#"{"error": "slow_down", "interval": 10}"#,
+            r#"{"error": "access_denied"}"#,
+        ])
+        .await;
+
+        let timing = PollTiming {
+            expires_in: Duration::from_secs(60),
+            ..fast_timing()
+        };
+        // The server-requested 10s interval is honoured, so cancel instead of waiting it out
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let seen = progress.clone();
+        let handle = tokio::spawn(async move {
+            poll_device_flow(
+                &HttpClient::default(),
+                &OAuthEndpoints::new(&base_url),
+                "device123",
+                timing,
+                cancel_rx,
+                move |p| seen.lock().unwrap().push(p),
+            )
+            .await
+        });
+
+        while progress.lock().unwrap().is_empty() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(progress.lock().unwrap()[0].interval_secs, 10);
+        cancel_tx.send(()).unwrap();
+        assert!(matches!(
+            handle.await.unwrap().unwrap(),
+            DeviceFlowOutcome::Cancelled
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_device_flow_typed_outcomes() {
+        let (base_url, _) = mock_oauth_server(vec![r#"{"error": "access_denied"}"#]).await;
+        assert!(matches!(
+            run(&base_url, fast_timing()).await.0.unwrap(),
+            DeviceFlowOutcome::Denied
+        ));
+
+        let (base_url, _) = mock_oauth_server(vec![r#"{"error": "expired_token"}"#]).await;
+        assert!(matches!(
+            run(&base_url, fast_timing()).await.0.unwrap(),
+            DeviceFlowOutcome::Expired
+        ));
+
+        let (base_url, _) = mock_oauth_server(vec![
+            r#"{"error": "incorrect_client_credentials", "error_description": "Bad client"}"#,
+        ])
+        .await;
+        match run(&base_url, fast_timing()).await.0 {
+            Err(AuthError::OAuth(message)) => assert_eq!(message, "Bad client"),
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_device_flow_stops_at_expiry() {
+        let (base_url, requests) = mock_oauth_server(vec![
+            r#"{"error": "authorization_pending"}"#,
+            r#"{"error": "authorization_pending"}"#,
+            r#"{"error": "authorization_pending"}"#,
+        ])
+        .await;
+        let timing = PollTiming {
+            interval: Duration::from_millis(40),
+            expires_in: Duration::from_millis(100),
+            slow_down_step: Duration::from_millis(40),
+        };
+
+        let (outcome, _) = run(&base_url, timing).await;
+        assert!(matches!(outcome.unwrap(), DeviceFlowOutcome::Expired));
+        assert!(requests.lock().unwrap().len() <= 2);
+    }
+
+    #[tokio::test]
+    async fn test_device_flow_cancelled_when_sender_dropped() {
+        let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
+        drop(cancel_tx);
+        let outcome = poll_device_flow(
+            &HttpClient::default(),
+            &OAuthEndpoints::new("http://127.0.0.1:1"),
+            "device123",
+            fast_timing(),
+            cancel_rx,
+            |_| {},
+        )
+        .await;
+        assert!(matches!(outcome.unwrap(), DeviceFlowOutcome::Cancelled));
+    }
+
+    #[test]
+    fn test_oauth_endpoints() {
+        let endpoints = OAuthEndpoints::new("http://localhost:8080/");
+        assert_eq!(
+            endpoints.device_code_url,
+            "http://localhost:8080/login/device/code"
+        );
+        assert_eq!(
+            endpoints.access_token_url,
+            "http://localhost:8080/login/oauth/access_token"
+        );
+    }
+}
@@ -0,0 +1,338 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::github::GitHubError;
+use crate::models::{CheckState, PullRequestWithChecks, SlaStatus};
+use crate::view::PullRequestView;
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error(transparent)]
+    GitHub(#[from] GitHubError),
+    #[error("Failed to render JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to copy to clipboard: {0}")]
+    Clipboard(String),
+}
+
+impl Serialize for ExportError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Csv,
+    Json,
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn csv_row<S: AsRef<str>>(fields: &[S]) -> String {
+    let fields: Vec<String> = fields.iter().map(|f| csv_field(f.as_ref())).collect();
+    fields.join(",") + "\n"
+}
+
+pub fn check_emoji(pr: &PullRequestWithChecks) -> &'static str {
+    match pr.check_status.as_ref().map(|c| &c.state) {
+        Some(CheckState::Success) => "✅",
+        Some(CheckState::Failure) => "❌",
+        Some(CheckState::Error) => "⚠️",
+        Some(CheckState::Pending) => "⏳",
+        None => "⚪",
+    }
+}
+
+pub fn check_label(pr: &PullRequestWithChecks) -> &'static str {
+    match pr.check_status.as_ref().map(|c| &c.state) {
+        Some(CheckState::Success) => "success",
+        Some(CheckState::Failure) => "failure",
+        Some(CheckState::Error) => "error",
+        Some(CheckState::Pending) => "pending",
+        None => "",
+    }
+}
+
+fn sla_label(status: SlaStatus) -> &'static str {
+    match status {
+        SlaStatus::Ok => "ok",
+        SlaStatus::Warning => "warning",
+        SlaStatus::Breached => "breached",
+    }
+}
+
+/// `1d 4h`, `3h 20m` or `45m`
+pub fn format_wait(secs: i64) -> String {
+    let (days, hours, minutes) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn markdown_item(pr: &PullRequestWithChecks) -> String {
+    let p = &pr.pull_request;
+    let mut line = format!(
+        "- {} [{}#{}]({}) {}",
+        check_emoji(pr),
+        p.repository.full_name,
+        p.number,
+        p.html_url,
+        escape_markdown(&p.title)
+    );
+    if p.draft {
+        line.push_str(" _(draft)_");
+    }
+    line.push_str(&format!(" by @{}", p.user.login));
+    if let Some(sla) = &pr.review_sla {
+        line.push_str(&format!(", waiting {}", format_wait(sla.waiting_secs)));
+        if sla.status == SlaStatus::Breached {
+            line.push_str(" 🔥");
+        }
+    }
+    line
+}
+
+/// Bulleted list with links and check emojis, one section per group
+pub fn render_markdown(view: &PullRequestView) -> String {
+    let mut out = format!("## Review queue ({})\n", view.total);
+    if view.total == 0 {
+        out.push_str("\nNothing waiting for review.\n");
+        return out;
+    }
+    let grouped = view.groups.len() > 1 || view.groups.iter().any(|g| g.key != "all");
+    for group in &view.groups {
+        out.push('\n');
+        if grouped {
+            out.push_str(&format!("### {} ({})\n\n", group.label, group.count));
+        }
+        for pr in &group.pull_requests {
+            out.push_str(&markdown_item(pr));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+pub fn render_csv(prs: &[PullRequestWithChecks]) -> String {
+    let mut out = csv_row(&[
+        "repository",
+        "number",
+        "title",
+        "author",
+        "draft",
+        "checks",
+        "created_at",
+        "updated_at",
+        "review_requested_at",
+        "waiting_secs",
+        "sla",
+        "url",
+    ]);
+    for pr in prs {
+        let p = &pr.pull_request;
+        let sla = pr.review_sla.as_ref();
+        out.push_str(&csv_row(&[
+            p.repository.full_name.clone(),
+            p.number.to_string(),
+            p.title.clone(),
+            p.user.login.clone(),
+            p.draft.to_string(),
+            check_label(pr).to_string(),
+            p.created_at.to_rfc3339(),
+            p.updated_at.to_rfc3339(),
+            sla.map(|s| s.requested_at.to_rfc3339()).unwrap_or_default(),
+            sla.map(|s| s.waiting_secs.to_string()).unwrap_or_default(),
+            sla.map(|s| sla_label(s.status).to_string())
+                .unwrap_or_default(),
+            p.html_url.clone(),
+        ]));
+    }
+    out
+}
+
+/// The same JSON the window and IPC receive
+pub fn render_json(prs: &[PullRequestWithChecks]) -> Result<String, ExportError> {
+    Ok(serde_json::to_string_pretty(prs)?)
+}
+
+pub fn render(format: ExportFormat, view: &PullRequestView) -> Result<String, ExportError> {
+    let prs: Vec<PullRequestWithChecks> = view
+        .groups
+        .iter()
+        .flat_map(|g| g.pull_requests.iter().cloned())
+        .collect();
+    match format {
+        ExportFormat::Markdown => Ok(render_markdown(view)),
+        ExportFormat::Csv => Ok(render_csv(&prs)),
+        ExportFormat::Json => render_json(&prs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        CheckStatus, Label, PullRequest, PullRequestState, Repository, ReviewSla, User,
+    };
+    use crate::view::{build_view, GroupBy, SortKey};
+    use chrono::{DateTime, Utc};
+
+    fn time(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn user(login: &str) -> User {
+        User {
+            id: 1,
+            login: login.to_string(),
+            avatar_url: String::new(),
+            html_url: format!("https://github.com/{}", login),
+        }
+    }
+
+    fn pr(
+        repo: &str,
+        number: i32,
+        title: &str,
+        checks: Option<CheckState>,
+    ) -> PullRequestWithChecks {
+        PullRequestWithChecks {
+            pull_request: PullRequest {
+                id: number.into(),
+                number,
+                title: title.to_string(),
+                html_url: format!("https://github.com/{}/pull/{}", repo, number),
+                state: PullRequestState::Open,
+                draft: false,
+                created_at: time("2026-10-15T09:00:00Z"),
+                updated_at: time("2026-10-17T12:00:00Z"),
+                merged_at: None,
+                user: user("hubot"),
+                labels: vec![Label {
+                    id: 1,
+                    name: "enhancement".to_string(),
+                    color: "a2eeef".to_string(),
+                    description: None,
+                }],
+                requested_reviewers: vec![user("octocat")],
+                repository: Repository {
+                    id: 1,
+                    name: repo.split('/').nth(1).unwrap().to_string(),
+                    full_name: repo.to_string(),
+                    html_url: format!("https://github.com/{}", repo),
+                    owner: user(repo.split('/').next().unwrap()),
+                },
+            },
+            check_status: checks.map(|state| CheckStatus {
+                state,
+                total_count: 2,
+                statuses: vec![],
+            }),
+            review_sla: None,
+        }
+    }
+
+    fn sample() -> Vec<PullRequestWithChecks> {
+        let mut breached = pr(
+            "octo-org/hello-world",
+            7,
+            "Add greeting endpoint",
+            Some(CheckState::Failure),
+        );
+        breached.review_sla = Some(ReviewSla {
+            requested_at: time("2026-10-16T08:00:00Z"),
+            waiting_secs: 93_600,
+            status: SlaStatus::Breached,
+        });
+        let mut draft = pr(
+            "octo-org/hello-world",
+            9,
+            "WIP: dark_mode [part 1], \"themes\"",
+            Some(CheckState::Pending),
+        );
+        draft.pull_request.draft = true;
+        vec![
+            breached,
+            pr(
+                "octo-org/spoon-knife",
+                42,
+                "Fix fork counter",
+                Some(CheckState::Success),
+            ),
+            draft,
+            pr("octo-org/docs", 3, "Typo", None),
+        ]
+    }
+
+    fn view(group_by: GroupBy) -> PullRequestView {
+        let sort: Vec<SortKey> = vec!["checks".parse().unwrap()];
+        build_view(sample(), group_by, &sort, time("2026-10-18T00:00:00Z"))
+    }
+
+    #[test]
+    fn test_markdown_snapshot() {
+        insta::assert_snapshot!(render_markdown(&view(GroupBy::None)));
+    }
+
+    #[test]
+    fn test_markdown_grouped_snapshot() {
+        insta::assert_snapshot!(render_markdown(&view(GroupBy::Repository)));
+    }
+
+    #[test]
+    fn test_markdown_empty() {
+        let empty = build_view(Vec::new(), GroupBy::None, &[], time("2026-10-18T00:00:00Z"));
+        assert_eq!(
+            render_markdown(&empty),
+            "## Review queue (0)\n\nNothing waiting for review.\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_snapshot() {
+        insta::assert_snapshot!(render(ExportFormat::Csv, &view(GroupBy::None)).unwrap());
+    }
+
+    #[test]
+    fn test_json_snapshot() {
+        insta::assert_snapshot!(render(ExportFormat::Json, &view(GroupBy::None)).unwrap());
+    }
+
+    #[test]
+    fn test_format_wait() {
+        assert_eq!(format_wait(45 * 60), "45m");
+        assert_eq!(format_wait(3 * 3600 + 20 * 60), "3h 20m");
+        assert_eq!(format_wait(93_600), "1d 2h");
+    }
+}
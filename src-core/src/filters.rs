@@ -0,0 +1,453 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::github::{fetch_unfiltered_prs, GitHubError};
+use crate::models::PullRequestWithChecks;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    /// Only PRs matching at least one include rule are shown
+    Include,
+    /// PRs matching the rule are hidden
+    #[default]
+    Exclude,
+}
+
+/// A `[[filters]]` entry in config.toml; a PR matches when every criterion that is set matches
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FilterRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub action: FilterAction,
+    /// Glob over `owner/name`, e.g. `octo-org/*-generated`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    /// Repository owner (organization or user)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org: Option<String>,
+    /// Glob over the author's login; `*[bot]` matches every GitHub App
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Glob over label names; matches if any label does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draft: Option<bool>,
+    /// Not updated for at least this many days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub older_than_days: Option<u32>,
+}
+
+/// Case-insensitive glob supporting `*` and `?`; brackets are literal so `[bot]` works
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it is currently absorbing
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+impl FilterRule {
+    pub fn validate(&self) -> Result<(), String> {
+        let globs = [&self.repo, &self.org, &self.author, &self.label];
+        if globs.iter().all(|g| g.is_none())
+            && self.draft.is_none()
+            && self.older_than_days.is_none()
+        {
+            return Err(
+                "needs at least one of repo, org, author, label, draft or older_than_days"
+                    .to_string(),
+            );
+        }
+        if globs
+            .iter()
+            .any(|g| g.as_deref().is_some_and(|g| g.trim().is_empty()))
+        {
+            return Err("patterns must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// The rule's name, or a summary of its criteria
+    pub fn describe(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        let mut parts = Vec::new();
+        if let Some(repo) = &self.repo {
+            parts.push(format!("repo:{}", repo));
+        }
+        if let Some(org) = &self.org {
+            parts.push(format!("org:{}", org));
+        }
+        if let Some(author) = &self.author {
+            parts.push(format!("author:{}", author));
+        }
+        if let Some(label) = &self.label {
+            parts.push(format!("label:{}", label));
+        }
+        if let Some(draft) = self.draft {
+            parts.push(format!("draft:{}", draft));
+        }
+        if let Some(days) = self.older_than_days {
+            parts.push(format!("older-than:{}d", days));
+        }
+        parts.join(" ")
+    }
+
+    pub fn matches(&self, pr: &PullRequestWithChecks, now: DateTime<Utc>) -> bool {
+        let pr = &pr.pull_request;
+        let glob = |pattern: &Option<String>, text: &str| {
+            pattern.as_deref().is_none_or(|p| glob_match(p, text))
+        };
+        glob(&self.repo, &pr.repository.full_name)
+            && self.org.as_deref().is_none_or(|org| {
+                let owner = pr
+                    .repository
+                    .full_name
+                    .split_once('/')
+                    .map_or(pr.repository.owner.login.as_str(), |(owner, _)| owner);
+                glob_match(org, owner)
+            })
+            && glob(&self.author, &pr.user.login)
+            && self
+                .label
+                .as_deref()
+                .is_none_or(|p| pr.labels.iter().any(|l| glob_match(p, &l.name)))
+            && self.draft.is_none_or(|draft| pr.draft == draft)
+            && self
+                .older_than_days
+                .is_none_or(|days| now - pr.updated_at >= Duration::days(days.into()))
+    }
+}
+
+/// Whether `pr` survives `rules`: it must match an include rule (if there are any)
+/// and no exclude rule
+pub fn is_shown(rules: &[FilterRule], pr: &PullRequestWithChecks, now: DateTime<Utc>) -> bool {
+    let mut includes = rules
+        .iter()
+        .filter(|r| r.action == FilterAction::Include)
+        .peekable();
+    let included = includes.peek().is_none() || includes.any(|r| r.matches(pr, now));
+    included
+        && !rules
+            .iter()
+            .any(|r| r.action == FilterAction::Exclude && r.matches(pr, now))
+}
+
+pub fn apply_filters(
+    rules: &[FilterRule],
+    prs: Vec<PullRequestWithChecks>,
+    now: DateTime<Utc>,
+) -> Vec<PullRequestWithChecks> {
+    prs.into_iter()
+        .filter(|pr| is_shown(rules, pr, now))
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PullRequestRef {
+    pub repository: String,
+    pub number: i32,
+    pub title: String,
+    pub author: String,
+}
+
+impl From<&PullRequestWithChecks> for PullRequestRef {
+    fn from(pr: &PullRequestWithChecks) -> Self {
+        let pr = &pr.pull_request;
+        Self {
+            repository: pr.repository.full_name.clone(),
+            number: pr.number,
+            title: pr.title.clone(),
+            author: pr.user.login.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RulePreview {
+    pub index: usize,
+    pub description: String,
+    pub action: FilterAction,
+    /// PRs the rule matches: hidden by exclude rules, kept by include rules
+    pub matches: Vec<PullRequestRef>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FilterPreview {
+    pub total: usize,
+    pub shown: usize,
+    pub rules: Vec<RulePreview>,
+    /// Hidden because include rules exist and none of them matched
+    pub not_included: Vec<PullRequestRef>,
+}
+
+pub fn preview(
+    rules: &[FilterRule],
+    prs: &[PullRequestWithChecks],
+    now: DateTime<Utc>,
+) -> FilterPreview {
+    let has_includes = rules.iter().any(|r| r.action == FilterAction::Include);
+    FilterPreview {
+        total: prs.len(),
+        shown: prs.iter().filter(|pr| is_shown(rules, pr, now)).count(),
+        rules: rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| RulePreview {
+                index,
+                description: rule.describe(),
+                action: rule.action,
+                matches: prs
+                    .iter()
+                    .filter(|pr| rule.matches(pr, now))
+                    .map(PullRequestRef::from)
+                    .collect(),
+            })
+            .collect(),
+        not_included: prs
+            .iter()
+            .filter(|pr| {
+                has_includes
+                    && !rules
+                        .iter()
+                        .any(|r| r.action == FilterAction::Include && r.matches(pr, now))
+            })
+            .map(PullRequestRef::from)
+            .collect(),
+    }
+}
+
+/// Show what `rules` (or the configured rules) would do to the current PR list
+pub async fn preview_filters(rules: Option<Vec<FilterRule>>) -> Result<FilterPreview, GitHubError> {
+    let rules = rules.unwrap_or_else(|| config::current().filters.clone());
+    for (i, rule) in rules.iter().enumerate() {
+        rule.validate()
+            .map_err(|e| GitHubError::Api(format!("Filter rule {}: {}", i + 1, e)))?;
+    }
+    let prs = fetch_unfiltered_prs().await?;
+    Ok(preview(&rules, &prs, Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Label, PullRequest, PullRequestState, Repository, User};
+
+    fn user(login: &str) -> User {
+        User {
+            id: 1,
+            login: login.to_string(),
+            avatar_url: String::new(),
+            html_url: String::new(),
+        }
+    }
+
+    fn pr(
+        repo: &str,
+        author: &str,
+        labels: &[&str],
+        draft: bool,
+        age_days: i64,
+    ) -> PullRequestWithChecks {
+        let (owner, name) = repo.split_once('/').unwrap();
+        let updated_at = now() - Duration::days(age_days);
+        PullRequestWithChecks {
+            pull_request: PullRequest {
+                id: 1,
+                number: 1,
+                title: format!("PR in {}", repo),
+                html_url: String::new(),
+                state: PullRequestState::Open,
+                draft,
+                created_at: updated_at,
+                updated_at,
+                merged_at: None,
+                user: user(author),
+                labels: labels
+                    .iter()
+                    .map(|name| Label {
+                        id: 1,
+                        name: name.to_string(),
+                        color: String::new(),
+                        description: None,
+                    })
+                    .collect(),
+                requested_reviewers: vec![],
+                repository: Repository {
+                    id: 1,
+                    name: name.to_string(),
+                    full_name: repo.to_string(),
+                    html_url: String::new(),
+                    owner: user(owner),
+                },
+            },
+            check_status: None,
+            review_sla: None,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2026-10-18T00:00:00Z".parse().unwrap()
+    }
+
+    fn exclude() -> FilterRule {
+        FilterRule::default()
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("octo-org/*", "octo-org/hello-world"));
+        assert!(glob_match("*-generated", "octo-org/api-generated"));
+        assert!(glob_match("*[bot]", "dependabot[bot]"));
+        assert!(!glob_match("*[bot]", "robot"));
+        assert!(glob_match("Octo?Org/*", "octo-org/x"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("a*b*c", "axxbyy"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[test]
+    fn test_rule_criteria() {
+        let bot_pr = pr(
+            "octo-org/api",
+            "dependabot[bot]",
+            &["dependencies"],
+            false,
+            1,
+        );
+        let draft_pr = pr("other/tool", "hubot", &[], true, 40);
+
+        let rule = |f: fn(&mut FilterRule)| {
+            let mut rule = exclude();
+            f(&mut rule);
+            rule
+        };
+        let by_author = rule(|r| r.author = Some("*[bot]".into()));
+        assert!(by_author.matches(&bot_pr, now()));
+        assert!(!by_author.matches(&draft_pr, now()));
+
+        let by_org = rule(|r| r.org = Some("OCTO-ORG".into()));
+        assert!(by_org.matches(&bot_pr, now()));
+        assert!(!by_org.matches(&draft_pr, now()));
+
+        let by_label = rule(|r| r.label = Some("dep*".into()));
+        assert!(by_label.matches(&bot_pr, now()));
+
+        let by_draft = rule(|r| r.draft = Some(true));
+        assert!(by_draft.matches(&draft_pr, now()));
+        assert!(!by_draft.matches(&bot_pr, now()));
+
+        let by_age = rule(|r| r.older_than_days = Some(30));
+        assert!(by_age.matches(&draft_pr, now()));
+        assert!(!by_age.matches(&bot_pr, now()));
+
+        // Every criterion has to match
+        let combined = rule(|r| {
+            r.org = Some("octo-org".into());
+            r.draft = Some(true);
+        });
+        assert!(!combined.matches(&bot_pr, now()));
+    }
+
+    #[test]
+    fn test_include_and_exclude() {
+        let prs = vec![
+            pr("octo-org/api", "hubot", &[], false, 1),
+            pr("octo-org/api", "dependabot[bot]", &[], false, 1),
+            pr("elsewhere/repo", "hubot", &[], false, 1),
+        ];
+        let rules = vec![
+            FilterRule {
+                action: FilterAction::Include,
+                org: Some("octo-org".into()),
+                ..Default::default()
+            },
+            FilterRule {
+                author: Some("*[bot]".into()),
+                ..Default::default()
+            },
+        ];
+
+        let shown = apply_filters(&rules, prs.clone(), now());
+        assert_eq!(shown.len(), 1);
+        assert_eq!(shown[0].pull_request.user.login, "hubot");
+        assert_eq!(shown[0].pull_request.repository.full_name, "octo-org/api");
+
+        assert_eq!(apply_filters(&[], prs, now()).len(), 3);
+    }
+
+    #[test]
+    fn test_preview() {
+        let prs = vec![
+            pr("octo-org/api", "hubot", &[], false, 1),
+            pr("octo-org/api", "renovate[bot]", &[], false, 1),
+            pr("elsewhere/repo", "hubot", &[], false, 1),
+        ];
+        let rules = vec![
+            FilterRule {
+                name: Some("Bots".into()),
+                author: Some("*[bot]".into()),
+                ..Default::default()
+            },
+            FilterRule {
+                action: FilterAction::Include,
+                repo: Some("octo-org/*".into()),
+                ..Default::default()
+            },
+        ];
+
+        let preview = preview(&rules, &prs, now());
+        assert_eq!(preview.total, 3);
+        assert_eq!(preview.shown, 1);
+        assert_eq!(preview.rules[0].description, "Bots");
+        assert_eq!(preview.rules[0].matches[0].author, "renovate[bot]");
+        assert_eq!(preview.rules[1].description, "repo:octo-org/*");
+        assert_eq!(preview.rules[1].matches.len(), 2);
+        assert_eq!(preview.not_included[0].repository, "elsewhere/repo");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(exclude().validate().is_err());
+        assert!(FilterRule {
+            repo: Some(" ".into()),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(FilterRule {
+            draft: Some(true),
+            ..Default::default()
+        }
+        .validate()
+        .is_ok());
+    }
+}
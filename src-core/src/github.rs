@@ -0,0 +1,773 @@
+use crate::auth::{get_token, AuthError};
+use crate::config;
+use crate::filters::apply_filters;
+use crate::http::{api_url, HttpClient, HttpError};
+use crate::models::{
+    ChangedFile, CheckState, CheckStatus, Label, PullRequest, PullRequestDetails, PullRequestState,
+    PullRequestWithChecks, Repository, StatusCheck, User,
+};
+use crate::sla::{fetch_review_sla, fetch_viewer_login};
+use crate::view::sort_prs;
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum GitHubError {
+    #[error("Authentication error: {0}")]
+    Auth(#[from] AuthError),
+    #[error(transparent)]
+    Http(#[from] HttpError),
+    #[error("API error: {0}")]
+    Api(String),
+}
+
+impl serde::Serialize for GitHubError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct SearchResponse {
+    total_count: i32,
+    incomplete_results: bool,
+    items: Vec<SearchItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchItem {
+    id: i64,
+    number: i32,
+    title: String,
+    html_url: String,
+    state: String,
+    draft: Option<bool>,
+    created_at: String,
+    updated_at: String,
+    merged_at: Option<String>,
+    user: ApiUser,
+    labels: Vec<ApiLabel>,
+    requested_reviewers: Option<Vec<ApiUser>>,
+    repository_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiUser {
+    id: i64,
+    login: String,
+    avatar_url: String,
+    html_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiLabel {
+    id: i64,
+    name: String,
+    color: String,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiRepository {
+    id: i64,
+    name: String,
+    full_name: String,
+    html_url: String,
+    owner: ApiUser,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCombinedStatus {
+    state: String,
+    total_count: i32,
+    statuses: Vec<ApiStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiStatus {
+    state: String,
+    context: String,
+    description: Option<String>,
+    target_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiPullRequest {
+    id: i64,
+    number: i32,
+    title: String,
+    html_url: String,
+    state: String,
+    draft: Option<bool>,
+    created_at: String,
+    updated_at: String,
+    merged_at: Option<String>,
+    user: ApiUser,
+    labels: Vec<ApiLabel>,
+    requested_reviewers: Vec<ApiUser>,
+    head: ApiHeadRef,
+    base: ApiBaseRef,
+    body: Option<String>,
+    additions: Option<i32>,
+    deletions: Option<i32>,
+    changed_files: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiHeadRef {
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiBaseRef {
+    repo: ApiRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiChangedFile {
+    filename: String,
+    status: String,
+    additions: i32,
+    deletions: i32,
+}
+
+impl From<ApiUser> for User {
+    fn from(api_user: ApiUser) -> Self {
+        User {
+            id: api_user.id,
+            login: api_user.login,
+            avatar_url: api_user.avatar_url,
+            html_url: api_user.html_url,
+        }
+    }
+}
+
+impl From<ApiLabel> for Label {
+    fn from(api_label: ApiLabel) -> Self {
+        Label {
+            id: api_label.id,
+            name: api_label.name,
+            color: api_label.color,
+            description: api_label.description,
+        }
+    }
+}
+
+impl From<ApiRepository> for Repository {
+    fn from(api_repo: ApiRepository) -> Self {
+        Repository {
+            id: api_repo.id,
+            name: api_repo.name,
+            full_name: api_repo.full_name,
+            html_url: api_repo.html_url,
+            owner: api_repo.owner.into(),
+        }
+    }
+}
+
+fn parse_state(state: &str) -> PullRequestState {
+    match state.to_lowercase().as_str() {
+        "open" => PullRequestState::Open,
+        "closed" => PullRequestState::Closed,
+        _ => PullRequestState::Open,
+    }
+}
+
+fn parse_datetime(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .ok()
+}
+
+fn parse_check_state(state: &str) -> CheckState {
+    match state.to_lowercase().as_str() {
+        "success" => CheckState::Success,
+        "pending" => CheckState::Pending,
+        "failure" => CheckState::Failure,
+        "error" => CheckState::Error,
+        _ => CheckState::Pending,
+    }
+}
+
+/// Pull requests matching the configured queries, after `[[filters]]` rules from
+/// config.toml and in `[view]` sort order
+#[tracing::instrument]
+pub async fn review_requested_prs() -> Result<Vec<PullRequestWithChecks>, GitHubError> {
+    let prs = fetch_unfiltered_prs().await?;
+    let settings = config::current();
+    let mut prs = apply_filters(&settings.filters, prs, chrono::Utc::now());
+    sort_prs(&mut prs, &settings.view.sort);
+    Ok(prs)
+}
+
+/// Pull requests matching the configured queries, before any filter rules
+pub(crate) async fn fetch_unfiltered_prs() -> Result<Vec<PullRequestWithChecks>, GitHubError> {
+    let token = get_token().await?;
+    let client = HttpClient::from_env();
+    let login = fetch_viewer_login(&client, &token).await;
+
+    let search_url = &api_url("/search/issues");
+
+    // Review-requested and assigned PRs unless config.toml lists other queries
+    let settings = config::current();
+    let queries = &settings.pull_requests.queries;
+
+    let mut all_items: Vec<SearchItem> = Vec::new();
+    let mut seen_ids: std::collections::HashSet<i64> = std::collections::HashSet::new();
+
+    for query in queries {
+        let search_response: SearchResponse = client
+            .github_get(search_url, &token)
+            .query(&[
+                ("q", query.as_str()),
+                ("sort", "updated"),
+                ("order", "desc"),
+            ])
+            .fetch("Failed to fetch PRs")
+            .await?
+            .json()?;
+
+        // Deduplicate by PR id
+        for item in search_response.items {
+            if seen_ids.insert(item.id) {
+                all_items.push(item);
+            }
+        }
+    }
+
+    // Most recently updated first, comparing parsed timestamps rather than strings
+    all_items.sort_by_key(|item| std::cmp::Reverse(parse_datetime(&item.updated_at)));
+
+    let mut prs_with_checks = Vec::new();
+
+    for item in all_items {
+        // Fetch repository details
+        let repo_response = client
+            .github_get(&item.repository_url, &token)
+            .send()
+            .await?;
+
+        let repository: Repository = if repo_response.status().is_success() {
+            let api_repo: ApiRepository = repo_response.json()?;
+            api_repo.into()
+        } else {
+            // Fallback: extract repo info from URL
+            let parts: Vec<&str> = item.repository_url.split('/').collect();
+            let repo_name = parts.last().unwrap_or(&"unknown").to_string();
+            let owner_name = parts
+                .get(parts.len().saturating_sub(2))
+                .unwrap_or(&"unknown");
+            Repository {
+                id: 0,
+                name: repo_name.clone(),
+                full_name: format!("{}/{}", owner_name, repo_name),
+                html_url: item
+                    .repository_url
+                    .replace("api.github.com/repos", "github.com"),
+                owner: User {
+                    id: 0,
+                    login: owner_name.to_string(),
+                    avatar_url: String::new(),
+                    html_url: format!("https://github.com/{}", owner_name),
+                },
+            }
+        };
+
+        // Fetch PR details to get requested_reviewers
+        let pr_url = api_url(&format!(
+            "/repos/{}/pulls/{}",
+            repository.full_name, item.number
+        ));
+        let pr_response = client.github_get(&pr_url, &token).send().await?;
+
+        let requested_reviewers: Vec<User> = if pr_response.status().is_success() {
+            #[derive(Deserialize)]
+            struct PrDetail {
+                requested_reviewers: Vec<ApiUser>,
+            }
+            let pr_detail: PrDetail = pr_response.json()?;
+            pr_detail
+                .requested_reviewers
+                .into_iter()
+                .map(|u| u.into())
+                .collect()
+        } else {
+            item.requested_reviewers
+                .unwrap_or_default()
+                .into_iter()
+                .map(|u| u.into())
+                .collect()
+        };
+
+        // Fetch check status
+        let status_url = api_url(&format!(
+            "/repos/{}/commits/HEAD/status",
+            repository.full_name
+        ));
+        let _status_response = client.github_get(&status_url, &token).send().await;
+
+        let pr = PullRequest {
+            id: item.id,
+            number: item.number,
+            title: item.title,
+            html_url: item.html_url,
+            state: parse_state(&item.state),
+            draft: item.draft.unwrap_or(false),
+            created_at: parse_datetime(&item.created_at).unwrap_or_else(chrono::Utc::now),
+            updated_at: parse_datetime(&item.updated_at).unwrap_or_else(chrono::Utc::now),
+            merged_at: item.merged_at.as_deref().and_then(parse_datetime),
+            user: item.user.into(),
+            labels: item.labels.into_iter().map(|l| l.into()).collect(),
+            requested_reviewers,
+            repository,
+        };
+
+        // Fetch combined status for the PR's head commit
+        let check_status = fetch_pr_check_status(&client, &token, &pr).await.ok();
+        let review_sla = fetch_review_sla(
+            &client,
+            &token,
+            &pr.repository.full_name,
+            pr.number,
+            pr.updated_at,
+            login.as_deref(),
+        )
+        .await;
+
+        prs_with_checks.push(PullRequestWithChecks {
+            pull_request: pr,
+            check_status,
+            review_sla,
+        });
+    }
+
+    Ok(prs_with_checks)
+}
+
+/// Fetch a single pull request with its check status
+#[tracing::instrument]
+pub async fn fetch_pull_request(
+    owner: &str,
+    repo: &str,
+    number: i32,
+) -> Result<PullRequestWithChecks, GitHubError> {
+    let token = get_token().await?;
+    let client = HttpClient::from_env();
+
+    let api_pr = fetch_api_pull_request(&client, &token, owner, repo, number).await?;
+    Ok(build_pull_request_with_checks(&client, &token, api_pr).await)
+}
+
+/// Fetch a single pull request with its description and changed files
+#[tracing::instrument]
+pub async fn fetch_pull_request_details(
+    owner: &str,
+    repo: &str,
+    number: i32,
+) -> Result<PullRequestDetails, GitHubError> {
+    let token = get_token().await?;
+    let client = HttpClient::from_env();
+
+    let mut api_pr = fetch_api_pull_request(&client, &token, owner, repo, number).await?;
+    let body = api_pr.body.take();
+    let additions = api_pr.additions.unwrap_or(0);
+    let deletions = api_pr.deletions.unwrap_or(0);
+    let changed_files = api_pr.changed_files.unwrap_or(0);
+    let files = fetch_changed_files(&client, &token, owner, repo, number).await?;
+    let pull_request = build_pull_request_with_checks(&client, &token, api_pr).await;
+
+    Ok(PullRequestDetails {
+        pull_request,
+        body,
+        additions,
+        deletions,
+        changed_files,
+        files,
+    })
+}
+
+async fn fetch_api_pull_request(
+    client: &HttpClient,
+    token: &str,
+    owner: &str,
+    repo: &str,
+    number: i32,
+) -> Result<ApiPullRequest, GitHubError> {
+    let pr_url = api_url(&format!("/repos/{}/{}/pulls/{}", owner, repo, number));
+    Ok(client
+        .github_get(&pr_url, token)
+        .fetch(&format!("Failed to fetch {}/{}#{}", owner, repo, number))
+        .await?
+        .json()?)
+}
+
+async fn build_pull_request_with_checks(
+    client: &HttpClient,
+    token: &str,
+    api_pr: ApiPullRequest,
+) -> PullRequestWithChecks {
+    let repository: Repository = api_pr.base.repo.into();
+    let check_status =
+        fetch_commit_check_status(client, token, &repository.full_name, &api_pr.head.sha)
+            .await
+            .ok();
+    let updated_at = parse_datetime(&api_pr.updated_at).unwrap_or_else(chrono::Utc::now);
+    let login = fetch_viewer_login(client, token).await;
+    let review_sla = fetch_review_sla(
+        client,
+        token,
+        &repository.full_name,
+        api_pr.number,
+        updated_at,
+        login.as_deref(),
+    )
+    .await;
+
+    let pr = PullRequest {
+        id: api_pr.id,
+        number: api_pr.number,
+        title: api_pr.title,
+        html_url: api_pr.html_url,
+        state: parse_state(&api_pr.state),
+        draft: api_pr.draft.unwrap_or(false),
+        created_at: parse_datetime(&api_pr.created_at).unwrap_or_else(chrono::Utc::now),
+        updated_at,
+        merged_at: api_pr.merged_at.as_deref().and_then(parse_datetime),
+        user: api_pr.user.into(),
+        labels: api_pr.labels.into_iter().map(|l| l.into()).collect(),
+        requested_reviewers: api_pr
+            .requested_reviewers
+            .into_iter()
+            .map(|u| u.into())
+            .collect(),
+        repository,
+    };
+
+    PullRequestWithChecks {
+        pull_request: pr,
+        check_status,
+        review_sla,
+    }
+}
+
+async fn fetch_changed_files(
+    client: &HttpClient,
+    token: &str,
+    owner: &str,
+    repo: &str,
+    number: i32,
+) -> Result<Vec<ChangedFile>, GitHubError> {
+    let files_url = api_url(&format!("/repos/{}/{}/pulls/{}/files", owner, repo, number));
+    let files: Vec<ApiChangedFile> = client
+        .github_get(&files_url, token)
+        .query(&[("per_page", "100")])
+        .fetch("Failed to fetch changed files")
+        .await?
+        .json()?;
+    Ok(files
+        .into_iter()
+        .map(|f| ChangedFile {
+            filename: f.filename,
+            status: f.status,
+            additions: f.additions,
+            deletions: f.deletions,
+        })
+        .collect())
+}
+
+/// Fetch the combined check status of a pull request's head commit
+#[tracing::instrument]
+pub async fn fetch_check_status(
+    owner: &str,
+    repo: &str,
+    number: i32,
+) -> Result<CheckStatus, GitHubError> {
+    let token = get_token().await?;
+    let client = HttpClient::from_env();
+    let full_name = format!("{}/{}", owner, repo);
+
+    let sha = fetch_head_sha(&client, &token, &full_name, number).await?;
+    fetch_commit_check_status(&client, &token, &full_name, &sha).await
+}
+
+async fn fetch_pr_check_status(
+    client: &HttpClient,
+    token: &str,
+    pr: &PullRequest,
+) -> Result<CheckStatus, GitHubError> {
+    let sha = fetch_head_sha(client, token, &pr.repository.full_name, pr.number).await?;
+    fetch_commit_check_status(client, token, &pr.repository.full_name, &sha).await
+}
+
+pub(crate) async fn fetch_head_sha(
+    client: &HttpClient,
+    token: &str,
+    full_name: &str,
+    number: i32,
+) -> Result<String, GitHubError> {
+    // Get the PR's head SHA
+    let pr_url = api_url(&format!("/repos/{}/pulls/{}", full_name, number));
+
+    #[derive(Deserialize)]
+    struct PrHead {
+        head: ApiHeadRef,
+    }
+
+    let pr_head: PrHead = client
+        .github_get(&pr_url, token)
+        .fetch("Failed to fetch PR details")
+        .await?
+        .json()?;
+    Ok(pr_head.head.sha)
+}
+
+async fn fetch_commit_check_status(
+    client: &HttpClient,
+    token: &str,
+    full_name: &str,
+    sha: &str,
+) -> Result<CheckStatus, GitHubError> {
+    // Fetch combined status
+    let status_url = api_url(&format!("/repos/{}/commits/{}/status", full_name, sha));
+    let status_response = client.github_get(&status_url, token).send().await?;
+
+    if !status_response.status().is_success() {
+        return Ok(CheckStatus {
+            state: CheckState::Pending,
+            total_count: 0,
+            statuses: vec![],
+        });
+    }
+
+    let api_status: ApiCombinedStatus = status_response.json()?;
+
+    Ok(CheckStatus {
+        state: parse_check_state(&api_status.state),
+        total_count: api_status.total_count,
+        statuses: api_status
+            .statuses
+            .into_iter()
+            .map(|s| StatusCheck {
+                state: parse_check_state(&s.state),
+                context: s.context,
+                description: s.description,
+                target_url: s.target_url,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{FIXTURES_ENV, HTTP_MODE_ENV};
+    use crate::test_support::{FakeGitHubEnv, IsolatedEnv};
+    use ghview_fake_github::{FakePull, Scenario};
+    use serial_test::serial;
+
+    const TOKEN: &str = "gho_test";
+
+    /// Replays `tests/fixtures/replay` with a dummy environment token
+    fn replay_env() -> IsolatedEnv {
+        let env = IsolatedEnv::new();
+        env.set(HTTP_MODE_ENV, "replay");
+        env.set(
+            FIXTURES_ENV,
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/replay"),
+        );
+        env.set("GH_TOKEN", "gho_replay");
+        env
+    }
+
+    fn scenario() -> Scenario {
+        Scenario::new()
+            .token(TOKEN, "octocat", &["repo"])
+            .pull(
+                FakePull::new("octo-org/hello-world", 7, "Add greeting endpoint")
+                    .author("hubot")
+                    .body("Adds a greeting")
+                    .label("enhancement")
+                    .reviewer("octocat")
+                    .reviewer("monalisa")
+                    .review_requested()
+                    .assigned()
+                    .updated_at("2026-10-17T12:00:00Z")
+                    .file("src/greeting.rs", 40, 2)
+                    .file("README.md", 3, 0)
+                    .status("success", "ci/build")
+                    .status("failure", "ci/test"),
+            )
+            .pull(
+                FakePull::new("octo-org/spoon-knife", 42, "Fix fork counter")
+                    .assigned()
+                    .updated_at("2026-10-16T08:30:00Z")
+                    .status("success", "ci/build"),
+            )
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_review_requested_prs_replay() {
+        let _env = replay_env();
+        let prs = review_requested_prs().await.unwrap();
+
+        // Deduplicated across both searches and sorted by updated_at
+        let numbers: Vec<i32> = prs.iter().map(|p| p.pull_request.number).collect();
+        assert_eq!(numbers, vec![7, 42]);
+
+        let first = &prs[0];
+        assert_eq!(
+            first.pull_request.repository.full_name,
+            "octo-org/hello-world"
+        );
+        assert_eq!(first.pull_request.labels[0].name, "enhancement");
+        let reviewers: Vec<&str> = first
+            .pull_request
+            .requested_reviewers
+            .iter()
+            .map(|u| u.login.as_str())
+            .collect();
+        assert_eq!(reviewers, vec!["octocat", "monalisa"]);
+
+        let checks = first.check_status.as_ref().unwrap();
+        assert_eq!(checks.state, CheckState::Failure);
+        assert_eq!(checks.total_count, 2);
+        assert_eq!(
+            prs[1].check_status.as_ref().unwrap().state,
+            CheckState::Success
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_replay_reports_missing_fixture() {
+        let _env = replay_env();
+        let result = fetch_check_status("octo-org", "unknown", 1).await;
+        assert!(matches!(
+            result,
+            Err(GitHubError::Http(HttpError::FixtureMissing { .. }))
+        ));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_review_requested_prs() {
+        let fake = FakeGitHubEnv::start(scenario()).await.with_env_token(TOKEN);
+        let prs = review_requested_prs().await.unwrap();
+
+        let numbers: Vec<i32> = prs.iter().map(|p| p.pull_request.number).collect();
+        assert_eq!(numbers, vec![7, 42]);
+
+        let pr = &prs[0].pull_request;
+        assert_eq!(pr.repository.full_name, "octo-org/hello-world");
+        assert_eq!(pr.repository.owner.login, "octo-org");
+        assert_eq!(pr.user.login, "hubot");
+        assert_eq!(pr.requested_reviewers.len(), 2);
+        let checks = prs[0].check_status.as_ref().unwrap();
+        assert_eq!(checks.state, CheckState::Failure);
+        assert_eq!(checks.statuses[1].context, "ci/test");
+
+        let requests = fake.server.requests();
+        assert!(requests
+            .iter()
+            .all(|r| r.authorization.as_deref() == Some("Bearer gho_test")));
+        assert_eq!(
+            requests
+                .iter()
+                .filter(|r| r.path == "/search/issues")
+                .count(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_search_failure_is_an_api_error() {
+        let _fake = FakeGitHubEnv::start(scenario().fail("/search/issues", 500))
+            .await
+            .with_env_token(TOKEN);
+        match review_requested_prs().await {
+            Err(GitHubError::Api(message)) => assert!(message.contains("Injected failure")),
+            other => panic!("unexpected result: {:?}", other.map(|prs| prs.len())),
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_rejected_token_is_an_api_error() {
+        let _fake = FakeGitHubEnv::start(scenario())
+            .await
+            .with_env_token("gho_revoked");
+        assert!(matches!(
+            review_requested_prs().await,
+            Err(GitHubError::Api(_))
+        ));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_enrichment_failures_fall_back() {
+        let _fake = FakeGitHubEnv::start(
+            scenario()
+                .fail("/repos/octo-org/spoon-knife/commits", 502)
+                .fail("/repos/octo-org/spoon-knife", 404),
+        )
+        .await
+        .with_env_token(TOKEN);
+        let prs = review_requested_prs().await.unwrap();
+
+        // Repository info derived from the search result's repository_url
+        let pr = &prs[1].pull_request;
+        assert_eq!(pr.repository.full_name, "octo-org/spoon-knife");
+        assert_eq!(pr.repository.id, 0);
+        assert!(prs[1].check_status.is_none());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_fetch_pull_request_details() {
+        let _fake = FakeGitHubEnv::start(scenario()).await.with_env_token(TOKEN);
+        let details = fetch_pull_request_details("octo-org", "hello-world", 7)
+            .await
+            .unwrap();
+
+        assert_eq!(details.body.as_deref(), Some("Adds a greeting"));
+        assert_eq!(details.additions, 43);
+        assert_eq!(details.deletions, 2);
+        assert_eq!(details.changed_files, 2);
+        assert_eq!(details.files[0].filename, "src/greeting.rs");
+        assert_eq!(
+            details.pull_request.check_status.unwrap().state,
+            CheckState::Failure
+        );
+
+        assert!(matches!(
+            fetch_pull_request("octo-org", "hello-world", 999).await,
+            Err(GitHubError::Api(_))
+        ));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_fetch_check_status() {
+        let fake = FakeGitHubEnv::start(scenario()).await.with_env_token(TOKEN);
+        let status = fetch_check_status("octo-org", "spoon-knife", 42)
+            .await
+            .unwrap();
+        assert_eq!(status.state, CheckState::Success);
+
+        // A status endpoint error reads as pending rather than failing the call
+        fake.server.update(|s| {
+            *s = s.clone().fail("/repos/octo-org/spoon-knife/commits", 500);
+        });
+        let status = fetch_check_status("octo-org", "spoon-knife", 42)
+            .await
+            .unwrap();
+        assert_eq!(status.state, CheckState::Pending);
+        assert_eq!(status.total_count, 0);
+    }
+}
@@ -0,0 +1,480 @@
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::config;
+use crate::debug::{log_request, log_response, log_response_error};
+use crate::github::GitHubError;
+use crate::logging::redact;
+
+/// Environment variable selecting `live`, `record` or `replay`
+pub const HTTP_MODE_ENV: &str = "GHVIEW_HTTP_MODE";
+
+/// Environment variable overriding the fixture directory (default ~/.config/ghview/fixtures)
+pub const FIXTURES_ENV: &str = "GHVIEW_HTTP_FIXTURES";
+
+/// Environment variable overriding the REST API base URL, e.g. to point at a fake server
+pub const API_URL_ENV: &str = "GHVIEW_GITHUB_API_URL";
+
+/// Response headers that are never written to fixtures
+const SKIPPED_HEADERS: [&str; 2] = ["set-cookie", "authorization"];
+
+#[derive(Debug, Error)]
+pub enum HttpError {
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("Invalid response body: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("No recorded response for {method} {url} in {dir}")]
+    FixtureMissing {
+        method: String,
+        url: String,
+        dir: String,
+    },
+    #[error("Fixture error: {0}")]
+    Fixture(#[from] io::Error),
+}
+
+/// Absolute URL of a REST API path such as `/user`, against `GHVIEW_GITHUB_API_URL`
+/// or else `github.api_url` in config.toml
+pub fn api_url(path: &str) -> String {
+    let base = std::env::var(API_URL_ENV)
+        .ok()
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| config::current().github.api_url.clone());
+    format!("{}{}", base.trim_end_matches('/'), path)
+}
+
+/// Whether requests hit the network, and whether their responses are saved or served from disk
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum HttpMode {
+    #[default]
+    Live,
+    /// Send requests and save every response to the fixture directory
+    Record(PathBuf),
+    /// Serve responses from the fixture directory without touching the network
+    Replay(PathBuf),
+}
+
+impl HttpMode {
+    /// `GHVIEW_HTTP_MODE` and `GHVIEW_HTTP_FIXTURES`, falling back to `[http]` in config.toml
+    pub fn from_env() -> Self {
+        let settings = config::current();
+        let mode = std::env::var(HTTP_MODE_ENV)
+            .ok()
+            .filter(|mode| !mode.is_empty())
+            .unwrap_or_else(|| settings.http.mode.clone());
+        let fixtures = || {
+            std::env::var(FIXTURES_ENV)
+                .ok()
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+                .or_else(|| settings.http.fixtures_dir.clone())
+                .or_else(|| config::config_dir().ok().map(|dir| dir.join("fixtures")))
+                .unwrap_or_else(|| PathBuf::from("fixtures"))
+        };
+        match mode.trim().to_lowercase().as_str() {
+            "" | "live" => HttpMode::Live,
+            "record" => HttpMode::Record(fixtures()),
+            "replay" => HttpMode::Replay(fixtures()),
+            other => {
+                tracing::warn!("Unknown {} {:?}, using live", HTTP_MODE_ENV, other);
+                HttpMode::Live
+            }
+        }
+    }
+}
+
+/// A recorded exchange; the request's `Authorization` header and any tokens in
+/// the body are scrubbed before it is written
+#[derive(Debug, Serialize, Deserialize)]
+struct Fixture {
+    method: String,
+    url: String,
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body: String,
+}
+
+/// Fixture file for a request: readable prefix plus a hash of the full method and URL
+fn fixture_path(dir: &Path, method: &Method, url: &str) -> PathBuf {
+    let key = format!("{} {}", method, url);
+    // FNV-1a, so file names stay stable across Rust versions
+    let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    let path = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .split('?')
+        .next()
+        .unwrap_or_default();
+    let slug: String = path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .take(80)
+        .collect();
+    dir.join(format!(
+        "{}_{}_{:016x}.json",
+        method.as_str().to_lowercase(),
+        slug,
+        hash
+    ))
+}
+
+/// A fully buffered response
+#[derive(Debug)]
+pub struct HttpResponse {
+    status: StatusCode,
+    headers: BTreeMap<String, String>,
+    body: String,
+}
+
+impl HttpResponse {
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_lowercase()).map(String::as_str)
+    }
+
+    pub fn text(&self) -> &str {
+        &self.body
+    }
+
+    /// Page number of the `rel="last"` link GitHub sends with paginated responses;
+    /// `None` when everything fit on one page
+    pub fn last_page(&self) -> Option<u32> {
+        self.header("Link")?.split(',').find_map(|link| {
+            let (url, rel) = link.split_once(';')?;
+            if rel.trim() != r#"rel="last""# {
+                return None;
+            }
+            let url = reqwest::Url::parse(url.trim().trim_start_matches('<').trim_end_matches('>'))
+                .ok()?;
+            let page = url.query_pairs().find(|(name, _)| name == "page")?.1;
+            page.parse().ok()
+        })
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, HttpError> {
+        Ok(serde_json::from_str(&self.body)?)
+    }
+}
+
+fn header_map(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.as_str().to_string(), value.to_str().ok()?.into())))
+        .collect()
+}
+
+/// `reqwest::Client` that can record responses to, or replay them from, fixture files
+#[derive(Debug, Clone, Default)]
+pub struct HttpClient {
+    inner: reqwest::Client,
+    mode: HttpMode,
+}
+
+impl HttpClient {
+    pub fn new(mode: HttpMode) -> Self {
+        Self {
+            inner: reqwest::Client::new(),
+            mode,
+        }
+    }
+
+    /// Client in the mode selected by `GHVIEW_HTTP_MODE`
+    pub fn from_env() -> Self {
+        Self::new(HttpMode::from_env())
+    }
+
+    pub fn get(&self, url: &str) -> HttpRequest<'_> {
+        self.request(Method::GET, url)
+    }
+
+    pub fn post(&self, url: &str) -> HttpRequest<'_> {
+        self.request(Method::POST, url)
+    }
+
+    /// GET a GitHub REST API URL as the user `token` belongs to
+    pub fn github_get(&self, url: &str, token: &str) -> GitHubRequest<'_> {
+        self.github(Method::GET, url, token)
+    }
+
+    /// POST to a GitHub REST API URL as the user `token` belongs to
+    pub fn github_post(&self, url: &str, token: &str) -> GitHubRequest<'_> {
+        self.github(Method::POST, url, token)
+    }
+
+    fn github(&self, method: Method, url: &str, token: &str) -> GitHubRequest<'_> {
+        GitHubRequest {
+            request: self
+                .request(method.clone(), url)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("User-Agent", "ghview")
+                .header("Accept", "application/vnd.github+json"),
+            method,
+            url: url.to_string(),
+        }
+    }
+
+    fn request(&self, method: Method, url: &str) -> HttpRequest<'_> {
+        HttpRequest {
+            client: self,
+            builder: self.inner.request(method, url),
+        }
+    }
+
+    async fn execute(&self, request: reqwest::Request) -> Result<HttpResponse, HttpError> {
+        let response = self.inner.execute(request).await?;
+        Ok(HttpResponse {
+            status: response.status(),
+            headers: header_map(response.headers()),
+            body: response.text().await?,
+        })
+    }
+
+    async fn send(&self, request: reqwest::Request) -> Result<HttpResponse, HttpError> {
+        match &self.mode {
+            HttpMode::Live => self.execute(request).await,
+            HttpMode::Replay(dir) => replay(dir, request.method(), request.url().as_str()),
+            HttpMode::Record(dir) => {
+                let method = request.method().clone();
+                let url = request.url().to_string();
+                let response = self.execute(request).await?;
+                record(dir, &method, &url, &response)?;
+                Ok(response)
+            }
+        }
+    }
+}
+
+fn replay(dir: &Path, method: &Method, url: &str) -> Result<HttpResponse, HttpError> {
+    let path = fixture_path(dir, method, url);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Err(HttpError::FixtureMissing {
+                method: method.to_string(),
+                url: url.to_string(),
+                dir: dir.display().to_string(),
+            })
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let fixture: Fixture = serde_json::from_str(&content)?;
+    Ok(HttpResponse {
+        status: StatusCode::from_u16(fixture.status)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+        headers: fixture.headers,
+        body: fixture.body,
+    })
+}
+
+fn record(
+    dir: &Path,
+    method: &Method,
+    url: &str,
+    response: &HttpResponse,
+) -> Result<(), HttpError> {
+    let fixture = Fixture {
+        method: method.to_string(),
+        url: redact(url),
+        status: response.status.as_u16(),
+        headers: response
+            .headers
+            .iter()
+            .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+            .map(|(name, value)| (name.clone(), redact(value)))
+            .collect(),
+        body: redact(&response.body),
+    };
+    std::fs::create_dir_all(dir)?;
+    let path = fixture_path(dir, method, url);
+    std::fs::write(&path, serde_json::to_string_pretty(&fixture)?)?;
+    tracing::debug!(path = %path.display(), "Recorded HTTP fixture");
+    Ok(())
+}
+
+/// Thin wrapper over `reqwest::RequestBuilder` that sends through the owning `HttpClient`
+pub struct HttpRequest<'a> {
+    client: &'a HttpClient,
+    builder: reqwest::RequestBuilder,
+}
+
+impl HttpRequest<'_> {
+    pub fn header(mut self, name: &str, value: impl AsRef<str>) -> Self {
+        self.builder = self.builder.header(name, value.as_ref());
+        self
+    }
+
+    pub fn query<T: Serialize + ?Sized>(mut self, query: &T) -> Self {
+        self.builder = self.builder.query(query);
+        self
+    }
+
+    pub fn form<T: Serialize + ?Sized>(mut self, form: &T) -> Self {
+        self.builder = self.builder.form(form);
+        self
+    }
+
+    pub fn json<T: Serialize + ?Sized>(mut self, json: &T) -> Self {
+        self.builder = self.builder.json(json);
+        self
+    }
+
+    pub async fn send(self) -> Result<HttpResponse, HttpError> {
+        let request = self.builder.build()?;
+        self.client.send(request).await
+    }
+}
+
+/// Authenticated GitHub API request whose exchange is logged when sent
+pub struct GitHubRequest<'a> {
+    request: HttpRequest<'a>,
+    method: Method,
+    url: String,
+}
+
+impl GitHubRequest<'_> {
+    pub fn query<T: Serialize + ?Sized>(mut self, query: &T) -> Self {
+        self.request = self.request.query(query);
+        self
+    }
+
+    pub fn json<T: Serialize + ?Sized>(mut self, json: &T) -> Self {
+        self.request = self.request.json(json);
+        self
+    }
+
+    /// Send without checking the status, for callers that fall back on failure
+    pub async fn send(self) -> Result<HttpResponse, HttpError> {
+        log_request(self.method.as_str(), &self.url);
+        let response = self.request.send().await?;
+        log_response(&self.url, response.status());
+        Ok(response)
+    }
+
+    /// Send, turning a non-success status into `GitHubError::Api` with the
+    /// response body after `failure`, e.g. "Failed to fetch PRs"
+    pub async fn fetch(self, failure: &str) -> Result<HttpResponse, GitHubError> {
+        let url = self.url.clone();
+        let response = self.send().await?;
+        if !response.status().is_success() {
+            log_response_error(&url, response.status(), response.text());
+            return Err(GitHubError::Api(format!(
+                "{}: {}",
+                failure,
+                response.text()
+            )));
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("ghview_fixtures_{}", rand::random::<u64>()))
+    }
+
+    fn response(body: &str) -> HttpResponse {
+        HttpResponse {
+            status: StatusCode::OK,
+            headers: BTreeMap::from([
+                ("x-oauth-scopes".to_string(), "repo".to_string()),
+                ("set-cookie".to_string(), "session=abc".to_string()),
+            ]),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_fixture_path_is_stable_and_distinguishes_queries() {
+        let dir = Path::new("/fixtures");
+        let a = fixture_path(
+            dir,
+            &Method::GET,
+            "https://api.github.com/search/issues?q=a",
+        );
+        let b = fixture_path(
+            dir,
+            &Method::GET,
+            "https://api.github.com/search/issues?q=b",
+        );
+        assert_ne!(a, b);
+        assert_eq!(
+            a,
+            fixture_path(
+                dir,
+                &Method::GET,
+                "https://api.github.com/search/issues?q=a"
+            )
+        );
+        assert!(a
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .starts_with("get_api_github_com_search_issues_"));
+    }
+
+    #[test]
+    fn test_record_then_replay() {
+        let dir = temp_dir();
+        let url = "https://api.github.com/user";
+        record(&dir, &Method::GET, url, &response(r#"{"login":"octocat"}"#)).unwrap();
+
+        let replayed = replay(&dir, &Method::GET, url).unwrap();
+        assert_eq!(replayed.status(), StatusCode::OK);
+        assert_eq!(replayed.header("X-OAuth-Scopes"), Some("repo"));
+        assert_eq!(replayed.header("set-cookie"), None);
+        assert_eq!(replayed.text(), r#"{"login":"octocat"}"#);
+
+        assert!(matches!(
+            replay(&dir, &Method::POST, url),
+            Err(HttpError::FixtureMissing { .. })
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_last_page_follows_link_header() {
+        let mut paged = response("[]");
+        paged.headers.insert(
+            "link".to_string(),
+            "<https://api.github.com/repositories/1/issues/2/timeline?per_page=100&page=2>; rel=\"next\", \
+             <https://api.github.com/repositories/1/issues/2/timeline?per_page=100&page=7>; rel=\"last\""
+                .to_string(),
+        );
+        assert_eq!(paged.last_page(), Some(7));
+        assert_eq!(response("[]").last_page(), None);
+    }
+
+    #[test]
+    fn test_record_scrubs_tokens() {
+        let dir = temp_dir();
+        let url = "https://github.com/login/oauth/access_token";
+        record(
+            &dir,
+            &Method::POST,
+            url,
+            &response(r#"{"access_token":"ghu_0123456789abcdefghij","token_type":"bearer"}"#),
+        )
+        .unwrap();
+
+        let content = std::fs::read_to_string(fixture_path(&dir, &Method::POST, url)).unwrap();
+        assert!(!content.contains("ghu_0123456789abcdefghij"));
+        assert!(!content.contains("session=abc"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
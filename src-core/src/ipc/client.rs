@@ -0,0 +1,249 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::PathBuf;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+#[cfg(unix)]
+use tokio::net::UnixStream;
+
+use super::protocol::{IpcRequest, IpcResponse};
+use super::transport::{read_session_file, session_path, IpcTransport};
+use super::IpcError;
+
+#[derive(Debug, Clone)]
+enum Endpoint {
+    #[cfg(unix)]
+    Unix(PathBuf),
+    Tcp {
+        addr: SocketAddr,
+        token: String,
+    },
+}
+
+/// Client side of the app's IPC server, used by the CLI and the MCP server
+#[derive(Debug, Clone)]
+pub struct IpcClient {
+    endpoint: Endpoint,
+}
+
+impl IpcClient {
+    /// Resolve the running app's socket or TCP session; fails when there's nothing
+    /// to connect to
+    pub fn connect() -> Result<Self, IpcError> {
+        let endpoint = match IpcTransport::from_env()? {
+            #[cfg(unix)]
+            IpcTransport::Unix(path) => {
+                if !path.exists() {
+                    return Err(IpcError::NotRunning(format!(
+                        "Socket not found: {}",
+                        path.display()
+                    )));
+                }
+                Endpoint::Unix(path)
+            }
+            IpcTransport::Tcp(_) => {
+                let path = session_path()?;
+                if !path.exists() {
+                    return Err(IpcError::NotRunning(format!(
+                        "Session file not found: {}",
+                        path.display()
+                    )));
+                }
+                let session = read_session_file()?;
+                Endpoint::Tcp {
+                    addr: session.addr,
+                    token: session.token,
+                }
+            }
+        };
+        Ok(Self { endpoint })
+    }
+
+    /// Whether the app answers; a stale socket or session file is left behind when
+    /// it exits abnormally
+    pub async fn ping(&self) -> bool {
+        self.request::<serde_json::Value>("ping", serde_json::json!({}))
+            .await
+            .is_ok()
+    }
+
+    /// Send one request to the app and return its result
+    pub async fn request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: impl Serialize,
+    ) -> Result<T, IpcError> {
+        let params = serde_json::to_value(params)?;
+        let result = match &self.endpoint {
+            #[cfg(unix)]
+            Endpoint::Unix(path) => {
+                let stream = UnixStream::connect(path).await.map_err(IpcError::Connect)?;
+                exchange(stream, method, params, None).await?
+            }
+            Endpoint::Tcp { addr, token } => {
+                let stream = TcpStream::connect(addr).await.map_err(IpcError::Connect)?;
+                exchange(stream, method, params, Some(token.clone())).await?
+            }
+        };
+        Ok(serde_json::from_value(result)?)
+    }
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl IpcClient {
+    /// A client pointing at a closed port, for exercising code paths that fail before IPC
+    pub fn unavailable() -> Self {
+        Self {
+            endpoint: Endpoint::Tcp {
+                addr: "127.0.0.1:1".parse().unwrap(),
+                token: String::new(),
+            },
+        }
+    }
+}
+
+async fn exchange<S>(
+    stream: S,
+    method: &str,
+    params: serde_json::Value,
+    token: Option<String>,
+) -> Result<serde_json::Value, IpcError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let request = IpcRequest {
+        method: method.to_string(),
+        params,
+        token,
+    };
+    let mut request_json = serde_json::to_string(&request)?;
+    request_json.push('\n');
+    writer.write_all(request_json.as_bytes()).await?;
+    writer.flush().await?;
+
+    let mut line = String::new();
+    BufReader::new(reader).read_line(&mut line).await?;
+    if line.is_empty() {
+        return Err(IpcError::NoResponse);
+    }
+
+    let response: IpcResponse = serde_json::from_str(&line)?;
+    if let Some(error) = response.error {
+        return Err(IpcError::Remote(error));
+    }
+    Ok(response.result.unwrap_or(serde_json::Value::Null))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::transport::{tokens_match, write_session_file, IpcSession, TRANSPORT_ENV};
+    use crate::test_support::IsolatedEnv;
+    use serial_test::serial;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_exchange_round_trip() {
+        let (client, server) = tokio::io::duplex(4096);
+        let server_task = tokio::spawn(async move {
+            let (reader, mut writer) = tokio::io::split(server);
+            let mut line = String::new();
+            BufReader::new(reader).read_line(&mut line).await.unwrap();
+            let request: IpcRequest = serde_json::from_str(&line).unwrap();
+            let response = if request.token.as_deref() == Some("secret") {
+                IpcResponse::success(serde_json::json!({"method": request.method}))
+            } else {
+                IpcResponse::error("Unauthorized: missing token")
+            };
+            let mut json = serde_json::to_string(&response).unwrap();
+            json.push('\n');
+            writer.write_all(json.as_bytes()).await.unwrap();
+        });
+
+        let result = exchange(
+            client,
+            "ping",
+            serde_json::json!({}),
+            Some("secret".to_string()),
+        )
+        .await
+        .unwrap();
+        server_task.await.unwrap();
+        assert_eq!(result, serde_json::json!({"method": "ping"}));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_surfaces_server_errors() {
+        let (client, server) = tokio::io::duplex(4096);
+        tokio::spawn(async move {
+            let (reader, mut writer) = tokio::io::split(server);
+            let mut line = String::new();
+            BufReader::new(reader).read_line(&mut line).await.unwrap();
+            writer
+                .write_all(b"{\"error\": \"Unknown method: nope\"}\n")
+                .await
+                .unwrap();
+        });
+
+        let error = exchange(client, "nope", serde_json::json!({}), None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Unknown method: nope");
+        assert!(!error.is_unreachable());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_tcp_client_authenticates_with_session_token() {
+        let env = IsolatedEnv::new();
+        env.set(TRANSPORT_ENV, "tcp");
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        write_session_file(&IpcSession {
+            addr: listener.local_addr().unwrap(),
+            token: "secret".to_string(),
+        })
+        .unwrap();
+        let server_task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = tokio::io::split(stream);
+            let mut line = String::new();
+            BufReader::new(reader).read_line(&mut line).await.unwrap();
+            let request: IpcRequest = serde_json::from_str(&line).unwrap();
+            let authorized = tokens_match("secret", request.token.as_deref().unwrap_or(""));
+            let mut json = serde_json::to_string(&IpcResponse::success(
+                serde_json::json!({ "authorized": authorized }),
+            ))
+            .unwrap();
+            json.push('\n');
+            writer.write_all(json.as_bytes()).await.unwrap();
+        });
+
+        let client = IpcClient::connect().unwrap();
+        let result: serde_json::Value =
+            client.request("ping", serde_json::json!({})).await.unwrap();
+        server_task.await.unwrap();
+        assert_eq!(result, serde_json::json!({ "authorized": true }));
+    }
+
+    #[test]
+    #[serial]
+    fn test_tcp_client_needs_session_file() {
+        let env = IsolatedEnv::new();
+        env.set(TRANSPORT_ENV, "tcp");
+        let error = IpcClient::connect().unwrap_err();
+        assert!(matches!(error, IpcError::NotRunning(_)));
+        assert!(error.is_unreachable());
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_client_is_unreachable() {
+        let error = IpcClient::unavailable()
+            .request::<serde_json::Value>("ping", serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(error.is_unreachable());
+    }
+}
@@ -0,0 +1,49 @@
+//! Local IPC between the ghview app and the processes that talk to it, the CLI and
+//! the MCP server
+
+use std::io;
+use std::net::SocketAddr;
+use thiserror::Error;
+
+use crate::config::ConfigError;
+
+pub mod client;
+pub mod protocol;
+pub mod transport;
+
+pub use client::IpcClient;
+
+#[derive(Debug, Error)]
+pub enum IpcError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("Unsupported IPC transport: {0}")]
+    UnsupportedTransport(String),
+    #[error("Invalid IPC address: {0}")]
+    InvalidAddress(String),
+    #[error("IPC address must be a loopback address: {0}")]
+    NotLoopback(SocketAddr),
+    #[error("ghview is not running. Please start ghview first. ({0})")]
+    NotRunning(String),
+    #[error("Failed to connect to ghview: {0}")]
+    Connect(#[source] io::Error),
+    #[error("IPC I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid IPC data: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("No response from ghview")]
+    NoResponse,
+    /// An error the app answered with
+    #[error("{0}")]
+    Remote(String),
+}
+
+impl IpcError {
+    /// Whether the app is gone, as opposed to having answered with an error
+    pub fn is_unreachable(&self) -> bool {
+        matches!(
+            self,
+            IpcError::NotRunning(_) | IpcError::Connect(_) | IpcError::Io(_) | IpcError::NoResponse
+        )
+    }
+}
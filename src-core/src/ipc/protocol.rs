@@ -0,0 +1,78 @@
+//! Messages exchanged over IPC: one JSON request line, answered by one JSON response line
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcRequest {
+    pub method: String,
+    pub params: serde_json::Value,
+    /// Session auth token, required by transports other than Unix sockets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl IpcResponse {
+    pub fn success(result: serde_json::Value) -> Self {
+        Self {
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            result: None,
+            error: Some(message.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipc_request_serialization() {
+        let request = IpcRequest {
+            method: "screenshot".to_string(),
+            params: serde_json::json!({"output_dir": "/tmp"}),
+            token: None,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        let parsed: IpcRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.method, "screenshot");
+        assert!(!json.contains("token"));
+    }
+
+    #[test]
+    fn test_ipc_request_with_token_deserialization() {
+        let json = r#"{"method": "ping", "params": {}, "token": "abc"}"#;
+        let request: IpcRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.token, Some("abc".to_string()));
+    }
+
+    #[test]
+    fn test_ipc_response_success() {
+        let response = IpcResponse::success(serde_json::json!({"path": "/tmp/screenshot.png"}));
+        assert!(response.result.is_some());
+        assert!(response.error.is_none());
+    }
+
+    #[test]
+    fn test_ipc_response_error() {
+        let response = IpcResponse::error("Failed to capture screenshot");
+        assert!(response.result.is_none());
+        assert_eq!(
+            response.error,
+            Some("Failed to capture screenshot".to_string())
+        );
+    }
+}
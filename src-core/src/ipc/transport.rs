@@ -0,0 +1,194 @@
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use super::IpcError;
+use crate::config;
+
+/// Environment variable selecting the IPC transport (`unix` or `tcp`)
+pub const TRANSPORT_ENV: &str = "GHVIEW_IPC_TRANSPORT";
+
+/// Environment variable overriding the TCP listen address
+pub const TCP_ADDR_ENV: &str = "GHVIEW_IPC_ADDR";
+
+const SESSION_FILE_NAME: &str = "ipc-session.json";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IpcTransport {
+    #[cfg(unix)]
+    Unix(PathBuf),
+    Tcp(SocketAddr),
+}
+
+impl IpcTransport {
+    /// Resolve the transport from the environment, then the `[ipc]` section of config.toml.
+    ///
+    /// Unix sockets are the default where available; everywhere else (and
+    /// whenever `GHVIEW_IPC_TRANSPORT=tcp` is set) a loopback TCP listener is used.
+    pub fn from_env() -> Result<Self, IpcError> {
+        let settings = config::current();
+        let kind = std::env::var(TRANSPORT_ENV).unwrap_or_else(|_| settings.ipc.transport.clone());
+        match kind.as_str() {
+            #[cfg(unix)]
+            "unix" => Ok(IpcTransport::Unix(settings.ipc.socket_path.clone())),
+            "tcp" => Self::tcp_from_env(&settings.ipc.tcp_addr),
+            other => Err(IpcError::UnsupportedTransport(other.to_string())),
+        }
+    }
+
+    fn tcp_from_env(default_addr: &str) -> Result<Self, IpcError> {
+        let addr = std::env::var(TCP_ADDR_ENV).unwrap_or_else(|_| default_addr.to_string());
+        let addr: SocketAddr = addr.parse().map_err(|_| IpcError::InvalidAddress(addr))?;
+        if !addr.ip().is_loopback() {
+            return Err(IpcError::NotLoopback(addr));
+        }
+        Ok(IpcTransport::Tcp(addr))
+    }
+}
+
+/// Connection details published for clients of the TCP transport
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcSession {
+    pub addr: SocketAddr,
+    pub token: String,
+}
+
+/// The IPC session file in the config directory, shared by the app and its clients
+pub fn session_path() -> Result<PathBuf, IpcError> {
+    Ok(config::config_dir()?.join(SESSION_FILE_NAME))
+}
+
+/// Generate a random per-session auth token
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Write the session file readable only by the current user
+pub fn write_session_file(session: &IpcSession) -> Result<PathBuf, IpcError> {
+    let path = session_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Remove any stale file first so the new one is created with fresh permissions
+    if path.exists() {
+        fs::remove_file(&path)?;
+    }
+
+    let json = serde_json::to_string_pretty(session)?;
+
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&path)?;
+        file.write_all(json.as_bytes())?;
+    }
+
+    #[cfg(not(unix))]
+    fs::write(&path, json)?;
+
+    Ok(path)
+}
+
+/// Read the session file written by a running ghview
+pub fn read_session_file() -> Result<IpcSession, IpcError> {
+    let content = fs::read_to_string(session_path()?)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Compare tokens without short-circuiting on the first differing byte
+pub fn tokens_match(expected: &str, provided: &str) -> bool {
+    let expected = expected.as_bytes();
+    let provided = provided.as_bytes();
+    if expected.len() != provided.len() {
+        return false;
+    }
+    expected
+        .iter()
+        .zip(provided)
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::IsolatedEnv;
+    use serial_test::serial;
+    use std::env;
+
+    #[test]
+    fn test_generate_token_is_random_hex() {
+        let a = generate_token();
+        let b = generate_token();
+        assert_eq!(a.len(), 64);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("abc123", "abc123"));
+        assert!(!tokens_match("abc123", "abc124"));
+        assert!(!tokens_match("abc123", "abc"));
+        assert!(!tokens_match("abc123", ""));
+    }
+
+    #[test]
+    #[serial]
+    fn test_from_env_tcp() {
+        env::set_var(TRANSPORT_ENV, "tcp");
+        env::set_var(TCP_ADDR_ENV, "127.0.0.1:4567");
+        let transport = IpcTransport::from_env().unwrap();
+        env::remove_var(TRANSPORT_ENV);
+        env::remove_var(TCP_ADDR_ENV);
+        assert_eq!(
+            transport,
+            IpcTransport::Tcp("127.0.0.1:4567".parse().unwrap())
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_from_env_rejects_non_loopback() {
+        env::set_var(TRANSPORT_ENV, "tcp");
+        env::set_var(TCP_ADDR_ENV, "0.0.0.0:4567");
+        let result = IpcTransport::from_env();
+        env::remove_var(TRANSPORT_ENV);
+        env::remove_var(TCP_ADDR_ENV);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_write_session_file() {
+        let env = IsolatedEnv::new();
+
+        let session = IpcSession {
+            addr: "127.0.0.1:4567".parse().unwrap(),
+            token: "secret".to_string(),
+        };
+        let path = write_session_file(&session).unwrap();
+        let loaded = read_session_file().unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        assert!(path.starts_with(&env.home));
+        assert_eq!(loaded.token, "secret");
+        assert_eq!(loaded.addr.port(), 4567);
+    }
+}
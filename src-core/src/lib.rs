@@ -0,0 +1,24 @@
+//! GitHub, auth and credential logic behind ghview, with a plain async API. The app
+//! exposes it as Tauri commands; the CLI and the MCP server call it directly.
+
+pub mod actions;
+pub mod audit;
+pub mod auth;
+pub mod auth_provider;
+pub mod config;
+pub mod credential;
+pub mod credential_store;
+pub mod debug;
+pub mod device_flow;
+pub mod export;
+pub mod filters;
+pub mod github;
+pub mod http;
+pub mod ipc;
+pub mod logging;
+pub mod models;
+pub mod sla;
+pub mod stats;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+pub mod view;
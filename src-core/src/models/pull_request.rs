@@ -79,4 +79,42 @@ pub struct PullRequestWithChecks {
     #[serde(flatten)]
     pub pull_request: PullRequest,
     pub check_status: Option<CheckStatus>,
+    /// Outstanding review request of the current user, measured against `[sla]`
+    #[serde(default)]
+    pub review_sla: Option<ReviewSla>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SlaStatus {
+    Ok,
+    Warning,
+    Breached,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewSla {
+    /// Latest `review_requested` timeline event for the current user
+    pub requested_at: DateTime<Utc>,
+    pub waiting_secs: i64,
+    pub status: SlaStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedFile {
+    pub filename: String,
+    pub status: String,
+    pub additions: i32,
+    pub deletions: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestDetails {
+    #[serde(flatten)]
+    pub pull_request: PullRequestWithChecks,
+    pub body: Option<String>,
+    pub additions: i32,
+    pub deletions: i32,
+    pub changed_files: i32,
+    pub files: Vec<ChangedFile>,
 }
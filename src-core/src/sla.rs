@@ -0,0 +1,498 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use crate::auth::fetch_login;
+use crate::config::{self, SlaSettings};
+use crate::github::{review_requested_prs, GitHubError};
+use crate::http::{api_url, HttpClient, HttpResponse};
+use crate::models::{PullRequestWithChecks, ReviewSla, SlaStatus};
+
+const TIMELINE_PAGE_SIZE: usize = 100;
+
+/// Outstanding request times by timeline URL and viewer, with the PR's `updated_at`
+/// they were read at. Requests and reviews bump `updated_at`, so an unchanged PR
+/// needs no timeline requests.
+type RequestCache = HashMap<(String, Option<String>), (DateTime<Utc>, Option<DateTime<Utc>>)>;
+
+static REQUESTED_AT: LazyLock<Mutex<RequestCache>> = LazyLock::new(Default::default);
+
+/// Forget cached request times, e.g. when a test points the API at a new server
+#[cfg(any(test, feature = "test-support"))]
+pub(crate) fn clear_cache() {
+    REQUESTED_AT.lock().unwrap().clear();
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct TimelineLogin {
+    pub(crate) login: String,
+}
+
+/// The parts of an issue timeline event needed to track review requests
+#[derive(Debug, Deserialize)]
+pub(crate) struct TimelineEvent {
+    pub(crate) event: Option<String>,
+    pub(crate) created_at: Option<String>,
+    pub(crate) requested_reviewer: Option<TimelineLogin>,
+    pub(crate) user: Option<TimelineLogin>,
+}
+
+pub(crate) fn parse_time(value: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value?)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// When `login`'s review was last requested and is still outstanding. Requests to a
+/// team never name the user, so when none names `login` the latest request of any
+/// kind is used instead.
+fn requested_at(events: &[TimelineEvent], login: Option<&str>) -> Option<DateTime<Utc>> {
+    let is_me = |user: &Option<TimelineLogin>| matches!((user, login), (Some(user), Some(login)) if user.login.eq_ignore_ascii_case(login));
+    let mut mine = None;
+    let mut named_me = false;
+    let mut latest_any = None;
+
+    for event in events {
+        match event.event.as_deref() {
+            Some("review_requested") => {
+                let at = parse_time(event.created_at.as_deref());
+                latest_any = at.or(latest_any);
+                if is_me(&event.requested_reviewer) {
+                    mine = at;
+                    named_me = true;
+                }
+            }
+            Some("review_request_removed") if is_me(&event.requested_reviewer) => mine = None,
+            // Submitting a review answers the request
+            Some("reviewed") if is_me(&event.user) => {
+                mine = None;
+                named_me = false;
+                latest_any = None;
+            }
+            _ => {}
+        }
+    }
+    if named_me {
+        mine
+    } else {
+        latest_any
+    }
+}
+
+pub fn evaluate(requested_at: DateTime<Utc>, now: DateTime<Utc>, sla: &SlaSettings) -> ReviewSla {
+    let waiting = now - requested_at;
+    let status = if waiting >= Duration::hours(sla.breach_hours.into()) {
+        SlaStatus::Breached
+    } else if waiting >= Duration::hours(sla.warning_hours.into()) {
+        SlaStatus::Warning
+    } else {
+        SlaStatus::Ok
+    };
+    ReviewSla {
+        requested_at,
+        waiting_secs: waiting.num_seconds().max(0),
+        status,
+    }
+}
+
+/// Whether `requested_at` ignores everything before `event`: a review by `login`
+/// answers earlier requests and a request naming them replaces earlier ones.
+/// Without a login, any request is the latest one so far.
+fn settles(event: &TimelineEvent, login: Option<&str>) -> bool {
+    let names_login = |user: &Option<TimelineLogin>| match (user, login) {
+        (Some(user), Some(login)) => user.login.eq_ignore_ascii_case(login),
+        _ => false,
+    };
+    match event.event.as_deref() {
+        Some("review_requested") => login.is_none() || names_login(&event.requested_reviewer),
+        Some("reviewed") => names_login(&event.user),
+        _ => false,
+    }
+}
+
+async fn fetch_timeline_page(
+    client: &HttpClient,
+    token: &str,
+    url: &str,
+    page: u32,
+) -> Result<HttpResponse, GitHubError> {
+    client
+        .github_get(url, token)
+        .query(&[
+            ("per_page", TIMELINE_PAGE_SIZE.to_string()),
+            ("page", page.to_string()),
+        ])
+        .fetch("Failed to fetch timeline")
+        .await
+}
+
+/// A PR's whole timeline, oldest first
+pub(crate) async fn fetch_timeline(
+    client: &HttpClient,
+    token: &str,
+    full_name: &str,
+    number: i32,
+) -> Result<Vec<TimelineEvent>, GitHubError> {
+    let url = api_url(&format!("/repos/{}/issues/{}/timeline", full_name, number));
+    let first = fetch_timeline_page(client, token, &url, 1).await?;
+    let last_page = first.last_page().unwrap_or(1);
+    let mut events: Vec<TimelineEvent> = first.json()?;
+    for page in 2..=last_page {
+        let page_events: Vec<TimelineEvent> = fetch_timeline_page(client, token, &url, page)
+            .await?
+            .json()?;
+        events.extend(page_events);
+    }
+    Ok(events)
+}
+
+/// As much of the end of a PR's timeline as `requested_at` needs for `login`. GitHub
+/// lists timelines oldest first, so after the first page the rest are read backwards
+/// from the `rel="last"` one until an event settles the outcome.
+async fn fetch_recent_timeline(
+    client: &HttpClient,
+    token: &str,
+    url: &str,
+    login: Option<&str>,
+) -> Result<Vec<TimelineEvent>, GitHubError> {
+    let first = fetch_timeline_page(client, token, url, 1).await?;
+    let last_page = first.last_page().unwrap_or(1);
+    let mut first_events: Vec<TimelineEvent> = first.json()?;
+
+    let mut events = Vec::new();
+    for page in (2..=last_page).rev() {
+        let mut page_events: Vec<TimelineEvent> = fetch_timeline_page(client, token, url, page)
+            .await?
+            .json()?;
+        let settled = page_events.iter().any(|event| settles(event, login));
+        page_events.append(&mut events);
+        events = page_events;
+        if settled {
+            return Ok(events);
+        }
+    }
+    first_events.append(&mut events);
+    Ok(first_events)
+}
+
+/// The SLA state of a PR last updated at `updated_at`, or `None` when tracking is
+/// off, nothing is outstanding or the timeline could not be fetched
+pub(crate) async fn fetch_review_sla(
+    client: &HttpClient,
+    token: &str,
+    full_name: &str,
+    number: i32,
+    updated_at: DateTime<Utc>,
+    login: Option<&str>,
+) -> Option<ReviewSla> {
+    let settings = config::current();
+    if !settings.sla.enabled {
+        return None;
+    }
+    let url = api_url(&format!("/repos/{}/issues/{}/timeline", full_name, number));
+    let key = (url, login.map(str::to_string));
+    let cached = REQUESTED_AT.lock().unwrap().get(&key).copied();
+    let at = match cached {
+        Some((read_at, at)) if read_at == updated_at => at,
+        _ => match fetch_recent_timeline(client, token, &key.0, login).await {
+            Ok(events) => {
+                let at = requested_at(&events, login);
+                REQUESTED_AT.lock().unwrap().insert(key, (updated_at, at));
+                at
+            }
+            Err(e) => {
+                tracing::debug!("No review SLA for {}#{}: {}", full_name, number, e);
+                return None;
+            }
+        },
+    };
+    at.map(|at| evaluate(at, Utc::now(), &settings.sla))
+}
+
+/// The viewer's login, used to pick their own review requests out of timelines
+pub(crate) async fn fetch_viewer_login(client: &HttpClient, token: &str) -> Option<String> {
+    if !config::current().sla.enabled {
+        return None;
+    }
+    fetch_login(client, token)
+        .await
+        .inspect_err(|e| tracing::debug!("Could not look up the current user: {}", e))
+        .ok()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RepositorySla {
+    pub repository: String,
+    pub ok: usize,
+    pub warning: usize,
+    pub breached: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SlaSummary {
+    pub warning_hours: u32,
+    pub breach_hours: u32,
+    pub ok: usize,
+    pub warning: usize,
+    pub breached: usize,
+    /// PRs without an outstanding review request
+    pub untracked: usize,
+    /// Most breached first
+    pub repositories: Vec<RepositorySla>,
+}
+
+pub fn summarize(prs: &[PullRequestWithChecks], sla: &SlaSettings) -> SlaSummary {
+    let mut summary = SlaSummary {
+        warning_hours: sla.warning_hours,
+        breach_hours: sla.breach_hours,
+        ..Default::default()
+    };
+
+    for pr in prs {
+        let Some(review_sla) = &pr.review_sla else {
+            summary.untracked += 1;
+            continue;
+        };
+        let name = &pr.pull_request.repository.full_name;
+        let index = match summary
+            .repositories
+            .iter()
+            .position(|r| &r.repository == name)
+        {
+            Some(index) => index,
+            None => {
+                summary.repositories.push(RepositorySla {
+                    repository: name.clone(),
+                    ..Default::default()
+                });
+                summary.repositories.len() - 1
+            }
+        };
+        let repo = &mut summary.repositories[index];
+        match review_sla.status {
+            SlaStatus::Ok => {
+                summary.ok += 1;
+                repo.ok += 1;
+            }
+            SlaStatus::Warning => {
+                summary.warning += 1;
+                repo.warning += 1;
+            }
+            SlaStatus::Breached => {
+                summary.breached += 1;
+                repo.breached += 1;
+            }
+        }
+    }
+
+    summary.repositories.sort_by(|a, b| {
+        b.breached
+            .cmp(&a.breached)
+            .then(b.warning.cmp(&a.warning))
+            .then_with(|| a.repository.cmp(&b.repository))
+    });
+    summary
+}
+
+/// Review SLA counts over the (filtered) PR list, overall and per repository
+pub async fn fetch_sla_summary() -> Result<SlaSummary, GitHubError> {
+    let prs = review_requested_prs().await?;
+    Ok(summarize(&prs, &config::current().sla))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FakeGitHubEnv;
+    use ghview_fake_github::{FakePull, Scenario};
+    use serial_test::serial;
+
+    fn event(json: serde_json::Value) -> TimelineEvent {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn requested(login: &str, at: &str) -> TimelineEvent {
+        event(serde_json::json!({
+            "event": "review_requested",
+            "created_at": at,
+            "requested_reviewer": {"login": login}
+        }))
+    }
+
+    fn time(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_requested_at_uses_latest_request_for_viewer() {
+        let events = vec![
+            requested("octocat", "2026-10-01T00:00:00Z"),
+            event(serde_json::json!({
+                "event": "review_request_removed",
+                "created_at": "2026-10-02T00:00:00Z",
+                "requested_reviewer": {"login": "octocat"}
+            })),
+            requested("monalisa", "2026-10-03T00:00:00Z"),
+            requested("OctoCat", "2026-10-04T00:00:00Z"),
+            event(serde_json::json!({"event": "commented", "created_at": "2026-10-05T00:00:00Z"})),
+        ];
+        assert_eq!(
+            requested_at(&events, Some("octocat")),
+            Some(time("2026-10-04T00:00:00Z"))
+        );
+        assert_eq!(requested_at(&events[..2], Some("octocat")), None);
+    }
+
+    #[test]
+    fn test_requested_at_falls_back_to_team_requests_and_clears_on_review() {
+        let team = event(serde_json::json!({
+            "event": "review_requested",
+            "created_at": "2026-10-01T00:00:00Z",
+            "requested_team": {"slug": "reviewers"}
+        }));
+        assert_eq!(
+            requested_at(std::slice::from_ref(&team), Some("octocat")),
+            Some(time("2026-10-01T00:00:00Z"))
+        );
+
+        let reviewed = event(serde_json::json!({
+            "event": "reviewed",
+            "submitted_at": "2026-10-02T00:00:00Z",
+            "user": {"login": "octocat"}
+        }));
+        assert_eq!(requested_at(&[team, reviewed], Some("octocat")), None);
+    }
+
+    #[test]
+    fn test_evaluate_thresholds() {
+        let sla = SlaSettings::default();
+        let requested = time("2026-10-17T00:00:00Z");
+        let status = |now: &str| evaluate(requested, time(now), &sla).status;
+
+        assert_eq!(status("2026-10-17T05:00:00Z"), SlaStatus::Ok);
+        assert_eq!(status("2026-10-17T20:00:00Z"), SlaStatus::Warning);
+        assert_eq!(status("2026-10-18T00:00:00Z"), SlaStatus::Breached);
+        assert_eq!(
+            evaluate(requested, time("2026-10-17T01:00:00Z"), &sla).waiting_secs,
+            3600
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_re_request_on_last_timeline_page() {
+        let hours_ago = |hours: i64| (Utc::now() - Duration::hours(hours)).to_rfc3339();
+        let pull = FakePull::new("octo-org/api", 1, "Long-running")
+            .review_requested()
+            .review_requested_at("octocat", &hours_ago(50))
+            .timeline_event("reviewed", "octocat", &hours_ago(45));
+        // Seven pages of 100, the re-request being the last event
+        let pull = (0..650)
+            .fold(pull, |pull, _| {
+                pull.timeline_event("commented", "monalisa", &hours_ago(40))
+            })
+            .review_requested_at("octocat", &hours_ago(2));
+        let fake = FakeGitHubEnv::start(
+            Scenario::new()
+                .token("gho_test", "octocat", &["repo"])
+                .pull(pull),
+        )
+        .await
+        .with_env_token("gho_test");
+
+        let prs = review_requested_prs().await.unwrap();
+        let review_sla = prs[0].review_sla.as_ref().unwrap();
+        assert_eq!(review_sla.status, SlaStatus::Ok);
+        assert!(review_sla.waiting_secs < 3 * 3600);
+
+        // The first page for the page count, then the last one settles it
+        let pages: Vec<Option<String>> = fake
+            .server
+            .requests()
+            .into_iter()
+            .filter(|r| r.path.ends_with("/timeline"))
+            .map(|r| r.query)
+            .collect();
+        assert_eq!(
+            pages,
+            vec![
+                Some("per_page=100&page=1".to_string()),
+                Some("per_page=100&page=7".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_sla_summary_against_fake_server() {
+        let hours_ago = |hours: i64| (Utc::now() - Duration::hours(hours)).to_rfc3339();
+        let scenario = Scenario::new()
+            .token("gho_test", "octocat", &["repo"])
+            .pull(
+                FakePull::new("octo-org/api", 1, "Stale")
+                    .review_requested()
+                    .review_requested_at("octocat", &hours_ago(30)),
+            )
+            .pull(
+                FakePull::new("octo-org/api", 2, "Fresh")
+                    .review_requested()
+                    .review_requested_at("monalisa", &hours_ago(40))
+                    .review_requested_at("octocat", &hours_ago(1)),
+            )
+            .pull(
+                FakePull::new("octo-org/web", 3, "Nearly due")
+                    .review_requested()
+                    .review_requested_at("octocat", &hours_ago(20)),
+            )
+            .pull(FakePull::new("octo-org/web", 4, "Assigned").assigned());
+        let fake = FakeGitHubEnv::start(scenario)
+            .await
+            .with_env_token("gho_test");
+
+        let prs = review_requested_prs().await.unwrap();
+        let stale = prs.iter().find(|p| p.pull_request.number == 1).unwrap();
+        let review_sla = stale.review_sla.as_ref().unwrap();
+        assert_eq!(review_sla.status, SlaStatus::Breached);
+        assert!(review_sla.waiting_secs >= 30 * 3600);
+
+        // Unchanged PRs reuse the request times read above
+        let timeline_requests = |fake: &FakeGitHubEnv| {
+            fake.server
+                .requests()
+                .iter()
+                .filter(|r| r.path.ends_with("/timeline"))
+                .count()
+        };
+        let fetched = timeline_requests(&fake);
+
+        let summary = fetch_sla_summary().await.unwrap();
+        assert_eq!(
+            (
+                summary.ok,
+                summary.warning,
+                summary.breached,
+                summary.untracked
+            ),
+            (1, 1, 1, 1)
+        );
+        assert_eq!(
+            summary.repositories,
+            vec![
+                RepositorySla {
+                    repository: "octo-org/api".to_string(),
+                    ok: 1,
+                    warning: 0,
+                    breached: 1,
+                },
+                RepositorySla {
+                    repository: "octo-org/web".to_string(),
+                    ok: 0,
+                    warning: 1,
+                    breached: 0,
+                },
+            ]
+        );
+        assert_eq!(timeline_requests(&fake), fetched);
+    }
+}
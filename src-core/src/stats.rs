@@ -0,0 +1,589 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+use crate::auth::{fetch_login, get_token, AuthError};
+use crate::config::{self, ConfigError};
+use crate::export::csv_row;
+use crate::github::GitHubError;
+use crate::http::{api_url, HttpClient, HttpError};
+use crate::sla::{fetch_timeline, parse_time, TimelineEvent};
+
+/// Days covered when no range is given: the last week, including today
+const DEFAULT_RANGE_DAYS: i64 = 7;
+const BUSIEST_REPOSITORIES: usize = 5;
+/// Search pages of 100 reviewed PRs fetched per range
+const MAX_SEARCH_PAGES: u32 = 10;
+const PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Error)]
+pub enum StatsError {
+    #[error(transparent)]
+    GitHub(#[from] GitHubError),
+    #[error("Invalid date range: {0}")]
+    InvalidRange(String),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("Failed to write export: {0}")]
+    Io(#[from] io::Error),
+}
+
+impl Serialize for StatsError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl From<AuthError> for StatsError {
+    fn from(e: AuthError) -> Self {
+        StatsError::GitHub(e.into())
+    }
+}
+
+impl From<HttpError> for StatsError {
+    fn from(e: HttpError) -> Self {
+        StatsError::GitHub(e.into())
+    }
+}
+
+/// Inclusive range of days, in UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct StatsRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl StatsRange {
+    /// Parse `YYYY-MM-DD` bounds; `to` defaults to today and `from` to a week before it
+    pub fn parse(
+        from: Option<&str>,
+        to: Option<&str>,
+        today: NaiveDate,
+    ) -> Result<Self, StatsError> {
+        let parse = |value: &str| {
+            NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| {
+                StatsError::InvalidRange(format!("expected YYYY-MM-DD, got {:?}", value))
+            })
+        };
+        let to = to.map(parse).transpose()?.unwrap_or(today);
+        let from = from
+            .map(parse)
+            .transpose()?
+            .unwrap_or(to - Duration::days(DEFAULT_RANGE_DAYS - 1));
+        if from > to {
+            return Err(StatsError::InvalidRange(format!(
+                "{} is after {}",
+                from, to
+            )));
+        }
+        Ok(Self { from, to })
+    }
+
+    fn contains(&self, at: DateTime<Utc>) -> bool {
+        (self.from..=self.to).contains(&at.date_naive())
+    }
+}
+
+/// One review the user submitted
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewRecord {
+    pub submitted_at: DateTime<Utc>,
+    pub repository: String,
+    pub number: i32,
+    pub title: String,
+    pub html_url: String,
+    /// `APPROVED`, `CHANGES_REQUESTED`, `COMMENTED` or `DISMISSED`
+    pub state: String,
+    /// Set on the user's first review of the PR: seconds since their review was
+    /// requested, or since the PR was opened if it never was
+    pub time_to_first_review_secs: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RepositoryReviews {
+    pub repository: String,
+    pub reviews: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeeklyReviews {
+    /// Monday of the week
+    pub week_start: NaiveDate,
+    pub reviews: usize,
+    pub pull_requests: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewStats {
+    pub login: String,
+    pub range: StatsRange,
+    pub reviews_submitted: usize,
+    pub pull_requests_reviewed: usize,
+    pub pull_requests_approved: usize,
+    pub pull_requests_changes_requested: usize,
+    pub median_time_to_first_review_secs: Option<i64>,
+    pub busiest_repositories: Vec<RepositoryReviews>,
+    pub weeks: Vec<WeeklyReviews>,
+    pub reviews: Vec<ReviewRecord>,
+}
+
+fn median(values: &mut [i64]) -> Option<i64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2
+    } else {
+        values[mid]
+    })
+}
+
+fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(day.weekday().num_days_from_monday().into())
+}
+
+/// Aggregate review records that already fall inside `range`
+pub fn compute_stats(
+    login: &str,
+    range: StatsRange,
+    mut reviews: Vec<ReviewRecord>,
+) -> ReviewStats {
+    reviews.sort_by_key(|r| r.submitted_at);
+    let pr_key = |r: &ReviewRecord| (r.repository.clone(), r.number);
+
+    let distinct = |state: Option<&str>| {
+        let mut prs: Vec<_> = reviews
+            .iter()
+            .filter(|r| state.is_none_or(|s| r.state == s))
+            .map(pr_key)
+            .collect();
+        prs.sort();
+        prs.dedup();
+        prs.len()
+    };
+
+    let mut per_repo: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut per_week: BTreeMap<NaiveDate, (usize, Vec<(String, i32)>)> = BTreeMap::new();
+    for review in &reviews {
+        *per_repo.entry(&review.repository).or_default() += 1;
+        let week = per_week
+            .entry(week_start(review.submitted_at.date_naive()))
+            .or_default();
+        week.0 += 1;
+        week.1.push(pr_key(review));
+    }
+    let mut busiest: Vec<RepositoryReviews> = per_repo
+        .into_iter()
+        .map(|(repository, reviews)| RepositoryReviews {
+            repository: repository.to_string(),
+            reviews,
+        })
+        .collect();
+    // Stable, so ties stay alphabetical
+    busiest.sort_by_key(|r| std::cmp::Reverse(r.reviews));
+    busiest.truncate(BUSIEST_REPOSITORIES);
+
+    let weeks = per_week
+        .into_iter()
+        .map(|(week_start, (count, mut prs))| {
+            prs.sort();
+            prs.dedup();
+            WeeklyReviews {
+                week_start,
+                reviews: count,
+                pull_requests: prs.len(),
+            }
+        })
+        .collect();
+
+    let mut waits: Vec<i64> = reviews
+        .iter()
+        .filter_map(|r| r.time_to_first_review_secs)
+        .collect();
+
+    ReviewStats {
+        login: login.to_string(),
+        range,
+        reviews_submitted: reviews.len(),
+        pull_requests_reviewed: distinct(None),
+        pull_requests_approved: distinct(Some("APPROVED")),
+        pull_requests_changes_requested: distinct(Some("CHANGES_REQUESTED")),
+        median_time_to_first_review_secs: median(&mut waits),
+        busiest_repositories: busiest,
+        weeks,
+        reviews,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewedSearchResponse {
+    items: Vec<ReviewedItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewedItem {
+    number: i32,
+    title: String,
+    html_url: String,
+    created_at: String,
+    repository_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiReviewUser {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiReview {
+    user: Option<ApiReviewUser>,
+    state: String,
+    submitted_at: Option<String>,
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(
+    client: &HttpClient,
+    token: &str,
+    url: &str,
+    query: &[(&str, String)],
+) -> Result<T, GitHubError> {
+    Ok(client
+        .github_get(url, token)
+        .query(query)
+        .fetch("Failed to fetch review data")
+        .await?
+        .json()?)
+}
+
+/// PRs the user reviewed that were updated since the start of `range`
+async fn search_reviewed(
+    client: &HttpClient,
+    token: &str,
+    login: &str,
+    range: StatsRange,
+) -> Result<Vec<ReviewedItem>, GitHubError> {
+    let url = api_url("/search/issues");
+    let query = format!("is:pr reviewed-by:{} updated:>={}", login, range.from);
+    let mut items = Vec::new();
+    for page in 1..=MAX_SEARCH_PAGES {
+        let response: ReviewedSearchResponse = get_json(
+            client,
+            token,
+            &url,
+            &[
+                ("q", query.clone()),
+                ("per_page", PAGE_SIZE.to_string()),
+                ("page", page.to_string()),
+            ],
+        )
+        .await?;
+        let last_page = response.items.len() < PAGE_SIZE;
+        items.extend(response.items);
+        if last_page {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+/// The user's reviews of one PR that fall inside `range`
+async fn reviews_of(
+    client: &HttpClient,
+    token: &str,
+    login: &str,
+    item: &ReviewedItem,
+    range: StatsRange,
+) -> Result<Vec<ReviewRecord>, GitHubError> {
+    let full_name = item
+        .repository_url
+        .split("/repos/")
+        .nth(1)
+        .unwrap_or_default()
+        .to_string();
+    let url = api_url(&format!(
+        "/repos/{}/pulls/{}/reviews",
+        full_name, item.number
+    ));
+    let reviews: Vec<ApiReview> =
+        get_json(client, token, &url, &[("per_page", PAGE_SIZE.to_string())]).await?;
+
+    let mut mine: Vec<(DateTime<Utc>, String)> = reviews
+        .into_iter()
+        .filter(|r| r.state != "PENDING")
+        .filter(|r| {
+            r.user
+                .as_ref()
+                .is_some_and(|u| u.login.eq_ignore_ascii_case(login))
+        })
+        .filter_map(|r| Some((parse_time(r.submitted_at.as_deref())?, r.state)))
+        .collect();
+    mine.sort_by_key(|(at, _)| *at);
+    let Some((first_at, _)) = mine.first().cloned() else {
+        return Ok(Vec::new());
+    };
+
+    // Only worth a timeline request when the first review counts towards this range
+    let time_to_first_review = if range.contains(first_at) {
+        let events = fetch_timeline(client, token, &full_name, item.number)
+            .await
+            .unwrap_or_default();
+        let started =
+            first_request(&events, login, first_at).or_else(|| parse_time(Some(&item.created_at)));
+        started.map(|started| (first_at - started).num_seconds().max(0))
+    } else {
+        None
+    };
+
+    Ok(mine
+        .into_iter()
+        .filter(|(at, _)| range.contains(*at))
+        .map(|(submitted_at, state)| ReviewRecord {
+            submitted_at,
+            repository: full_name.clone(),
+            number: item.number,
+            title: item.title.clone(),
+            html_url: item.html_url.clone(),
+            time_to_first_review_secs: (submitted_at == first_at)
+                .then_some(time_to_first_review)
+                .flatten(),
+            state,
+        })
+        .collect())
+}
+
+/// Earliest request naming `login` before their first review
+fn first_request(
+    events: &[TimelineEvent],
+    login: &str,
+    first_review: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    events
+        .iter()
+        .filter(|e| e.event.as_deref() == Some("review_requested"))
+        .filter(|e| {
+            e.requested_reviewer
+                .as_ref()
+                .is_some_and(|r| r.login.eq_ignore_ascii_case(login))
+        })
+        .filter_map(|e| parse_time(e.created_at.as_deref()))
+        .filter(|at| *at <= first_review)
+        .min()
+}
+
+/// Review statistics from GitHub review data. ghview keeps no local history of
+/// past review queues, so everything comes from the API.
+pub async fn review_stats(range: StatsRange) -> Result<ReviewStats, StatsError> {
+    let token = get_token().await?;
+    let client = HttpClient::from_env();
+    let login = fetch_login(&client, &token).await?;
+
+    let mut reviews = Vec::new();
+    for item in search_reviewed(&client, &token, &login, range).await? {
+        reviews.extend(reviews_of(&client, &token, &login, &item, range).await?);
+    }
+    Ok(compute_stats(&login, range, reviews))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsFormat {
+    Csv,
+    Json,
+}
+
+/// One row per review, for spreadsheets; the aggregates are in the JSON export
+pub fn stats_to_csv(stats: &ReviewStats) -> String {
+    let mut csv =
+        String::from("submitted_at,repository,number,title,state,time_to_first_review_secs,url\n");
+    for review in &stats.reviews {
+        let row = [
+            review.submitted_at.to_rfc3339(),
+            review.repository.clone(),
+            review.number.to_string(),
+            review.title.clone(),
+            review.state.clone(),
+            review
+                .time_to_first_review_secs
+                .map(|secs| secs.to_string())
+                .unwrap_or_default(),
+            review.html_url.clone(),
+        ];
+        csv.push_str(&csv_row(&row));
+    }
+    csv
+}
+
+pub async fn get_review_stats(
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<ReviewStats, StatsError> {
+    let range = StatsRange::parse(from.as_deref(), to.as_deref(), Utc::now().date_naive())?;
+    review_stats(range).await
+}
+
+/// Write review stats for the range to `exports/` in the config directory and return the path
+pub async fn export_review_stats(
+    from: Option<String>,
+    to: Option<String>,
+    format: StatsFormat,
+) -> Result<String, StatsError> {
+    let range = StatsRange::parse(from.as_deref(), to.as_deref(), Utc::now().date_naive())?;
+    let stats = review_stats(range).await?;
+    let (content, extension) = match format {
+        StatsFormat::Csv => (stats_to_csv(&stats), "csv"),
+        StatsFormat::Json => (
+            serde_json::to_string_pretty(&stats).map_err(io::Error::other)?,
+            "json",
+        ),
+    };
+
+    let dir: PathBuf = config::config_dir()?.join("exports");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!(
+        "ghview-review-stats-{}-{}.{}",
+        range.from, range.to, extension
+    ));
+    std::fs::write(&path, content)?;
+    tracing::info!(path = %path.display(), "Exported review stats");
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FakeGitHubEnv;
+    use ghview_fake_github::{FakePull, Scenario};
+    use serial_test::serial;
+
+    fn day(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    fn review(at: &str, repo: &str, number: i32, state: &str, wait: Option<i64>) -> ReviewRecord {
+        ReviewRecord {
+            submitted_at: at.parse().unwrap(),
+            repository: repo.to_string(),
+            number,
+            title: format!("PR {}", number),
+            html_url: String::new(),
+            state: state.to_string(),
+            time_to_first_review_secs: wait,
+        }
+    }
+
+    #[test]
+    fn test_range_parsing() {
+        let today = day("2026-10-18");
+        assert_eq!(
+            StatsRange::parse(None, None, today).unwrap(),
+            StatsRange {
+                from: day("2026-10-12"),
+                to: today
+            }
+        );
+        assert_eq!(
+            StatsRange::parse(Some("2026-10-01"), Some("2026-10-05"), today)
+                .unwrap()
+                .from,
+            day("2026-10-01")
+        );
+        assert!(StatsRange::parse(Some("2026-10-05"), Some("2026-10-01"), today).is_err());
+        assert!(StatsRange::parse(Some("last week"), None, today).is_err());
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let range =
+            StatsRange::parse(Some("2026-10-05"), Some("2026-10-18"), day("2026-10-18")).unwrap();
+        let stats = compute_stats(
+            "octocat",
+            range,
+            vec![
+                review(
+                    "2026-10-06T10:00:00Z",
+                    "o/api",
+                    1,
+                    "CHANGES_REQUESTED",
+                    Some(3600),
+                ),
+                review("2026-10-07T10:00:00Z", "o/api", 1, "APPROVED", None),
+                review("2026-10-13T10:00:00Z", "o/web", 2, "APPROVED", Some(7200)),
+                review("2026-10-14T10:00:00Z", "o/api", 3, "COMMENTED", Some(600)),
+            ],
+        );
+
+        assert_eq!(stats.reviews_submitted, 4);
+        assert_eq!(stats.pull_requests_reviewed, 3);
+        assert_eq!(stats.pull_requests_approved, 2);
+        assert_eq!(stats.pull_requests_changes_requested, 1);
+        assert_eq!(stats.median_time_to_first_review_secs, Some(3600));
+        assert_eq!(stats.busiest_repositories[0].repository, "o/api");
+        assert_eq!(stats.busiest_repositories[0].reviews, 3);
+        let weeks: Vec<_> = stats
+            .weeks
+            .iter()
+            .map(|w| (w.week_start.to_string(), w.reviews, w.pull_requests))
+            .collect();
+        assert_eq!(
+            weeks,
+            vec![
+                ("2026-10-05".to_string(), 2, 1),
+                ("2026-10-12".to_string(), 2, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csv_escapes_fields() {
+        let range = StatsRange::parse(None, None, day("2026-10-18")).unwrap();
+        let mut record = review("2026-10-14T10:00:00Z", "o/api", 3, "APPROVED", None);
+        record.title = "Fix \"quoted\", comma".to_string();
+        let csv = stats_to_csv(&compute_stats("octocat", range, vec![record]));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[1],
+            "2026-10-14T10:00:00+00:00,o/api,3,\"Fix \"\"quoted\"\", comma\",APPROVED,,"
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_review_stats_against_fake_server() {
+        let scenario = Scenario::new()
+            .token("gho_test", "octocat", &["repo"])
+            .pull(
+                FakePull::new("octo-org/api", 1, "Add endpoint")
+                    .created_at("2026-10-05T00:00:00Z")
+                    .updated_at("2026-10-08T00:00:00Z")
+                    .review_requested_at("octocat", "2026-10-06T08:00:00Z")
+                    .review_at("octocat", "CHANGES_REQUESTED", "2026-10-06T10:00:00Z")
+                    .review_at("monalisa", "APPROVED", "2026-10-06T11:00:00Z")
+                    .review_at("octocat", "APPROVED", "2026-10-07T09:00:00Z"),
+            )
+            .pull(
+                FakePull::new("octo-org/web", 2, "Old review")
+                    .updated_at("2026-09-01T00:00:00Z")
+                    .review_at("octocat", "APPROVED", "2026-09-01T00:00:00Z"),
+            );
+        let _fake = FakeGitHubEnv::start(scenario)
+            .await
+            .with_env_token("gho_test");
+
+        let range =
+            StatsRange::parse(Some("2026-10-05"), Some("2026-10-11"), day("2026-10-18")).unwrap();
+        let stats = review_stats(range).await.unwrap();
+        assert_eq!(stats.login, "octocat");
+        assert_eq!(stats.reviews_submitted, 2);
+        assert_eq!(stats.pull_requests_reviewed, 1);
+        assert_eq!(stats.median_time_to_first_review_secs, Some(2 * 3600));
+        assert_eq!(stats.reviews[1].state, "APPROVED");
+    }
+}
@@ -0,0 +1,90 @@
+//! Environment isolation for tests that run requests against the fake GitHub server
+
+use ghview_fake_github::{FakeGitHub, Scenario};
+use std::env;
+use std::path::PathBuf;
+
+use crate::auth_provider::TOKEN_ENV_VARS;
+use crate::credential_store::{PASSPHRASE_ENV, STORE_ENV};
+use crate::device_flow::OAUTH_URL_ENV;
+use crate::http::{API_URL_ENV, FIXTURES_ENV, HTTP_MODE_ENV};
+use crate::ipc::transport::{TCP_ADDR_ENV, TRANSPORT_ENV};
+
+/// Every variable that changes where tokens, credentials or responses come from
+const ISOLATED_VARS: [&str; 13] = [
+    "HOME",
+    TOKEN_ENV_VARS[0],
+    TOKEN_ENV_VARS[1],
+    "GH_CONFIG_DIR",
+    "XDG_CONFIG_HOME",
+    STORE_ENV,
+    PASSPHRASE_ENV,
+    HTTP_MODE_ENV,
+    FIXTURES_ENV,
+    API_URL_ENV,
+    OAUTH_URL_ENV,
+    TRANSPORT_ENV,
+    TCP_ADDR_ENV,
+];
+
+/// Clears `ISOLATED_VARS` and points HOME at an empty temporary directory until dropped
+pub struct IsolatedEnv {
+    original: Vec<(&'static str, Option<String>)>,
+    pub home: PathBuf,
+}
+
+impl IsolatedEnv {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        let home = env::temp_dir().join(format!("ghview_test_{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&home).unwrap();
+        let original = ISOLATED_VARS
+            .iter()
+            .map(|name| (*name, env::var(name).ok()))
+            .collect();
+        for name in ISOLATED_VARS {
+            env::remove_var(name);
+        }
+        env::set_var("HOME", &home);
+        IsolatedEnv { original, home }
+    }
+
+    pub fn set(&self, name: &str, value: &str) {
+        env::set_var(name, value);
+    }
+}
+
+impl Drop for IsolatedEnv {
+    fn drop(&mut self) {
+        for (name, value) in &self.original {
+            match value {
+                Some(value) => env::set_var(name, value),
+                None => env::remove_var(name),
+            }
+        }
+        let _ = std::fs::remove_dir_all(&self.home);
+    }
+}
+
+/// A running fake server that the API and OAuth base URLs point at
+pub struct FakeGitHubEnv {
+    pub server: FakeGitHub,
+    pub env: IsolatedEnv,
+}
+
+impl FakeGitHubEnv {
+    pub async fn start(scenario: Scenario) -> Self {
+        let env = IsolatedEnv::new();
+        let server = FakeGitHub::start(scenario).await.unwrap();
+        crate::sla::clear_cache();
+        env.set(API_URL_ENV, server.url());
+        env.set(OAUTH_URL_ENV, server.url());
+        FakeGitHubEnv { server, env }
+    }
+
+    /// Use `token` via `GH_TOKEN`
+    pub fn with_env_token(self, token: &str) -> Self {
+        self.env.set(TOKEN_ENV_VARS[0], token);
+        self
+    }
+}
@@ -0,0 +1,506 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::config;
+use crate::github::{review_requested_prs, GitHubError};
+use crate::models::{CheckState, PullRequestWithChecks};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    #[default]
+    None,
+    Repository,
+    CheckState,
+    ReviewStatus,
+    Age,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    Updated,
+    Created,
+    /// Failing, erroring, pending, unknown, then passing
+    Checks,
+    Review,
+    /// When the outstanding review request was made, oldest first; PRs with none
+    /// come last in either direction
+    Requested,
+    Repository,
+    Title,
+}
+
+impl SortField {
+    const NAMES: [(&'static str, SortField); 7] = [
+        ("updated", SortField::Updated),
+        ("created", SortField::Created),
+        ("checks", SortField::Checks),
+        ("review", SortField::Review),
+        ("requested", SortField::Requested),
+        ("repository", SortField::Repository),
+        ("title", SortField::Title),
+    ];
+
+    fn name(self) -> &'static str {
+        Self::NAMES
+            .iter()
+            .find(|(_, field)| *field == self)
+            .map_or("", |(name, _)| name)
+    }
+}
+
+/// One sort key, written `field` for ascending or `-field` for descending
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (descending, name) = match s.strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, s),
+        };
+        let field = SortField::NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, field)| *field)
+            .ok_or_else(|| {
+                let names: Vec<_> = SortField::NAMES.iter().map(|(n, _)| *n).collect();
+                format!(
+                    "unknown sort key {:?}, expected one of {}",
+                    name,
+                    names.join(", ")
+                )
+            })?;
+        Ok(SortKey { field, descending })
+    }
+}
+
+impl TryFrom<String> for SortKey {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<SortKey> for String {
+    fn from(key: SortKey) -> Self {
+        key.to_string()
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.descending {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.field.name())
+    }
+}
+
+/// Grouping and ordering of the PR list; missing fields fall back to `[view]` in config.toml
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ViewOptions {
+    #[serde(default)]
+    pub group_by: Option<GroupBy>,
+    #[serde(default)]
+    pub sort: Option<Vec<SortKey>>,
+}
+
+/// Where a PR stands from the reviewer's side; GitHub drops a reviewer from
+/// `requested_reviewers` once they submit a review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ReviewStatus {
+    ReviewRequested,
+    NoPendingReviewers,
+    Draft,
+}
+
+fn review_status(pr: &PullRequestWithChecks) -> ReviewStatus {
+    let pr = &pr.pull_request;
+    if pr.draft {
+        ReviewStatus::Draft
+    } else if pr.requested_reviewers.is_empty() {
+        ReviewStatus::NoPendingReviewers
+    } else {
+        ReviewStatus::ReviewRequested
+    }
+}
+
+/// Rank for "CI failing first"
+fn check_rank(pr: &PullRequestWithChecks) -> u8 {
+    match pr.check_status.as_ref().map(|c| &c.state) {
+        Some(CheckState::Failure) => 0,
+        Some(CheckState::Error) => 1,
+        Some(CheckState::Pending) => 2,
+        None => 3,
+        Some(CheckState::Success) => 4,
+    }
+}
+
+/// Age buckets by creation time, newest first
+const AGE_BUCKETS: [(&str, &str, i64); 3] = [
+    ("today", "Today", 1),
+    ("week", "This week", 7),
+    ("month", "This month", 30),
+];
+
+fn age_bucket(pr: &PullRequestWithChecks, now: DateTime<Utc>) -> usize {
+    let age = now - pr.pull_request.created_at;
+    AGE_BUCKETS
+        .iter()
+        .position(|(_, _, days)| age < Duration::days(*days))
+        .unwrap_or(AGE_BUCKETS.len())
+}
+
+fn requested_at(pr: &PullRequestWithChecks) -> Option<DateTime<Utc>> {
+    pr.review_sla.as_ref().map(|sla| sla.requested_at)
+}
+
+fn compare(a: &PullRequestWithChecks, b: &PullRequestWithChecks, key: SortKey) -> Ordering {
+    let (pa, pb) = (&a.pull_request, &b.pull_request);
+
+    let ordering = match key.field {
+        SortField::Updated => pa.updated_at.cmp(&pb.updated_at),
+        SortField::Created => pa.created_at.cmp(&pb.created_at),
+        SortField::Checks => check_rank(a).cmp(&check_rank(b)),
+        SortField::Review => review_status(a).cmp(&review_status(b)),
+        SortField::Requested => match (requested_at(a), requested_at(b)) {
+            (Some(ra), Some(rb)) => ra.cmp(&rb),
+            // Not reversed, so these stay last when descending
+            (Some(_), None) => return Ordering::Less,
+            (None, Some(_)) => return Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+        SortField::Repository => pa
+            .repository
+            .full_name
+            .to_lowercase()
+            .cmp(&pb.repository.full_name.to_lowercase())
+            .then(pa.number.cmp(&pb.number)),
+        SortField::Title => pa.title.to_lowercase().cmp(&pb.title.to_lowercase()),
+    };
+    if key.descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+/// Stable multi-key sort; PRs equal on every key keep their order
+pub fn sort_prs(prs: &mut [PullRequestWithChecks], keys: &[SortKey]) {
+    prs.sort_by(|a, b| {
+        keys.iter()
+            .map(|key| compare(a, b, *key))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestGroup {
+    pub key: String,
+    pub label: String,
+    pub count: usize,
+    pub pull_requests: Vec<PullRequestWithChecks>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestView {
+    pub group_by: GroupBy,
+    pub sort: Vec<SortKey>,
+    pub total: usize,
+    pub groups: Vec<PullRequestGroup>,
+}
+
+/// Sort key used to order groups, plus the group's key and label
+fn group_of(
+    pr: &PullRequestWithChecks,
+    group_by: GroupBy,
+    now: DateTime<Utc>,
+) -> (String, String, String) {
+    match group_by {
+        GroupBy::None => (String::new(), "all".into(), "All pull requests".into()),
+        GroupBy::Repository => {
+            let name = pr.pull_request.repository.full_name.clone();
+            (name.to_lowercase(), name.clone(), name)
+        }
+        GroupBy::CheckState => {
+            let (key, label) = match check_rank(pr) {
+                0 => ("failure", "Failing"),
+                1 => ("error", "Errored"),
+                2 => ("pending", "Pending"),
+                3 => ("unknown", "No checks"),
+                _ => ("success", "Passing"),
+            };
+            (check_rank(pr).to_string(), key.into(), label.into())
+        }
+        GroupBy::ReviewStatus => {
+            let status = review_status(pr);
+            let (key, label) = match status {
+                ReviewStatus::ReviewRequested => ("review_requested", "Review requested"),
+                ReviewStatus::NoPendingReviewers => {
+                    ("no_pending_reviewers", "No pending reviewers")
+                }
+                ReviewStatus::Draft => ("draft", "Draft"),
+            };
+            ((status as u8).to_string(), key.into(), label.into())
+        }
+        GroupBy::Age => {
+            let bucket = age_bucket(pr, now);
+            let (key, label) = AGE_BUCKETS
+                .get(bucket)
+                .map_or(("older", "Older"), |(key, label, _)| (*key, *label));
+            (bucket.to_string(), key.into(), label.into())
+        }
+    }
+}
+
+/// Sort `prs`, then split them into groups ordered by the grouping's natural order
+pub fn build_view(
+    mut prs: Vec<PullRequestWithChecks>,
+    group_by: GroupBy,
+    sort: &[SortKey],
+    now: DateTime<Utc>,
+) -> PullRequestView {
+    sort_prs(&mut prs, sort);
+    let total = prs.len();
+
+    let mut groups: Vec<(String, PullRequestGroup)> = Vec::new();
+    for pr in prs {
+        let (order, key, label) = group_of(&pr, group_by, now);
+        match groups.iter_mut().find(|(_, group)| group.key == key) {
+            Some((_, group)) => group.pull_requests.push(pr),
+            None => groups.push((
+                order,
+                PullRequestGroup {
+                    key,
+                    label,
+                    count: 0,
+                    pull_requests: vec![pr],
+                },
+            )),
+        }
+    }
+    groups.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    PullRequestView {
+        group_by,
+        sort: sort.to_vec(),
+        total,
+        groups: groups
+            .into_iter()
+            .map(|(_, mut group)| {
+                group.count = group.pull_requests.len();
+                group
+            })
+            .collect(),
+    }
+}
+
+/// `prs` grouped and sorted by `options` or else `[view]` in config.toml
+pub fn view_of(prs: Vec<PullRequestWithChecks>, options: ViewOptions) -> PullRequestView {
+    let settings = config::current();
+    let group_by = options.group_by.unwrap_or(settings.view.group_by);
+    let sort = options.sort.unwrap_or_else(|| settings.view.sort.clone());
+    build_view(prs, group_by, &sort, Utc::now())
+}
+
+/// The filtered PR list, grouped and sorted by `options` or else `[view]` in config.toml
+pub async fn pr_view(options: ViewOptions) -> Result<PullRequestView, GitHubError> {
+    Ok(view_of(review_requested_prs().await?, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        CheckStatus, PullRequest, PullRequestState, Repository, ReviewSla, SlaStatus, User,
+    };
+
+    fn now() -> DateTime<Utc> {
+        "2026-10-18T00:00:00Z".parse().unwrap()
+    }
+
+    fn user(login: &str) -> User {
+        User {
+            id: 1,
+            login: login.to_string(),
+            avatar_url: String::new(),
+            html_url: String::new(),
+        }
+    }
+
+    fn pr(
+        repo: &str,
+        number: i32,
+        created_days_ago: i64,
+        checks: Option<CheckState>,
+    ) -> PullRequestWithChecks {
+        let created_at = now() - Duration::days(created_days_ago);
+        PullRequestWithChecks {
+            pull_request: PullRequest {
+                id: number.into(),
+                number,
+                title: format!("PR {}", number),
+                html_url: String::new(),
+                state: PullRequestState::Open,
+                draft: false,
+                created_at,
+                updated_at: created_at + Duration::hours(number.into()),
+                merged_at: None,
+                user: user("octocat"),
+                labels: vec![],
+                requested_reviewers: vec![user("me")],
+                repository: Repository {
+                    id: 1,
+                    name: repo.to_string(),
+                    full_name: format!("octo-org/{}", repo),
+                    html_url: String::new(),
+                    owner: user("octo-org"),
+                },
+            },
+            check_status: checks.map(|state| CheckStatus {
+                state,
+                total_count: 1,
+                statuses: vec![],
+            }),
+            review_sla: None,
+        }
+    }
+
+    fn numbers(prs: &[PullRequestWithChecks]) -> Vec<i32> {
+        prs.iter().map(|pr| pr.pull_request.number).collect()
+    }
+
+    fn keys(keys: &[&str]) -> Vec<SortKey> {
+        keys.iter().map(|k| k.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_sort_key_parsing() {
+        let key: SortKey = "-updated".parse().unwrap();
+        assert_eq!(key.field, SortField::Updated);
+        assert!(key.descending);
+        assert_eq!(key.to_string(), "-updated");
+        assert_eq!("checks".parse::<SortKey>().unwrap().to_string(), "checks");
+        assert!("-stars".parse::<SortKey>().unwrap_err().contains("stars"));
+    }
+
+    #[test]
+    fn test_multi_key_sort_puts_failing_first_then_oldest() {
+        let mut prs = vec![
+            pr("api", 1, 2, Some(CheckState::Success)),
+            pr("api", 2, 9, Some(CheckState::Failure)),
+            pr("web", 3, 1, Some(CheckState::Failure)),
+            pr("web", 4, 30, None),
+            pr("web", 5, 40, Some(CheckState::Pending)),
+        ];
+        sort_prs(&mut prs, &keys(&["checks", "created"]));
+        assert_eq!(numbers(&prs), vec![2, 3, 5, 4, 1]);
+
+        sort_prs(&mut prs, &keys(&["-updated"]));
+        assert_eq!(numbers(&prs), vec![3, 1, 2, 4, 5]);
+    }
+
+    #[test]
+    fn test_sort_by_requested_puts_unrequested_last() {
+        let requested = |number: i32, hours_ago: i64| {
+            let mut pr = pr("api", number, 0, None);
+            pr.review_sla = Some(ReviewSla {
+                requested_at: now() - Duration::hours(hours_ago),
+                waiting_secs: hours_ago * 3600,
+                status: SlaStatus::Ok,
+            });
+            pr
+        };
+        let mut prs = vec![
+            pr("api", 1, 0, None),
+            requested(2, 3),
+            requested(3, 30),
+            pr("api", 4, 0, None),
+            requested(5, 10),
+        ];
+        sort_prs(&mut prs, &keys(&["requested"]));
+        assert_eq!(numbers(&prs), vec![3, 5, 2, 1, 4]);
+
+        sort_prs(&mut prs, &keys(&["-requested", "-updated"]));
+        assert_eq!(numbers(&prs), vec![2, 5, 3, 4, 1]);
+    }
+
+    #[test]
+    fn test_sort_uses_timestamps_not_strings() {
+        let mut earlier = pr("api", 1, 0, None);
+        let mut later = pr("api", 2, 0, None);
+        // "+02:00" sorts after "Z" as text but is the earlier instant
+        earlier.pull_request.updated_at = "2026-10-17T11:00:00+02:00".parse().unwrap();
+        later.pull_request.updated_at = "2026-10-17T10:00:00Z".parse().unwrap();
+        let mut prs = vec![earlier, later];
+        sort_prs(&mut prs, &keys(&["-updated"]));
+        assert_eq!(numbers(&prs), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_group_by_check_state_and_age() {
+        let prs = vec![
+            pr("api", 1, 0, Some(CheckState::Success)),
+            pr("api", 2, 3, Some(CheckState::Failure)),
+            pr("web", 3, 60, Some(CheckState::Failure)),
+        ];
+
+        let view = build_view(prs.clone(), GroupBy::CheckState, &[], now());
+        assert_eq!(view.total, 3);
+        let summary: Vec<_> = view
+            .groups
+            .iter()
+            .map(|g| (g.key.as_str(), g.count))
+            .collect();
+        assert_eq!(summary, vec![("failure", 2), ("success", 1)]);
+
+        let view = build_view(prs.clone(), GroupBy::Age, &keys(&["created"]), now());
+        let summary: Vec<_> = view
+            .groups
+            .iter()
+            .map(|g| (g.key.as_str(), g.count))
+            .collect();
+        assert_eq!(summary, vec![("today", 1), ("week", 1), ("older", 1)]);
+
+        let view = build_view(prs, GroupBy::None, &keys(&["repository"]), now());
+        assert_eq!(view.groups.len(), 1);
+        assert_eq!(numbers(&view.groups[0].pull_requests), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_group_by_review_status_and_repository() {
+        let mut draft = pr("web", 1, 0, None);
+        draft.pull_request.draft = true;
+        let mut reviewed = pr("api", 2, 0, None);
+        reviewed.pull_request.requested_reviewers.clear();
+        let prs = vec![draft, reviewed, pr("web", 3, 0, None)];
+
+        let view = build_view(prs.clone(), GroupBy::ReviewStatus, &[], now());
+        let summary: Vec<_> = view.groups.iter().map(|g| g.label.as_str()).collect();
+        assert_eq!(
+            summary,
+            vec!["Review requested", "No pending reviewers", "Draft"]
+        );
+
+        let view = build_view(prs, GroupBy::Repository, &[], now());
+        let summary: Vec<_> = view
+            .groups
+            .iter()
+            .map(|g| (g.key.as_str(), g.count))
+            .collect();
+        assert_eq!(summary, vec![("octo-org/api", 1), ("octo-org/web", 2)]);
+    }
+}
@@ -0,0 +1,13 @@
+//! Fake GitHub API for exercising ghview without network access.
+//!
+//! Build a [`Scenario`], start a [`FakeGitHub`] with it, and point the backend at
+//! [`FakeGitHub::url`] via `GHVIEW_GITHUB_API_URL` and `GHVIEW_GITHUB_OAUTH_URL`.
+
+mod scenario;
+mod server;
+
+pub use scenario::{
+    DevicePoll, Failure, FakeCheckRun, FakeComment, FakeFile, FakePull, FakeReview, FakeStatus,
+    FakeTimelineEvent, FakeToken, Scenario,
+};
+pub use server::{FakeGitHub, RecordedRequest, DEVICE_CODE};
@@ -0,0 +1,22 @@
+use anyhow::Result;
+use ghview_fake_github::{FakeGitHub, Scenario};
+use std::net::SocketAddr;
+
+/// Serve the demo scenario until interrupted
+#[tokio::main]
+async fn main() -> Result<()> {
+    let addr: SocketAddr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8787".to_string())
+        .parse()?;
+    let server = FakeGitHub::bind(addr, Scenario::demo()).await?;
+
+    eprintln!("Fake GitHub listening on {}", server.url());
+    eprintln!("Run ghview against it with:");
+    eprintln!("  GHVIEW_GITHUB_API_URL={} \\", server.url());
+    eprintln!("  GHVIEW_GITHUB_OAUTH_URL={} \\", server.url());
+    eprintln!("  GH_TOKEN=gho_demo pnpm tauri dev");
+
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+
+/// Timestamp used when a scenario doesn't set one
+const DEFAULT_TIMESTAMP: &str = "2026-01-01T00:00:00Z";
+
+/// A token the fake API accepts
+#[derive(Debug, Clone)]
+pub struct FakeToken {
+    pub login: String,
+    /// Sent as `X-OAuth-Scopes`; `None` omits the header like fine-grained tokens do
+    pub scopes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeFile {
+    pub filename: String,
+    pub status: String,
+    pub additions: i32,
+    pub deletions: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeStatus {
+    pub state: String,
+    pub context: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeCheckRun {
+    pub name: String,
+    pub status: String,
+    pub conclusion: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeReview {
+    pub user: String,
+    pub state: String,
+    pub submitted_at: String,
+}
+
+/// A comment on the pull request's conversation
+#[derive(Debug, Clone)]
+pub struct FakeComment {
+    pub user: String,
+    pub body: String,
+}
+
+/// An entry of the issue timeline, e.g. `review_requested` or `commented`
+#[derive(Debug, Clone)]
+pub struct FakeTimelineEvent {
+    pub event: String,
+    /// The requested reviewer for review request events, otherwise the acting user
+    pub reviewer: String,
+    pub created_at: String,
+}
+
+/// A pull request, including everything reachable from it (head commit statuses,
+/// check runs, reviews, comments and files)
+#[derive(Debug, Clone)]
+pub struct FakePull {
+    pub repo: String,
+    pub number: i32,
+    pub title: String,
+    pub author: String,
+    pub body: Option<String>,
+    pub draft: bool,
+    pub state: String,
+    pub labels: Vec<String>,
+    pub requested_reviewers: Vec<String>,
+    pub head_sha: String,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Matched by `review-requested:@me`
+    pub review_requested: bool,
+    /// Matched by `assignee:@me`
+    pub assigned: bool,
+    pub files: Vec<FakeFile>,
+    pub statuses: Vec<FakeStatus>,
+    pub check_runs: Vec<FakeCheckRun>,
+    pub reviews: Vec<FakeReview>,
+    pub comments: Vec<FakeComment>,
+    pub timeline: Vec<FakeTimelineEvent>,
+}
+
+impl FakePull {
+    /// An open pull request by `octocat`; `repo` is `owner/name`
+    pub fn new(repo: &str, number: i32, title: &str) -> Self {
+        Self {
+            repo: repo.to_string(),
+            number,
+            title: title.to_string(),
+            author: "octocat".to_string(),
+            body: None,
+            draft: false,
+            state: "open".to_string(),
+            labels: Vec::new(),
+            requested_reviewers: Vec::new(),
+            head_sha: format!("{:040x}", stable_id(&format!("{}#{}", repo, number))),
+            created_at: DEFAULT_TIMESTAMP.to_string(),
+            updated_at: DEFAULT_TIMESTAMP.to_string(),
+            review_requested: false,
+            assigned: false,
+            files: Vec::new(),
+            statuses: Vec::new(),
+            check_runs: Vec::new(),
+            reviews: Vec::new(),
+            comments: Vec::new(),
+            timeline: Vec::new(),
+        }
+    }
+
+    pub fn author(mut self, login: &str) -> Self {
+        self.author = login.to_string();
+        self
+    }
+
+    pub fn body(mut self, body: &str) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+
+    pub fn draft(mut self) -> Self {
+        self.draft = true;
+        self
+    }
+
+    pub fn label(mut self, name: &str) -> Self {
+        self.labels.push(name.to_string());
+        self
+    }
+
+    pub fn reviewer(mut self, login: &str) -> Self {
+        self.requested_reviewers.push(login.to_string());
+        self
+    }
+
+    pub fn created_at(mut self, timestamp: &str) -> Self {
+        self.created_at = timestamp.to_string();
+        self
+    }
+
+    pub fn updated_at(mut self, timestamp: &str) -> Self {
+        self.updated_at = timestamp.to_string();
+        self
+    }
+
+    pub fn review_requested(mut self) -> Self {
+        self.review_requested = true;
+        self
+    }
+
+    pub fn assigned(mut self) -> Self {
+        self.assigned = true;
+        self
+    }
+
+    pub fn file(mut self, filename: &str, additions: i32, deletions: i32) -> Self {
+        self.files.push(FakeFile {
+            filename: filename.to_string(),
+            status: "modified".to_string(),
+            additions,
+            deletions,
+        });
+        self
+    }
+
+    pub fn status(mut self, state: &str, context: &str) -> Self {
+        self.statuses.push(FakeStatus {
+            state: state.to_string(),
+            context: context.to_string(),
+            description: None,
+        });
+        self
+    }
+
+    /// A completed check run; `conclusion` is e.g. `success` or `failure`
+    pub fn check_run(mut self, name: &str, conclusion: &str) -> Self {
+        self.check_runs.push(FakeCheckRun {
+            name: name.to_string(),
+            status: "completed".to_string(),
+            conclusion: Some(conclusion.to_string()),
+        });
+        self
+    }
+
+    /// Request `login`'s review at `timestamp`, both in `requested_reviewers` and the timeline
+    pub fn review_requested_at(mut self, login: &str, timestamp: &str) -> Self {
+        self.requested_reviewers.push(login.to_string());
+        self.timeline.push(FakeTimelineEvent {
+            event: "review_requested".to_string(),
+            reviewer: login.to_string(),
+            created_at: timestamp.to_string(),
+        });
+        self
+    }
+
+    /// Append `event` by (or, for review requests, naming) `login` to the timeline
+    pub fn timeline_event(mut self, event: &str, login: &str, timestamp: &str) -> Self {
+        self.timeline.push(FakeTimelineEvent {
+            event: event.to_string(),
+            reviewer: login.to_string(),
+            created_at: timestamp.to_string(),
+        });
+        self
+    }
+
+    pub fn review(self, login: &str, state: &str) -> Self {
+        self.review_at(login, state, DEFAULT_TIMESTAMP)
+    }
+
+    /// A review submitted at `timestamp`; matched by `reviewed-by:<login>` searches
+    pub fn review_at(mut self, login: &str, state: &str, timestamp: &str) -> Self {
+        self.reviews.push(FakeReview {
+            user: login.to_string(),
+            state: state.to_string(),
+            submitted_at: timestamp.to_string(),
+        });
+        self
+    }
+}
+
+/// One answer of the device flow access token endpoint
+#[derive(Debug, Clone)]
+pub enum DevicePoll {
+    Pending,
+    SlowDown,
+    Denied,
+    Expired,
+    /// Issue a token for `login`; a refresh token is included when `expires_in` is set
+    Authorize {
+        login: String,
+        expires_in: Option<u64>,
+    },
+}
+
+/// Make matching requests fail with `status`
+#[derive(Debug, Clone)]
+pub struct Failure {
+    /// Request path prefix, e.g. `/search/issues`
+    pub path: String,
+    pub status: u16,
+    /// How many more requests fail; `None` fails forever
+    pub remaining: Option<usize>,
+}
+
+/// Everything the fake server knows about, plus scripted device-flow answers and
+/// injected failures
+#[derive(Debug, Clone, Default)]
+pub struct Scenario {
+    pub tokens: HashMap<String, FakeToken>,
+    /// Refresh token -> login it issues access tokens for
+    pub refresh_tokens: HashMap<String, String>,
+    pub pulls: Vec<FakePull>,
+    /// Answered in order; the last one repeats
+    pub device_polls: Vec<DevicePoll>,
+    /// Interval returned with the device code
+    pub device_interval: u64,
+    pub failures: Vec<Failure>,
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        Self {
+            device_interval: 5,
+            ..Default::default()
+        }
+    }
+
+    /// Accept `token` as `login` with the given scopes
+    pub fn token(mut self, token: &str, login: &str, scopes: &[&str]) -> Self {
+        self.tokens.insert(
+            token.to_string(),
+            FakeToken {
+                login: login.to_string(),
+                scopes: Some(scopes.iter().map(|s| s.to_string()).collect()),
+            },
+        );
+        self
+    }
+
+    /// Accept `token` as `login` without sending `X-OAuth-Scopes`
+    pub fn fine_grained_token(mut self, token: &str, login: &str) -> Self {
+        self.tokens.insert(
+            token.to_string(),
+            FakeToken {
+                login: login.to_string(),
+                scopes: None,
+            },
+        );
+        self
+    }
+
+    pub fn refresh_token(mut self, refresh_token: &str, login: &str) -> Self {
+        self.refresh_tokens
+            .insert(refresh_token.to_string(), login.to_string());
+        self
+    }
+
+    pub fn pull(mut self, pull: FakePull) -> Self {
+        self.pulls.push(pull);
+        self
+    }
+
+    pub fn device_flow(mut self, polls: Vec<DevicePoll>) -> Self {
+        self.device_polls = polls;
+        self
+    }
+
+    pub fn fail(self, path: &str, status: u16) -> Self {
+        self.fail_times(path, status, None)
+    }
+
+    pub fn fail_times(mut self, path: &str, status: u16, times: Option<usize>) -> Self {
+        self.failures.push(Failure {
+            path: path.to_string(),
+            status,
+            remaining: times,
+        });
+        self
+    }
+
+    /// A few pull requests across two repositories, for running the app against
+    pub fn demo() -> Self {
+        Scenario::new()
+            .token("gho_demo", "octocat", &["repo", "read:org"])
+            .device_flow(vec![
+                DevicePoll::Pending,
+                DevicePoll::Authorize {
+                    login: "octocat".to_string(),
+                    expires_in: None,
+                },
+            ])
+            .pull(
+                FakePull::new("octo-org/hello-world", 7, "Add greeting endpoint")
+                    .author("hubot")
+                    .body("Adds `GET /greeting`.\n\nCloses #3.")
+                    .label("enhancement")
+                    .reviewer("octocat")
+                    .review_requested()
+                    .updated_at("2026-10-17T12:00:00Z")
+                    .file("src/greeting.rs", 42, 0)
+                    .file("src/main.rs", 3, 1)
+                    .status("success", "ci/build")
+                    .status("failure", "ci/test")
+                    .check_run("lint", "success")
+                    .review("monalisa", "APPROVED"),
+            )
+            .pull(
+                FakePull::new("octo-org/spoon-knife", 42, "Fix fork counter")
+                    .author("monalisa")
+                    .assigned()
+                    .updated_at("2026-10-16T08:30:00Z")
+                    .file("counter.js", 5, 5)
+                    .status("success", "ci/build"),
+            )
+            .pull(
+                FakePull::new("octo-org/hello-world", 9, "WIP: dark mode")
+                    .author("hubot")
+                    .draft()
+                    .label("ui")
+                    .reviewer("octocat")
+                    .review_requested()
+                    .updated_at("2026-10-15T18:45:00Z")
+                    .status("pending", "ci/build"),
+            )
+    }
+}
+
+/// Deterministic id derived from a name (FNV-1a, truncated to keep JSON numbers small)
+pub fn stable_id(name: &str) -> i64 {
+    let hash = name.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    (hash % 1_000_000_000) as i64
+}
@@ -0,0 +1,945 @@
+use serde_json::{json, Value};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use crate::scenario::{
+    stable_id, DevicePoll, FakeCheckRun, FakeComment, FakePull, FakeReview, FakeToken, Scenario,
+};
+
+/// Device code handed out by `/login/device/code`
+pub const DEVICE_CODE: &str = "fake-device-code";
+const USER_CODE: &str = "FAKE-1234";
+
+/// A request received by the fake server
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub authorization: Option<String>,
+    pub body: String,
+}
+
+struct State {
+    base_url: String,
+    scenario: Scenario,
+    requests: Vec<RecordedRequest>,
+    device_polls: usize,
+    issued_tokens: usize,
+}
+
+/// Fake GitHub REST API and OAuth endpoints served on a local port
+///
+/// API routes live at the root (like `api.github.com`) and OAuth routes under
+/// `/login`, so the same URL serves as both the API and the OAuth base URL.
+/// The server stops when this is dropped.
+pub struct FakeGitHub {
+    url: String,
+    state: Arc<Mutex<State>>,
+    _shutdown: oneshot::Sender<()>,
+}
+
+impl FakeGitHub {
+    /// Serve `scenario` on a random local port
+    pub async fn start(scenario: Scenario) -> io::Result<Self> {
+        Self::bind("127.0.0.1:0".parse().unwrap(), scenario).await
+    }
+
+    pub async fn bind(addr: SocketAddr, scenario: Scenario) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let state = Arc::new(Mutex::new(State {
+            base_url: url.clone(),
+            scenario,
+            requests: Vec::new(),
+            device_polls: 0,
+            issued_tokens: 0,
+        }));
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+
+        let accept_state = state.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    accepted = listener.accept() => {
+                        let Ok((stream, _)) = accepted else { continue };
+                        let state = accept_state.clone();
+                        tokio::spawn(async move {
+                            let _ = handle_connection(stream, state).await;
+                        });
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            url,
+            state,
+            _shutdown: shutdown_tx,
+        })
+    }
+
+    /// Base URL, e.g. `http://127.0.0.1:54321`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Requests received so far, oldest first
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Change the scenario while the server is running
+    pub fn update(&self, f: impl FnOnce(&mut Scenario)) {
+        f(&mut self.state.lock().unwrap().scenario);
+    }
+}
+
+struct Response {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Value,
+}
+
+impl Response {
+    fn json(body: Value) -> Self {
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    fn created(body: Value) -> Self {
+        Self {
+            status: 201,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: json!({
+                "message": message,
+                "documentation_url": "https://docs.github.com/rest",
+            }),
+        }
+    }
+}
+
+/// Read one request, answer it and close the connection
+async fn handle_connection(mut stream: TcpStream, state: Arc<Mutex<State>>) -> io::Result<()> {
+    let Some(request) = read_request(&mut stream).await? else {
+        return Ok(());
+    };
+    let response = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(request.clone());
+        route(&mut state, &request)
+    };
+
+    let body = response.body.to_string();
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason(response.status),
+        body.len()
+    );
+    for (name, value) in response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn read_request(stream: &mut TcpStream) -> io::Result<Option<RecordedRequest>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let (head, body_start) = loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break (String::from_utf8_lossy(&buf[..end]).to_string(), end + 4);
+        }
+    };
+
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    let mut authorization = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        match name.trim().to_lowercase().as_str() {
+            "content-length" => content_length = value.trim().parse().unwrap_or(0),
+            "authorization" => authorization = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+
+    while buf.len() < body_start + content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let body = String::from_utf8_lossy(&buf[body_start.min(buf.len())..]).to_string();
+
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target, None),
+    };
+    Ok(Some(RecordedRequest {
+        method,
+        path,
+        query,
+        authorization,
+        body,
+    }))
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        422 => "Unprocessable Entity",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+fn params(encoded: &str) -> Vec<(String, String)> {
+    url::form_urlencoded::parse(encoded.as_bytes())
+        .into_owned()
+        .collect()
+}
+
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn route(state: &mut State, request: &RecordedRequest) -> Response {
+    if let Some(failure) = state
+        .scenario
+        .failures
+        .iter_mut()
+        .find(|f| request.path.starts_with(&f.path) && f.remaining != Some(0))
+    {
+        if let Some(remaining) = failure.remaining.as_mut() {
+            *remaining -= 1;
+        }
+        return Response::error(failure.status, "Injected failure");
+    }
+
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("POST", ["login", "device", "code"]) => device_code(state),
+        ("POST", ["login", "oauth", "access_token"]) => access_token(state, &request.body),
+        (_, ["login", ..]) => Response::error(404, "Not Found"),
+        (method, segments) => {
+            let Some(token) = authenticate(state, request) else {
+                return Response::error(401, "Bad credentials");
+            };
+            match method {
+                "GET" => api(state, &token, segments, request.query.as_deref()),
+                "POST" => api_post(state, &token, segments, &request.body),
+                _ => Response::error(404, "Not Found"),
+            }
+        }
+    }
+}
+
+fn authenticate(state: &State, request: &RecordedRequest) -> Option<FakeToken> {
+    let header = request.authorization.as_deref()?;
+    let token = header
+        .strip_prefix("Bearer ")
+        .or_else(|| header.strip_prefix("token "))?;
+    state.scenario.tokens.get(token).cloned()
+}
+
+fn api(state: &State, token: &FakeToken, segments: &[&str], query: Option<&str>) -> Response {
+    let pull = |owner: &str, repo: &str, number: &str| {
+        let full_name = format!("{}/{}", owner, repo);
+        state
+            .scenario
+            .pulls
+            .iter()
+            .find(|p| p.repo == full_name && p.number.to_string() == number)
+    };
+    let by_sha = |owner: &str, repo: &str, sha: &str| {
+        let full_name = format!("{}/{}", owner, repo);
+        state
+            .scenario
+            .pulls
+            .iter()
+            .find(|p| p.repo == full_name && p.head_sha == sha)
+    };
+    let not_found = || Response::error(404, "Not Found");
+
+    match segments {
+        ["user"] => {
+            let mut response = Response::json(user_json(&token.login));
+            if let Some(scopes) = &token.scopes {
+                response.headers.push(("X-OAuth-Scopes", scopes.join(", ")));
+            }
+            response
+        }
+        ["search", "issues"] => search(state, query.unwrap_or_default()),
+        ["repos", owner, repo] => {
+            let full_name = format!("{}/{}", owner, repo);
+            if state.scenario.pulls.iter().any(|p| p.repo == full_name) {
+                Response::json(repo_json(&full_name))
+            } else {
+                not_found()
+            }
+        }
+        ["repos", owner, repo, "pulls", number] => pull(owner, repo, number)
+            .map(|p| Response::json(pull_json(p)))
+            .unwrap_or_else(not_found),
+        ["repos", owner, repo, "pulls", number, "files"] => pull(owner, repo, number)
+            .map(|p| {
+                Response::json(Value::Array(
+                    p.files
+                        .iter()
+                        .map(|f| {
+                            json!({
+                                "filename": f.filename,
+                                "status": f.status,
+                                "additions": f.additions,
+                                "deletions": f.deletions,
+                                "changes": f.additions + f.deletions,
+                            })
+                        })
+                        .collect(),
+                ))
+            })
+            .unwrap_or_else(not_found),
+        ["repos", owner, repo, "pulls", number, "reviews"] => pull(owner, repo, number)
+            .map(|p| {
+                Response::json(Value::Array(
+                    p.reviews
+                        .iter()
+                        .enumerate()
+                        .map(|(i, r)| {
+                            json!({
+                                "id": stable_id(&format!("{}#{}/review/{}", p.repo, p.number, i)),
+                                "user": user_json(&r.user),
+                                "state": r.state,
+                                "submitted_at": r.submitted_at,
+                                "commit_id": p.head_sha,
+                            })
+                        })
+                        .collect(),
+                ))
+            })
+            .unwrap_or_else(not_found),
+        ["repos", owner, repo, "issues", number, "timeline"] => pull(owner, repo, number)
+            .map(|p| {
+                let events = p.timeline.iter().map(|e| match e.event.as_str() {
+                    "review_requested" | "review_request_removed" => json!({
+                        "event": e.event,
+                        "created_at": e.created_at,
+                        "actor": user_json(&p.author),
+                        "requested_reviewer": user_json(&e.reviewer),
+                    }),
+                    _ => json!({
+                        "event": e.event,
+                        "created_at": e.created_at,
+                        "actor": user_json(&e.reviewer),
+                        "user": user_json(&e.reviewer),
+                    }),
+                });
+                paginate(state, segments, query, events.collect())
+            })
+            .unwrap_or_else(not_found),
+        ["repos", owner, repo, "commits", sha, "status"] => by_sha(owner, repo, sha)
+            .map(|p| Response::json(combined_status_json(p)))
+            .unwrap_or_else(not_found),
+        ["repos", owner, repo, "commits", sha, "check-runs"] => by_sha(owner, repo, sha)
+            .map(|p| {
+                Response::json(json!({
+                    "total_count": p.check_runs.len(),
+                    "check_runs": p.check_runs.iter().map(|c| json!({
+                        "id": check_run_id(p, c),
+                        "name": c.name,
+                        "status": c.status,
+                        "conclusion": c.conclusion,
+                        "head_sha": p.head_sha,
+                    })).collect::<Vec<_>>(),
+                }))
+            })
+            .unwrap_or_else(not_found),
+        _ => not_found(),
+    }
+}
+
+/// Reviews, comments and check run re-runs; they change the scenario so later reads
+/// see them
+fn api_post(state: &mut State, token: &FakeToken, segments: &[&str], body: &str) -> Response {
+    let body = match body.trim() {
+        "" => json!({}),
+        body => match serde_json::from_str::<Value>(body) {
+            Ok(body) => body,
+            Err(_) => return Response::error(400, "Problems parsing JSON"),
+        },
+    };
+    let text = body.get("body").and_then(Value::as_str).unwrap_or_default();
+    let not_found = || Response::error(404, "Not Found");
+
+    match segments {
+        ["repos", owner, repo, "pulls", number, "reviews"] => {
+            let Some(p) = pull_mut(state, owner, repo, number) else {
+                return not_found();
+            };
+            let review_state = match body.get("event").and_then(Value::as_str) {
+                Some("APPROVE") => "APPROVED",
+                Some("REQUEST_CHANGES") if !text.is_empty() => "CHANGES_REQUESTED",
+                Some("COMMENT") if !text.is_empty() => "COMMENTED",
+                Some("REQUEST_CHANGES" | "COMMENT") => {
+                    return Response::error(422, "Review body is required")
+                }
+                _ => return Response::error(422, "Unprocessable Entity"),
+            };
+            p.reviews.push(FakeReview {
+                user: token.login.clone(),
+                state: review_state.to_string(),
+                submitted_at: p.updated_at.clone(),
+            });
+            let id = stable_id(&format!(
+                "{}#{}/review/{}",
+                p.repo,
+                p.number,
+                p.reviews.len() - 1
+            ));
+            Response::json(json!({
+                "id": id,
+                "user": user_json(&token.login),
+                "body": text,
+                "state": review_state,
+                "html_url": format!("https://github.com/{}/pull/{}#pullrequestreview-{}", p.repo, p.number, id),
+                "commit_id": p.head_sha,
+            }))
+        }
+        ["repos", owner, repo, "issues", number, "comments"] => {
+            let Some(p) = pull_mut(state, owner, repo, number) else {
+                return not_found();
+            };
+            if text.is_empty() {
+                return Response::error(422, "Body cannot be blank");
+            }
+            p.comments.push(FakeComment {
+                user: token.login.clone(),
+                body: text.to_string(),
+            });
+            let id = stable_id(&format!(
+                "{}#{}/comment/{}",
+                p.repo,
+                p.number,
+                p.comments.len() - 1
+            ));
+            Response::created(json!({
+                "id": id,
+                "user": user_json(&token.login),
+                "body": text,
+                "html_url": format!("https://github.com/{}/pull/{}#issuecomment-{}", p.repo, p.number, id),
+            }))
+        }
+        ["repos", owner, repo, "check-runs", id, "rerequest"] => {
+            let full_name = format!("{}/{}", owner, repo);
+            let check_run = state
+                .scenario
+                .pulls
+                .iter_mut()
+                .filter(|p| p.repo == full_name)
+                .flat_map(|p| {
+                    let ids: Vec<i64> = p.check_runs.iter().map(|c| check_run_id(p, c)).collect();
+                    p.check_runs.iter_mut().zip(ids)
+                })
+                .find(|(_, check_id)| check_id.to_string() == *id);
+            let Some((check_run, _)) = check_run else {
+                return not_found();
+            };
+            check_run.status = "queued".to_string();
+            check_run.conclusion = None;
+            Response::created(json!({}))
+        }
+        _ => not_found(),
+    }
+}
+
+fn pull_mut<'a>(
+    state: &'a mut State,
+    owner: &str,
+    repo: &str,
+    number: &str,
+) -> Option<&'a mut FakePull> {
+    let full_name = format!("{}/{}", owner, repo);
+    state
+        .scenario
+        .pulls
+        .iter_mut()
+        .find(|p| p.repo == full_name && p.number.to_string() == number)
+}
+
+fn check_run_id(p: &FakePull, check_run: &FakeCheckRun) -> i64 {
+    stable_id(&format!("{}#{}/check/{}", p.repo, p.number, check_run.name))
+}
+
+/// The requested page of `items`, oldest first, with GitHub's `Link` header when
+/// there is more than one page
+fn paginate(state: &State, segments: &[&str], query: Option<&str>, items: Vec<Value>) -> Response {
+    let params = params(query.unwrap_or_default());
+    let number = |name: &str, default: usize| {
+        param(&params, name)
+            .and_then(|value| value.parse::<usize>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(default)
+    };
+    let per_page = number("per_page", 30).min(100);
+    let page = number("page", 1);
+    let last = items.len().div_ceil(per_page).max(1);
+
+    let page_items = items
+        .into_iter()
+        .skip((page - 1) * per_page)
+        .take(per_page)
+        .collect();
+    let mut response = Response::json(Value::Array(page_items));
+    if last > 1 {
+        let url = |page: usize| {
+            format!(
+                "<{}/{}?per_page={}&page={}>",
+                state.base_url,
+                segments.join("/"),
+                per_page,
+                page
+            )
+        };
+        let mut links = Vec::new();
+        if page > 1 {
+            links.push(format!("{}; rel=\"prev\"", url(page - 1)));
+        }
+        if page < last {
+            links.push(format!("{}; rel=\"next\"", url(page + 1)));
+        }
+        links.push(format!("{}; rel=\"last\"", url(last)));
+        links.push(format!("{}; rel=\"first\"", url(1)));
+        response.headers.push(("Link", links.join(", ")));
+    }
+    response
+}
+
+fn search(state: &State, query: &str) -> Response {
+    let params = params(query);
+    let q = param(&params, "q").unwrap_or_default();
+    // Everything fits on the first page
+    let first_page = param(&params, "page").is_none_or(|page| page == "1");
+    let mut items: Vec<&FakePull> = state
+        .scenario
+        .pulls
+        .iter()
+        .filter(|_| first_page)
+        .filter(|p| {
+            q.split_whitespace().all(|term| match term {
+                "is:open" => p.state == "open",
+                "review-requested:@me" => p.review_requested,
+                "assignee:@me" => p.assigned,
+                _ => {
+                    if let Some(login) = term.strip_prefix("reviewed-by:") {
+                        p.reviews.iter().any(|r| r.user == login)
+                    } else if let Some(date) = term.strip_prefix("updated:>=") {
+                        p.updated_at.as_str() >= date
+                    } else {
+                        true
+                    }
+                }
+            })
+        })
+        .collect();
+    items.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+    Response::json(json!({
+        "total_count": items.len(),
+        "incomplete_results": false,
+        "items": items.iter().map(|p| {
+            let mut item = pull_json(p);
+            let object = item.as_object_mut().unwrap();
+            object.remove("head");
+            object.remove("base");
+            object.insert(
+                "repository_url".to_string(),
+                json!(format!("{}/repos/{}", state.base_url, p.repo)),
+            );
+            item
+        }).collect::<Vec<_>>(),
+    }))
+}
+
+fn user_json(login: &str) -> Value {
+    let id = stable_id(login);
+    json!({
+        "id": id,
+        "login": login,
+        "avatar_url": format!("https://avatars.githubusercontent.com/u/{}", id),
+        "html_url": format!("https://github.com/{}", login),
+    })
+}
+
+fn repo_json(full_name: &str) -> Value {
+    let (owner, name) = full_name.split_once('/').unwrap_or(("", full_name));
+    json!({
+        "id": stable_id(full_name),
+        "name": name,
+        "full_name": full_name,
+        "html_url": format!("https://github.com/{}", full_name),
+        "owner": user_json(owner),
+    })
+}
+
+fn pull_json(p: &FakePull) -> Value {
+    json!({
+        "id": stable_id(&format!("{}#{}", p.repo, p.number)),
+        "number": p.number,
+        "title": p.title,
+        "html_url": format!("https://github.com/{}/pull/{}", p.repo, p.number),
+        "state": p.state,
+        "draft": p.draft,
+        "created_at": p.created_at,
+        "updated_at": p.updated_at,
+        "merged_at": null,
+        "user": user_json(&p.author),
+        "labels": p.labels.iter().map(|name| json!({
+            "id": stable_id(name),
+            "name": name,
+            "color": "ededed",
+            "description": null,
+        })).collect::<Vec<_>>(),
+        "requested_reviewers": p.requested_reviewers.iter().map(|l| user_json(l)).collect::<Vec<_>>(),
+        "head": { "sha": p.head_sha },
+        "base": { "repo": repo_json(&p.repo) },
+        "body": p.body,
+        "additions": p.files.iter().map(|f| f.additions).sum::<i32>(),
+        "deletions": p.files.iter().map(|f| f.deletions).sum::<i32>(),
+        "changed_files": p.files.len(),
+    })
+}
+
+/// Combined state as GitHub computes it: any failure or error wins, then pending
+fn combined_status_json(p: &FakePull) -> Value {
+    let states: Vec<&str> = p.statuses.iter().map(|s| s.state.as_str()).collect();
+    let state = if states.iter().any(|s| *s == "failure" || *s == "error") {
+        "failure"
+    } else if states.is_empty() || states.contains(&"pending") {
+        "pending"
+    } else {
+        "success"
+    };
+    json!({
+        "state": state,
+        "sha": p.head_sha,
+        "total_count": p.statuses.len(),
+        "statuses": p.statuses.iter().map(|s| json!({
+            "state": s.state,
+            "context": s.context,
+            "description": s.description,
+            "target_url": format!("https://ci.example.com/{}/{}", p.repo, s.context),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn device_code(state: &State) -> Response {
+    Response::json(json!({
+        "device_code": DEVICE_CODE,
+        "user_code": USER_CODE,
+        "verification_uri": format!("{}/login/device", state.base_url),
+        "expires_in": 900,
+        "interval": state.scenario.device_interval,
+    }))
+}
+
+fn access_token(state: &mut State, body: &str) -> Response {
+    let params = params(body);
+    match param(&params, "grant_type") {
+        Some("refresh_token") => {
+            let refresh_token = param(&params, "refresh_token").unwrap_or_default();
+            match state.scenario.refresh_tokens.remove(refresh_token) {
+                Some(login) => issue_token(state, &login, Some(28800)),
+                None => Response::json(json!({
+                    "error": "bad_refresh_token",
+                    "error_description": "The refresh token passed is incorrect or expired.",
+                })),
+            }
+        }
+        Some("urn:ietf:params:oauth:grant-type:device_code") => {
+            if param(&params, "device_code") != Some(DEVICE_CODE) {
+                return Response::json(json!({ "error": "incorrect_device_code" }));
+            }
+            let polls = &state.scenario.device_polls;
+            let Some(poll) = polls
+                .get(state.device_polls)
+                .or_else(|| polls.last())
+                .cloned()
+            else {
+                return Response::json(json!({ "error": "authorization_pending" }));
+            };
+            state.device_polls += 1;
+            match poll {
+                DevicePoll::Pending => Response::json(json!({ "error": "authorization_pending" })),
+                DevicePoll::SlowDown => Response::json(json!({
+                    "error": "slow_down",
+                    "interval": state.scenario.device_interval + 5,
+                })),
+                DevicePoll::Denied => Response::json(json!({ "error": "access_denied" })),
+                DevicePoll::Expired => Response::json(json!({ "error": "expired_token" })),
+                DevicePoll::Authorize { login, expires_in } => {
+                    issue_token(state, &login, expires_in)
+                }
+            }
+        }
+        _ => Response::json(json!({ "error": "unsupported_grant_type" })),
+    }
+}
+
+/// Mint an access token (and a refresh token if it expires) that the API accepts
+fn issue_token(state: &mut State, login: &str, expires_in: Option<u64>) -> Response {
+    state.issued_tokens += 1;
+    let access_token = format!("ghu_fake{:04}", state.issued_tokens);
+    state.scenario.tokens.insert(
+        access_token.clone(),
+        FakeToken {
+            login: login.to_string(),
+            scopes: Some(vec!["repo".to_string()]),
+        },
+    );
+
+    let mut body = json!({
+        "access_token": access_token,
+        "token_type": "bearer",
+        "scope": "repo",
+    });
+    if let Some(expires_in) = expires_in {
+        let refresh_token = format!("ghr_fake{:04}", state.issued_tokens);
+        state
+            .scenario
+            .refresh_tokens
+            .insert(refresh_token.clone(), login.to_string());
+        body["expires_in"] = json!(expires_in);
+        body["refresh_token"] = json!(refresh_token);
+        body["refresh_token_expires_in"] = json!(15_811_200);
+    }
+    Response::json(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(scenario: Scenario) -> State {
+        State {
+            base_url: "http://fake".to_string(),
+            scenario,
+            requests: Vec::new(),
+            device_polls: 0,
+            issued_tokens: 0,
+        }
+    }
+
+    fn get(path: &str, query: Option<&str>, token: Option<&str>) -> RecordedRequest {
+        RecordedRequest {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            query: query.map(str::to_string),
+            authorization: token.map(|t| format!("Bearer {}", t)),
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_api_requires_known_token() {
+        let mut state = state(Scenario::demo());
+        assert_eq!(route(&mut state, &get("/user", None, None)).status, 401);
+        assert_eq!(
+            route(&mut state, &get("/user", None, Some("nope"))).status,
+            401
+        );
+
+        let response = route(&mut state, &get("/user", None, Some("gho_demo")));
+        assert_eq!(response.body["login"], "octocat");
+        assert_eq!(
+            response.headers,
+            vec![("X-OAuth-Scopes", "repo, read:org".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_search_filters_and_sorts() {
+        let mut state = state(Scenario::demo());
+        let response = route(
+            &mut state,
+            &get(
+                "/search/issues",
+                Some("q=is%3Apr+is%3Aopen+review-requested%3A%40me"),
+                Some("gho_demo"),
+            ),
+        );
+        let numbers: Vec<i64> = response.body["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["number"].as_i64().unwrap())
+            .collect();
+        assert_eq!(numbers, vec![7, 9]);
+        assert_eq!(
+            response.body["items"][0]["repository_url"],
+            "http://fake/repos/octo-org/hello-world"
+        );
+    }
+
+    fn post(path: &str, body: Value) -> RecordedRequest {
+        RecordedRequest {
+            method: "POST".to_string(),
+            body: body.to_string(),
+            ..get(path, None, Some("gho_demo"))
+        }
+    }
+
+    #[test]
+    fn test_posts_change_the_scenario() {
+        let mut state = state(Scenario::demo());
+        let reviews = "/repos/octo-org/hello-world/pulls/7/reviews";
+        assert_eq!(
+            route(&mut state, &post(reviews, json!({"event": "COMMENT"}))).status,
+            422
+        );
+        let response = route(&mut state, &post(reviews, json!({"event": "APPROVE"})));
+        assert_eq!(response.body["state"], "APPROVED");
+        let listed = route(&mut state, &get(reviews, None, Some("gho_demo")));
+        assert_eq!(listed.body[1]["user"]["login"], "octocat");
+
+        let comment = post(
+            "/repos/octo-org/hello-world/issues/7/comments",
+            json!({"body": "Looks good"}),
+        );
+        assert_eq!(route(&mut state, &comment).status, 201);
+        assert_eq!(state.scenario.pulls[0].comments[0].body, "Looks good");
+
+        let head_sha = state.scenario.pulls[0].head_sha.clone();
+        let check_runs = route(
+            &mut state,
+            &get(
+                &format!(
+                    "/repos/octo-org/hello-world/commits/{}/check-runs",
+                    head_sha
+                ),
+                None,
+                Some("gho_demo"),
+            ),
+        );
+        let id = check_runs.body["check_runs"][0]["id"].as_i64().unwrap();
+        let rerequest = post(
+            &format!("/repos/octo-org/hello-world/check-runs/{}/rerequest", id),
+            json!({}),
+        );
+        assert_eq!(route(&mut state, &rerequest).status, 201);
+        assert_eq!(state.scenario.pulls[0].check_runs[0].status, "queued");
+    }
+
+    #[test]
+    fn test_timeline_is_paginated_with_link_header() {
+        let pull = (0..5).fold(FakePull::new("octo-org/api", 1, "Long"), |pull, i| {
+            pull.timeline_event(
+                "commented",
+                "monalisa",
+                &format!("2026-10-0{}T00:00:00Z", i + 1),
+            )
+        });
+        let mut state = state(Scenario::demo().pull(pull));
+        let path = "/repos/octo-org/api/issues/1/timeline";
+
+        let first = route(&mut state, &get(path, Some("per_page=2"), Some("gho_demo")));
+        assert_eq!(first.body.as_array().unwrap().len(), 2);
+        assert_eq!(
+            first.headers,
+            vec![(
+                "Link",
+                "<http://fake/repos/octo-org/api/issues/1/timeline?per_page=2&page=2>; rel=\"next\", \
+                 <http://fake/repos/octo-org/api/issues/1/timeline?per_page=2&page=3>; rel=\"last\", \
+                 <http://fake/repos/octo-org/api/issues/1/timeline?per_page=2&page=1>; rel=\"first\""
+                    .to_string()
+            )]
+        );
+
+        let last = route(
+            &mut state,
+            &get(path, Some("per_page=2&page=3"), Some("gho_demo")),
+        );
+        assert_eq!(last.body[0]["created_at"], "2026-10-05T00:00:00Z");
+        assert_eq!(last.body[0]["user"]["login"], "monalisa");
+    }
+
+    #[test]
+    fn test_injected_failures_run_out() {
+        let mut state = state(Scenario::demo().fail_times("/user", 502, Some(1)));
+        assert_eq!(
+            route(&mut state, &get("/user", None, Some("gho_demo"))).status,
+            502
+        );
+        assert_eq!(
+            route(&mut state, &get("/user", None, Some("gho_demo"))).status,
+            200
+        );
+    }
+
+    #[test]
+    fn test_device_flow_script() {
+        let mut state = state(Scenario::demo());
+        let poll = RecordedRequest {
+            method: "POST".to_string(),
+            path: "/login/oauth/access_token".to_string(),
+            query: None,
+            authorization: None,
+            body: format!(
+                "client_id=x&device_code={}&grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Adevice_code",
+                DEVICE_CODE
+            ),
+        };
+        assert_eq!(
+            route(&mut state, &poll).body["error"],
+            "authorization_pending"
+        );
+        let token = route(&mut state, &poll).body["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            route(&mut state, &get("/user", None, Some(&token))).body["login"],
+            "octocat"
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use anyhow::{Context, Result};
+use ghview_core::github::{
+    fetch_check_status, fetch_pull_request, fetch_pull_request_details, review_requested_prs,
+};
+use ghview_core::ipc::{IpcClient, IpcError};
+use ghview_core::view::{pr_view, ViewOptions};
+use serde_json::{json, Value};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// Where tool data comes from: the ghview app over IPC while it answers, otherwise
+/// the GitHub API directly with the stored credentials
+#[derive(Clone, Default)]
+pub struct Backend {
+    app: Arc<Mutex<Option<IpcClient>>>,
+}
+
+impl Backend {
+    /// The connection to the app, resolved again whenever the last one went away
+    fn app(&self) -> Result<IpcClient, IpcError> {
+        let mut app = self.app.lock().unwrap();
+        if let Some(client) = app.as_ref() {
+            return Ok(client.clone());
+        }
+        let client = IpcClient::connect()?;
+        *app = Some(client.clone());
+        Ok(client)
+    }
+
+    fn disconnect(&self) {
+        *self.app.lock().unwrap() = None;
+    }
+
+    /// Call a method that only the app can serve
+    async fn call_app(&self, method: &str, params: Value) -> Result<Value> {
+        let result = self.app()?.request(method, params).await;
+        if let Err(e) = &result {
+            if e.is_unreachable() {
+                self.disconnect();
+            }
+        }
+        Ok(result?)
+    }
+
+    /// Ask the app, falling back to `direct` when it isn't running or stopped answering
+    async fn fetch<F, Fut>(&self, method: &str, params: Value, direct: F) -> Result<Value>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Value>>,
+    {
+        if let Ok(client) = self.app() {
+            match client.request(method, params).await {
+                Err(e) if e.is_unreachable() => self.disconnect(),
+                result => return Ok(result?),
+            }
+        }
+        direct().await
+    }
+
+    /// Capture the ghview window; returns the saved file's path and dimensions
+    pub async fn screenshot(&self, params: Value) -> Result<Value> {
+        self.call_app("screenshot", params).await
+    }
+
+    /// Bring a view of the ghview window into sight
+    pub async fn navigate(&self, view: &str) -> Result<Value> {
+        self.call_app("navigate", json!({ "view": view })).await
+    }
+
+    /// Highlight a pull request in the ghview window and scroll to it
+    pub async fn select_pr(&self, owner: &str, repo: &str, number: i32) -> Result<Value> {
+        let params = json!({ "owner": owner, "repo": repo, "number": number });
+        self.call_app("select_pr", params).await
+    }
+
+    /// Resize the ghview window; returns the size it ended up with
+    pub async fn resize_window(&self, width: u32, height: u32) -> Result<Value> {
+        let params = json!({ "width": width, "height": height });
+        self.call_app("resize_window", params).await
+    }
+
+    /// Switch the ghview window to `light`, `dark` or the `system` theme
+    pub async fn set_theme(&self, theme: &str) -> Result<Value> {
+        self.call_app("set_theme", json!({ "theme": theme })).await
+    }
+
+    /// Wait until a condition holds in the ghview window; returns the time it took
+    pub async fn wait_for(&self, params: Value) -> Result<Value> {
+        self.call_app("wait_for", params).await
+    }
+
+    pub async fn list_review_requests(&self) -> Result<Value> {
+        self.fetch("list_review_requests", json!({}), || async {
+            Ok(serde_json::to_value(review_requested_prs().await?)?)
+        })
+        .await
+    }
+
+    /// The review queue grouped and sorted; `params` may override `group_by` and `sort`
+    pub async fn get_pr_view(&self, params: Value) -> Result<Value> {
+        self.fetch("get_pr_view", params.clone(), || async {
+            let options: ViewOptions =
+                serde_json::from_value(params).context("Invalid view params")?;
+            Ok(serde_json::to_value(pr_view(options).await?)?)
+        })
+        .await
+    }
+
+    pub async fn get_pull_request(&self, owner: &str, repo: &str, number: i32) -> Result<Value> {
+        let params = json!({ "owner": owner, "repo": repo, "number": number });
+        self.fetch("get_pull_request", params, || async {
+            Ok(serde_json::to_value(
+                fetch_pull_request(owner, repo, number).await?,
+            )?)
+        })
+        .await
+    }
+
+    pub async fn get_pull_request_details(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: i32,
+    ) -> Result<Value> {
+        let params = json!({ "owner": owner, "repo": repo, "number": number });
+        self.fetch("get_pull_request_details", params, || async {
+            Ok(serde_json::to_value(
+                fetch_pull_request_details(owner, repo, number).await?,
+            )?)
+        })
+        .await
+    }
+
+    pub async fn get_check_status(&self, owner: &str, repo: &str, number: i32) -> Result<Value> {
+        let params = json!({ "owner": owner, "repo": repo, "number": number });
+        self.fetch("get_check_status", params, || async {
+            Ok(serde_json::to_value(
+                fetch_check_status(owner, repo, number).await?,
+            )?)
+        })
+        .await
+    }
+
+    /// Reload the queue in the app window, or just fetch it when the app isn't running
+    pub async fn refresh(&self) -> Result<Value> {
+        self.fetch("refresh", json!({}), || async {
+            Ok(serde_json::to_value(review_requested_prs().await?)?)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+impl Backend {
+    /// A backend holding a connection to an app that has gone away
+    pub fn stale() -> Self {
+        Self {
+            app: Arc::new(Mutex::new(Some(IpcClient::unavailable()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ghview_core::test_support::FakeGitHubEnv;
+    use ghview_fake_github::{FakePull, Scenario};
+    use serial_test::serial;
+    use std::path::Path;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    /// A stand-in for the app answering every request with `{"source": "app"}`,
+    /// advertised through the session file under `home`
+    async fn start_fake_app(home: &Path) -> JoinHandle<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let session = json!({ "addr": listener.local_addr().unwrap(), "token": "secret" });
+        let dir = home.join(".config").join("ghview");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("ipc-session.json"), session.to_string()).unwrap();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let (reader, mut writer) = tokio::io::split(stream);
+                let mut line = String::new();
+                BufReader::new(reader).read_line(&mut line).await.unwrap();
+                writer
+                    .write_all(b"{\"result\": {\"source\": \"app\"}}\n")
+                    .await
+                    .unwrap();
+            }
+        })
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_falls_back_to_github_and_reconnects() {
+        let scenario = Scenario::new()
+            .token("gho_test", "octocat", &["repo"])
+            .pull(FakePull::new("octo-org/api", 1, "Add endpoint").review_requested());
+        let fake = FakeGitHubEnv::start(scenario)
+            .await
+            .with_env_token("gho_test");
+        fake.env.set("GHVIEW_IPC_TRANSPORT", "tcp");
+        let backend = Backend::default();
+
+        let pr = backend
+            .get_pull_request("octo-org", "api", 1)
+            .await
+            .unwrap();
+        assert_eq!(pr["title"], "Add endpoint");
+        assert!(backend.screenshot(json!({})).await.is_err());
+
+        let app = start_fake_app(&fake.env.home).await;
+        let pr = backend
+            .get_pull_request("octo-org", "api", 1)
+            .await
+            .unwrap();
+        assert_eq!(pr, json!({"source": "app"}));
+
+        app.abort();
+        let _ = app.await;
+        let prs = backend.list_review_requests().await.unwrap();
+        assert_eq!(prs[0]["title"], "Add endpoint");
+
+        let _app = start_fake_app(&fake.env.home).await;
+        let shot = backend.screenshot(json!({})).await.unwrap();
+        assert_eq!(shot, json!({"source": "app"}));
+    }
+}
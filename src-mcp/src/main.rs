@@ -1,12 +1,61 @@
-mod ipc;
+mod backend;
 mod mcp;
 
 use anyhow::Result;
+use clap::Parser;
+use ghview_core::ipc::transport::generate_token;
+use mcp::http::{ENDPOINT, TOKEN_ENV};
 use mcp::McpServer;
+use std::net::SocketAddr;
+
+/// MCP server for ghview - a GitHub PR viewer
+#[derive(Parser)]
+#[command(name = "ghview-mcp", version)]
+struct Args {
+    /// Serve the streamable HTTP transport on this local address instead of stdio.
+    /// Clients authenticate with the bearer token from GHVIEW_MCP_TOKEN, or a random
+    /// one printed at startup.
+    #[arg(
+        long,
+        value_name = "ADDR",
+        num_args = 0..=1,
+        default_missing_value = "127.0.0.1:8765"
+    )]
+    http: Option<SocketAddr>,
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let server = McpServer::new().await?;
-    server.run().await?;
-    Ok(())
+    let args = Args::parse();
+
+    // Tools fall back to the GitHub API when ghview isn't running, which needs its settings
+    if let Err(e) = ghview_core::config::init() {
+        eprintln!("ghview-mcp: using default settings: {}", e);
+    }
+    let server = McpServer::new();
+
+    let Some(addr) = args.http else {
+        return server.run().await;
+    };
+    if !addr.ip().is_loopback() {
+        anyhow::bail!(
+            "Refusing to listen on {}: only local addresses are allowed",
+            addr
+        );
+    }
+    let token = match std::env::var(TOKEN_ENV) {
+        Ok(token) if !token.is_empty() => token,
+        _ => {
+            let token = generate_token();
+            eprintln!("ghview-mcp: bearer token: {}", token);
+            token
+        }
+    };
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!(
+        "ghview-mcp: listening on http://{}{}",
+        listener.local_addr()?,
+        ENDPOINT
+    );
+    server.serve_http(listener, token).await
 }
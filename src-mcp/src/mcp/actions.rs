@@ -0,0 +1,444 @@
+use crate::mcp::coordinates::{pr_coordinates_schema, PrCoordinates};
+use crate::mcp::peer::{Peer, Progress};
+use crate::mcp::protocol::*;
+use crate::mcp::server::McpServer;
+use ghview_core::actions::{self, Action, ActionReport, ReviewEvent, ACTION_NAMES};
+use ghview_core::audit::{self, AuditEntry, AuditOutcome};
+use ghview_core::config::{self, McpSettings};
+use serde_json::{json, Value};
+
+/// Recorded as the source of every action taken through the MCP server
+const AUDIT_SOURCE: &str = "mcp";
+
+/// `pr_coordinates_schema` plus `extra` properties, the ones named in `required`
+/// becoming required too, and the `dry_run` flag every write tool takes
+fn action_schema(extra: Value, required: &[&str]) -> Value {
+    let mut schema = pr_coordinates_schema();
+    let properties = schema["properties"].as_object_mut().unwrap();
+    if let Value::Object(extra) = extra {
+        properties.extend(extra);
+    }
+    properties.insert(
+        "dry_run".to_string(),
+        json!({
+            "type": "boolean",
+            "description": "Return the requests that would be sent to GitHub without sending them"
+        }),
+    );
+    let required_list = schema["required"].as_array_mut().unwrap();
+    required_list.extend(required.iter().map(|name| json!(name)));
+    schema
+}
+
+/// `[mcp]` as config.toml has it now, so enabling a write tool or forcing dry runs
+/// applies without restarting the MCP server; the settings loaded at startup are
+/// kept while the file can't be read
+pub fn mcp_settings() -> McpSettings {
+    config::config_path()
+        .and_then(|path| config::read_from(&path))
+        .map(|settings| settings.mcp)
+        .unwrap_or_else(|_| config::current().mcp.clone())
+}
+
+/// Whether `name` is one of the write tools, enabled or not
+pub fn is_action(name: &str) -> bool {
+    ACTION_NAMES.contains(&name)
+}
+
+/// Write tools enabled in `[mcp] enabled_tools`; the rest stay hidden
+pub fn action_tools(settings: &McpSettings) -> Vec<Tool> {
+    let tools = [
+        Tool {
+            name: "submit_review".to_string(),
+            description:
+                "Submit a review on a pull request: approve it, comment on it or \
+                          request changes. Takes effect on GitHub immediately unless dry_run is set"
+                    .to_string(),
+            input_schema: action_schema(
+                json!({
+                    "event": {
+                        "type": "string",
+                        "enum": ["APPROVE", "COMMENT", "REQUEST_CHANGES"],
+                        "description": "The review verdict"
+                    },
+                    "body": {
+                        "type": "string",
+                        "description": "Review text; required for COMMENT and REQUEST_CHANGES"
+                    }
+                }),
+                &["event"],
+            ),
+        },
+        Tool {
+            name: "add_comment".to_string(),
+            description: "Post a comment on a pull request's conversation. Takes effect on \
+                          GitHub immediately unless dry_run is set"
+                .to_string(),
+            input_schema: action_schema(
+                json!({
+                    "body": {
+                        "type": "string",
+                        "description": "Comment text, in GitHub Markdown"
+                    }
+                }),
+                &["body"],
+            ),
+        },
+        Tool {
+            name: "rerun_failed_checks".to_string(),
+            description: "Re-run the failed check runs of a pull request's head commit. Takes \
+                          effect on GitHub immediately unless dry_run is set"
+                .to_string(),
+            input_schema: action_schema(json!({}), &[]),
+        },
+    ];
+    tools
+        .into_iter()
+        .filter(|tool| settings.enabled_tools.contains(&tool.name))
+        .collect()
+}
+
+/// Read a required non-empty string argument
+fn required_text(args: &Value, key: &str) -> Result<String, String> {
+    match args.get(key) {
+        Some(Value::String(text)) if !text.trim().is_empty() => Ok(text.clone()),
+        Some(_) => Err(format!(
+            "Invalid argument: {} must be a non-empty string",
+            key
+        )),
+        None => Err(format!("Missing required argument: {}", key)),
+    }
+}
+
+/// The action a write tool call asks for, and whether it's a dry run
+pub fn action_from_arguments(
+    name: &str,
+    arguments: &Option<Value>,
+) -> Result<(Action, bool), String> {
+    let PrCoordinates {
+        owner,
+        repo,
+        number,
+    } = PrCoordinates::from_arguments(arguments)?;
+    let args = arguments.as_ref().ok_or("Missing arguments")?;
+    let dry_run = match args.get("dry_run") {
+        Some(value) => value
+            .as_bool()
+            .ok_or("Invalid argument: dry_run must be a boolean")?,
+        None => false,
+    };
+
+    let action = match name {
+        "submit_review" => {
+            let event = match args.get("event") {
+                Some(value) => {
+                    serde_json::from_value::<ReviewEvent>(value.clone()).map_err(|_| {
+                        "Invalid argument: event must be APPROVE, COMMENT or REQUEST_CHANGES"
+                            .to_string()
+                    })?
+                }
+                None => return Err("Missing required argument: event".to_string()),
+            };
+            let body = match args.get("body") {
+                Some(Value::String(body)) => Some(body.clone()),
+                Some(_) => return Err("Invalid argument: body must be a string".to_string()),
+                None => None,
+            };
+            Action::SubmitReview {
+                owner,
+                repo,
+                number,
+                event,
+                body,
+            }
+        }
+        "add_comment" => Action::AddComment {
+            owner,
+            repo,
+            number,
+            body: required_text(args, "body")?,
+        },
+        "rerun_failed_checks" => Action::RerunFailedChecks {
+            owner,
+            repo,
+            number,
+        },
+        _ => return Err(format!("Unknown tool: {}", name)),
+    };
+    action.validate().map_err(|e| e.to_string())?;
+    Ok((action, dry_run))
+}
+
+fn format_report(report: &ActionReport) -> String {
+    let target = report.action.target();
+    if report.requests.is_empty() {
+        return format!("Nothing to do for {}: no failed checks", target);
+    }
+    let mut text = if report.dry_run {
+        format!("Dry run, nothing was sent. Would send for {}:\n", target)
+    } else {
+        format!("Done for {}:\n", target)
+    };
+    for request in &report.requests {
+        text.push_str(&format!(
+            "- {} ({} {})\n",
+            request.description, request.method, request.url
+        ));
+        if report.dry_run {
+            if let Some(body) = &request.body {
+                text.push_str(&format!("  body: {}\n", body));
+            }
+        }
+    }
+    for url in report
+        .responses
+        .iter()
+        .filter_map(|response| response.get("html_url").and_then(Value::as_str))
+    {
+        text.push_str(&format!("{}\n", url));
+    }
+    text
+}
+
+impl McpServer {
+    /// Run a write tool if config.toml enables it, auditing the attempt either way
+    pub(super) async fn call_action(
+        &self,
+        name: &str,
+        arguments: &Option<Value>,
+        peer: &Peer,
+        progress: &Progress,
+    ) -> CallToolResult {
+        let (action, dry_run) = match action_from_arguments(name, arguments) {
+            Ok(parsed) => parsed,
+            Err(e) => return CallToolResult::error(e),
+        };
+
+        let settings = mcp_settings();
+        if !settings.enabled_tools.iter().any(|tool| tool == name) {
+            let reason = format!(
+                "{} is disabled; add it to enabled_tools under [mcp] in ghview's config.toml to allow it",
+                name
+            );
+            let mut entry = AuditEntry::new(AUDIT_SOURCE, action, AuditOutcome::Refused);
+            entry.error = Some(reason.clone());
+            if let Err(e) = audit::record(&entry) {
+                peer.log(
+                    LogLevel::Error,
+                    "audit",
+                    json!({ "tool": name, "error": e.to_string() }),
+                );
+            }
+            return CallToolResult::error(reason);
+        }
+
+        let dry_run = dry_run || settings.dry_run;
+        progress.report(0, &format!("Planning {}", name));
+        match actions::run(action, dry_run, AUDIT_SOURCE).await {
+            Ok(report) => {
+                progress.report(1, &format!("Finished {}", name));
+                let data = serde_json::to_value(&report).unwrap_or_default();
+                CallToolResult::structured(format_report(&report), data)
+            }
+            Err(e) => CallToolResult::error(format!("{} failed: {}", name, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::server::offline_env;
+    use ghview_core::config::Settings;
+    use ghview_core::test_support::FakeGitHubEnv;
+    use ghview_fake_github::{FakePull, Scenario};
+    use serial_test::serial;
+
+    /// Write a config.toml with `[mcp]` set into the isolated config directory
+    fn write_mcp_config(mcp: McpSettings) {
+        let settings = Settings {
+            mcp,
+            ..Settings::default()
+        };
+        config::save_to(&config::config_path().unwrap(), &settings).unwrap();
+    }
+
+    fn enabled(tools: &[&str]) -> McpSettings {
+        McpSettings {
+            enabled_tools: tools.iter().map(|tool| tool.to_string()).collect(),
+            dry_run: false,
+        }
+    }
+
+    fn call(name: &str, arguments: Value) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "tools/call".to_string(),
+            params: Some(json!({ "name": name, "arguments": arguments })),
+        }
+    }
+
+    async fn tool_names(server: &McpServer) -> Vec<String> {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "tools/list".to_string(),
+            params: None,
+        };
+        let result = server.handle_request(&request).await.result.unwrap();
+        result["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_action_from_arguments() {
+        let args = Some(json!({
+            "owner": "octo-org", "repo": "api", "number": 1,
+            "event": "REQUEST_CHANGES", "body": "Needs tests", "dry_run": true
+        }));
+        let (action, dry_run) = action_from_arguments("submit_review", &args).unwrap();
+        assert!(dry_run);
+        assert_eq!(action.name(), "submit_review");
+
+        let error = |name: &str, args: Value| action_from_arguments(name, &Some(args)).unwrap_err();
+        let pr = json!({"owner": "octo-org", "repo": "api", "number": 1});
+        let with = |extra: Value| {
+            let mut args = pr.clone();
+            args.as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            args
+        };
+        assert_eq!(
+            error("submit_review", pr.clone()),
+            "Missing required argument: event"
+        );
+        assert_eq!(
+            error("submit_review", with(json!({"event": "MERGE"}))),
+            "Invalid argument: event must be APPROVE, COMMENT or REQUEST_CHANGES"
+        );
+        assert!(error("submit_review", with(json!({"event": "COMMENT"}))).contains("needs a body"));
+        assert_eq!(
+            error("add_comment", with(json!({"body": " "}))),
+            "Invalid argument: body must be a non-empty string"
+        );
+        assert_eq!(
+            error("rerun_failed_checks", with(json!({"dry_run": "yes"}))),
+            "Invalid argument: dry_run must be a boolean"
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_write_tools_are_disabled_by_default() {
+        let _env = offline_env();
+        let server = McpServer::unconnected();
+        let names = tool_names(&server).await;
+        assert!(ACTION_NAMES
+            .iter()
+            .all(|name| !names.contains(&name.to_string())));
+
+        let response = server
+            .handle_request(&call(
+                "add_comment",
+                json!({"owner": "octo-org", "repo": "api", "number": 1, "body": "Hi"}),
+            ))
+            .await;
+        let result = response.result.unwrap();
+        assert_eq!(result["isError"], true);
+        assert!(result["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("add_comment is disabled"));
+
+        let entries = audit::read_entries(None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].outcome, AuditOutcome::Refused);
+        assert_eq!(entries[0].source, "mcp");
+
+        // Enabling a tool in config.toml applies to the running server
+        write_mcp_config(enabled(&["add_comment"]));
+        assert!(tool_names(&server)
+            .await
+            .contains(&"add_comment".to_string()));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_enabled_tools_honor_dry_run() {
+        let scenario = Scenario::new()
+            .token("gho_test", "octocat", &["repo"])
+            .pull(FakePull::new("octo-org/api", 1, "Add endpoint"));
+        let fake = FakeGitHubEnv::start(scenario)
+            .await
+            .with_env_token("gho_test");
+        fake.env.set("GHVIEW_IPC_TRANSPORT", "tcp");
+        let server = McpServer::unconnected();
+        let review = json!({"owner": "octo-org", "repo": "api", "number": 1, "event": "APPROVE"});
+
+        write_mcp_config(enabled(&["submit_review"]));
+        let names = tool_names(&server).await;
+        assert!(names.contains(&"submit_review".to_string()));
+        assert!(!names.contains(&"add_comment".to_string()));
+
+        let mut dry_run = review.clone();
+        dry_run["dry_run"] = json!(true);
+        let result = server
+            .handle_request(&call("submit_review", dry_run))
+            .await
+            .result
+            .unwrap();
+        assert!(result["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("Dry run"));
+        assert_eq!(
+            result["structuredContent"]["requests"][0]["body"],
+            json!({"event": "APPROVE"})
+        );
+        assert!(fake.server.requests().is_empty());
+
+        // A dry run forced in config wins over the caller
+        write_mcp_config(McpSettings {
+            dry_run: true,
+            ..enabled(&["submit_review"])
+        });
+        let result = server
+            .handle_request(&call("submit_review", review.clone()))
+            .await
+            .result
+            .unwrap();
+        assert_eq!(result["structuredContent"]["dry_run"], true);
+        assert!(fake.server.requests().is_empty());
+
+        write_mcp_config(enabled(&["submit_review"]));
+        let result = server
+            .handle_request(&call("submit_review", review))
+            .await
+            .result
+            .unwrap();
+        assert_eq!(
+            result["structuredContent"]["responses"][0]["state"],
+            "APPROVED"
+        );
+
+        let outcomes: Vec<AuditOutcome> = audit::read_entries(None)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.outcome)
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                AuditOutcome::Performed,
+                AuditOutcome::DryRun,
+                AuditOutcome::DryRun
+            ]
+        );
+    }
+}
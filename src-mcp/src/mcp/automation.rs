@@ -0,0 +1,366 @@
+use crate::mcp::coordinates::{pr_coordinates_schema, PrCoordinates};
+use crate::mcp::peer::Progress;
+use crate::mcp::protocol::*;
+use crate::mcp::server::McpServer;
+use serde_json::{json, Map, Value};
+
+/// Views the ghview window can be asked to show
+const VIEWS: [&str; 2] = ["pull_requests", "account"];
+
+const THEMES: [&str; 3] = ["light", "dark", "system"];
+
+const MAX_WAIT_TIMEOUT_MS: u64 = 60_000;
+
+/// Tools that set up the ghview window, e.g. before a screenshot
+const AUTOMATION_TOOLS: [&str; 5] = [
+    "navigate",
+    "select_pr",
+    "resize_window",
+    "set_theme",
+    "wait_for",
+];
+
+pub fn is_automation(name: &str) -> bool {
+    AUTOMATION_TOOLS.contains(&name)
+}
+
+pub fn automation_tools() -> Vec<Tool> {
+    vec![
+        Tool {
+            name: "navigate".to_string(),
+            description: "Scroll a view of the ghview window into sight".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "view": {
+                        "type": "string",
+                        "enum": VIEWS,
+                        "description": "The review queue or the signed-in account bar"
+                    }
+                },
+                "required": ["view"]
+            }),
+        },
+        Tool {
+            name: "select_pr".to_string(),
+            description: "Highlight a pull request in the ghview window and scroll to it"
+                .to_string(),
+            input_schema: pr_coordinates_schema(),
+        },
+        Tool {
+            name: "resize_window".to_string(),
+            description: "Resize the ghview window's content area, in logical pixels".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "width": { "type": "integer", "minimum": 200, "maximum": 8192 },
+                    "height": { "type": "integer", "minimum": 200, "maximum": 8192 }
+                },
+                "required": ["width", "height"]
+            }),
+        },
+        Tool {
+            name: "set_theme".to_string(),
+            description: "Show the ghview window in light or dark mode, or follow the system again"
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "theme": { "type": "string", "enum": THEMES }
+                },
+                "required": ["theme"]
+            }),
+        },
+        Tool {
+            name: "wait_for".to_string(),
+            description: "Wait until the ghview window is ready, e.g. after navigate or \
+                          refresh and before screenshot. Every condition given must hold"
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "selector": {
+                        "type": "string",
+                        "description": "CSS selector that must match an element"
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "Text the matched element, or the whole page, must contain"
+                    },
+                    "timeout_ms": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_WAIT_TIMEOUT_MS,
+                        "description": "Give up after this long (default 10000)"
+                    }
+                }
+            }),
+        },
+    ]
+}
+
+/// A validated automation tool call
+#[derive(Debug, PartialEq)]
+pub enum UiCommand {
+    Navigate {
+        view: String,
+    },
+    SelectPr(PrCoordinates),
+    ResizeWindow {
+        width: u32,
+        height: u32,
+    },
+    SetTheme {
+        theme: String,
+    },
+    /// Parameters for the ghview `wait_for` IPC method
+    WaitFor(Value),
+}
+
+/// Read a string argument that must be one of `allowed`
+fn one_of(args: &Value, key: &str, allowed: &[&str]) -> Result<String, String> {
+    match args.get(key) {
+        Some(Value::String(value)) if allowed.contains(&value.as_str()) => Ok(value.clone()),
+        Some(_) => Err(format!(
+            "Invalid argument: {} must be one of {}",
+            key,
+            allowed.join(", ")
+        )),
+        None => Err(format!("Missing required argument: {}", key)),
+    }
+}
+
+fn window_dimension(args: &Value, key: &str) -> Result<u32, String> {
+    match args.get(key) {
+        Some(value) => value
+            .as_u64()
+            .filter(|n| *n > 0 && *n <= u32::MAX as u64)
+            .map(|n| n as u32)
+            .ok_or_else(|| format!("Invalid argument: {} must be a positive integer", key)),
+        None => Err(format!("Missing required argument: {}", key)),
+    }
+}
+
+fn wait_params(args: &Value) -> Result<Value, String> {
+    let mut params = Map::new();
+    for key in ["selector", "text"] {
+        match args.get(key) {
+            Some(Value::String(value)) if !value.trim().is_empty() => {
+                params.insert(key.to_string(), json!(value));
+            }
+            Some(_) => {
+                return Err(format!(
+                    "Invalid argument: {} must be a non-empty string",
+                    key
+                ))
+            }
+            None => {}
+        }
+    }
+    if params.is_empty() {
+        return Err("Missing arguments: specify a selector, text or both".to_string());
+    }
+    if let Some(value) = args.get("timeout_ms") {
+        let timeout = value
+            .as_u64()
+            .filter(|n| *n > 0 && *n <= MAX_WAIT_TIMEOUT_MS)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid argument: timeout_ms must be between 1 and {}",
+                    MAX_WAIT_TIMEOUT_MS
+                )
+            })?;
+        params.insert("timeout_ms".to_string(), json!(timeout));
+    }
+    Ok(Value::Object(params))
+}
+
+impl UiCommand {
+    pub fn from_arguments(name: &str, arguments: &Option<Value>) -> Result<Self, String> {
+        if name == "select_pr" {
+            return PrCoordinates::from_arguments(arguments).map(UiCommand::SelectPr);
+        }
+        let args = arguments.as_ref().ok_or("Missing arguments")?;
+        match name {
+            "navigate" => Ok(UiCommand::Navigate {
+                view: one_of(args, "view", &VIEWS)?,
+            }),
+            "resize_window" => Ok(UiCommand::ResizeWindow {
+                width: window_dimension(args, "width")?,
+                height: window_dimension(args, "height")?,
+            }),
+            "set_theme" => Ok(UiCommand::SetTheme {
+                theme: one_of(args, "theme", &THEMES)?,
+            }),
+            "wait_for" => Ok(UiCommand::WaitFor(wait_params(args)?)),
+            _ => Err(format!("Unknown tool: {}", name)),
+        }
+    }
+}
+
+impl McpServer {
+    /// Drive the ghview window; these need the app running
+    pub(super) async fn call_automation(
+        &self,
+        name: &str,
+        arguments: &Option<Value>,
+        progress: &Progress,
+    ) -> CallToolResult {
+        let command = match UiCommand::from_arguments(name, arguments) {
+            Ok(command) => command,
+            Err(e) => return CallToolResult::error(e),
+        };
+
+        let result = match &command {
+            UiCommand::Navigate { view } => self
+                .backend
+                .navigate(view)
+                .await
+                .map(|_| CallToolResult::success(format!("Showing the {} view", view))),
+            UiCommand::SelectPr(pr) => self
+                .backend
+                .select_pr(&pr.owner, &pr.repo, pr.number)
+                .await
+                .map(|_| {
+                    CallToolResult::success(format!(
+                        "Selected {}/{}#{}",
+                        pr.owner, pr.repo, pr.number
+                    ))
+                }),
+            UiCommand::ResizeWindow { width, height } => self
+                .backend
+                .resize_window(*width, *height)
+                .await
+                .map(|size| {
+                    CallToolResult::structured(
+                        format!("Resized the window to {}x{}", size["width"], size["height"]),
+                        size,
+                    )
+                }),
+            UiCommand::SetTheme { theme } => {
+                self.backend
+                    .set_theme(theme)
+                    .await
+                    .map(|_| match theme.as_str() {
+                        "system" => CallToolResult::success("The window follows the system theme"),
+                        _ => CallToolResult::success(format!("Switched to the {} theme", theme)),
+                    })
+            }
+            UiCommand::WaitFor(params) => {
+                progress.report(0, "Waiting for the condition");
+                self.backend.wait_for(params.clone()).await.map(|result| {
+                    progress.report(1, "Condition met");
+                    CallToolResult::structured(
+                        format!("Condition met after {} ms", result["elapsed_ms"]),
+                        result,
+                    )
+                })
+            }
+        };
+        result.unwrap_or_else(|e| CallToolResult::error(format!("{} failed: {}", name, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(name: &str, arguments: Value) -> Result<UiCommand, String> {
+        UiCommand::from_arguments(name, &Some(arguments))
+    }
+
+    #[test]
+    fn test_tools_match_names() {
+        let names: Vec<String> = automation_tools().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, AUTOMATION_TOOLS);
+        assert!(!is_automation("screenshot"));
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            parse("navigate", json!({"view": "account"})).unwrap(),
+            UiCommand::Navigate {
+                view: "account".to_string()
+            }
+        );
+        assert_eq!(
+            parse(
+                "select_pr",
+                json!({"owner": "octo-org", "repo": "api", "number": 7})
+            )
+            .unwrap(),
+            UiCommand::SelectPr(PrCoordinates {
+                owner: "octo-org".to_string(),
+                repo: "api".to_string(),
+                number: 7
+            })
+        );
+        assert_eq!(
+            parse("resize_window", json!({"width": 1280, "height": 800})).unwrap(),
+            UiCommand::ResizeWindow {
+                width: 1280,
+                height: 800
+            }
+        );
+        assert_eq!(
+            parse(
+                "wait_for",
+                json!({"selector": ".pr-card", "timeout_ms": 5000, "extra": true})
+            )
+            .unwrap(),
+            UiCommand::WaitFor(json!({"selector": ".pr-card", "timeout_ms": 5000}))
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_arguments() {
+        let error = |name: &str, args: Value| parse(name, args).unwrap_err();
+
+        assert_eq!(
+            error("navigate", json!({"view": "settings"})),
+            "Invalid argument: view must be one of pull_requests, account"
+        );
+        assert_eq!(
+            error("set_theme", json!({})),
+            "Missing required argument: theme"
+        );
+        assert_eq!(
+            error("resize_window", json!({"width": 0, "height": 600})),
+            "Invalid argument: width must be a positive integer"
+        );
+        assert_eq!(
+            error("select_pr", json!({"owner": "octo-org", "repo": "api"})),
+            "Missing required argument: number"
+        );
+        assert_eq!(
+            error("wait_for", json!({"timeout_ms": 100})),
+            "Missing arguments: specify a selector, text or both"
+        );
+        assert_eq!(
+            error("wait_for", json!({"text": ""})),
+            "Invalid argument: text must be a non-empty string"
+        );
+        assert_eq!(
+            error("wait_for", json!({"text": "Ready", "timeout_ms": 600000})),
+            "Invalid argument: timeout_ms must be between 1 and 60000"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_call_fails_without_the_app() {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "tools/call".to_string(),
+            params: Some(json!({ "name": "set_theme", "arguments": {"theme": "dark"} })),
+        };
+        let response = McpServer::unconnected().handle_request(&request).await;
+        let result = response.result.unwrap();
+        assert_eq!(result["isError"], json!(true));
+        assert!(result["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("set_theme failed:"));
+    }
+}
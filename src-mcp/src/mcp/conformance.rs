@@ -0,0 +1,353 @@
+//! Protocol conformance tests that drive a server over in-memory pipes, as a client would
+
+use crate::mcp::server::{offline_env, McpServer};
+use ghview_core::test_support::FakeGitHubEnv;
+use ghview_fake_github::{FakePull, Scenario};
+use serde_json::{json, Value};
+use serial_test::serial;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, Lines, WriteHalf};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Client {
+    writer: WriteHalf<DuplexStream>,
+    lines: Lines<BufReader<tokio::io::ReadHalf<DuplexStream>>>,
+    server: JoinHandle<anyhow::Result<()>>,
+}
+
+impl Client {
+    fn connect(server: McpServer) -> Self {
+        let (client, server_side) = tokio::io::duplex(64 * 1024);
+        let (server_reader, server_writer) = tokio::io::split(server_side);
+        let server = tokio::spawn(async move { server.serve(server_reader, server_writer).await });
+        let (reader, writer) = tokio::io::split(client);
+        Self {
+            writer,
+            lines: BufReader::new(reader).lines(),
+            server,
+        }
+    }
+
+    /// A client that has completed the initialization handshake
+    async fn initialized(server: McpServer) -> Self {
+        let mut client = Self::connect(server);
+        client
+            .send_raw(
+                &json!({
+                    "jsonrpc": "2.0",
+                    "id": 0,
+                    "method": "initialize",
+                    "params": {
+                        "protocolVersion": "2025-06-18",
+                        "capabilities": {},
+                        "clientInfo": {"name": "conformance", "version": "1"}
+                    }
+                })
+                .to_string(),
+            )
+            .await;
+        assert!(client.recv().await["result"].is_object());
+        client.notify("notifications/initialized", None).await;
+        client
+    }
+
+    async fn send_raw(&mut self, line: &str) {
+        self.writer.write_all(line.as_bytes()).await.unwrap();
+        self.writer.write_all(b"\n").await.unwrap();
+    }
+
+    async fn request(&mut self, id: i64, method: &str, params: Value) {
+        let message = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+        self.send_raw(&message.to_string()).await;
+    }
+
+    async fn notify(&mut self, method: &str, params: Option<Value>) {
+        let mut message = json!({"jsonrpc": "2.0", "method": method});
+        if let Some(params) = params {
+            message["params"] = params;
+        }
+        self.send_raw(&message.to_string()).await;
+    }
+
+    async fn recv(&mut self) -> Value {
+        let line = tokio::time::timeout(TIMEOUT, self.lines.next_line())
+            .await
+            .expect("timed out waiting for the server")
+            .unwrap()
+            .expect("server closed the connection");
+        serde_json::from_str(&line).unwrap()
+    }
+
+    /// The response to request `id`, skipping notifications sent before it
+    async fn response(&mut self, id: i64) -> Value {
+        loop {
+            let message = self.recv().await;
+            if message.get("method").is_none() {
+                assert_eq!(message["id"], json!(id), "{}", message);
+                return message;
+            }
+        }
+    }
+
+    /// Close the input and collect everything the server writes before exiting
+    async fn finish(mut self) -> Vec<Value> {
+        self.writer.shutdown().await.unwrap();
+        let mut messages = Vec::new();
+        while let Ok(Some(line)) = self.lines.next_line().await {
+            messages.push(serde_json::from_str(&line).unwrap());
+        }
+        self.server.await.unwrap().unwrap();
+        messages
+    }
+}
+
+#[tokio::test]
+async fn test_initialize_negotiates_protocol_version() {
+    for (requested, negotiated) in [
+        ("2024-11-05", "2024-11-05"),
+        ("2025-03-26", "2025-03-26"),
+        ("2099-01-01", "2025-06-18"),
+    ] {
+        let mut client = Client::connect(McpServer::unconnected());
+        client
+            .request(
+                1,
+                "initialize",
+                json!({"protocolVersion": requested, "capabilities": {}}),
+            )
+            .await;
+        let result = client.response(1).await["result"].clone();
+        assert_eq!(result["protocolVersion"], negotiated);
+        assert_eq!(result["serverInfo"]["name"], "ghview-mcp");
+        assert_eq!(result["capabilities"]["logging"], json!({}));
+    }
+
+    let mut client = Client::connect(McpServer::unconnected());
+    client.request(1, "initialize", json!({})).await;
+    assert_eq!(client.response(1).await["error"]["code"], -32602);
+}
+
+#[tokio::test]
+async fn test_requests_before_initialize_are_rejected() {
+    let mut client = Client::connect(McpServer::unconnected());
+    client.request(1, "tools/list", json!({})).await;
+    assert_eq!(client.response(1).await["error"]["code"], -32600);
+
+    client.request(2, "ping", json!({})).await;
+    assert_eq!(client.response(2).await["result"], json!({}));
+}
+
+#[tokio::test]
+async fn test_notifications_get_no_response() {
+    let mut client = Client::initialized(McpServer::unconnected()).await;
+    client.notify("notifications/initialized", None).await;
+    client
+        .notify("notifications/unknown", Some(json!({})))
+        .await;
+    client
+        .notify(
+            "notifications/cancelled",
+            Some(json!({"requestId": 99, "reason": "nothing to cancel"})),
+        )
+        .await;
+    client.request(1, "ping", json!({})).await;
+
+    // The first message after the notifications is the ping's response
+    let message = client.recv().await;
+    assert_eq!(message["id"], 1);
+    assert_eq!(message["result"], json!({}));
+}
+
+#[tokio::test]
+async fn test_malformed_messages() {
+    let mut client = Client::initialized(McpServer::unconnected()).await;
+
+    client.send_raw("{not json").await;
+    let message = client.recv().await;
+    assert_eq!(message["error"]["code"], -32700);
+    assert_eq!(message["id"], Value::Null);
+
+    client.send_raw(r#"{"jsonrpc": "2.0", "id": 3}"#).await;
+    let message = client.recv().await;
+    assert_eq!(message["error"]["code"], -32600);
+    assert_eq!(message["id"], 3);
+
+    client
+        .send_raw(r#"{"jsonrpc": "1.0", "id": 4, "method": "ping"}"#)
+        .await;
+    assert_eq!(client.recv().await["error"]["code"], -32600);
+
+    client.request(5, "no/such/method", json!({})).await;
+    assert_eq!(client.response(5).await["error"]["code"], -32601);
+
+    // Responses from the client are ignored
+    client
+        .send_raw(r#"{"jsonrpc": "2.0", "id": 6, "result": {}}"#)
+        .await;
+    client.request(7, "ping", json!({})).await;
+    assert_eq!(client.recv().await["id"], 7);
+}
+
+#[tokio::test]
+async fn test_batches() {
+    let mut client = Client::initialized(McpServer::unconnected()).await;
+
+    client.send_raw("[]").await;
+    assert_eq!(client.recv().await["error"]["code"], -32600);
+
+    client
+        .send_raw(
+            &json!([
+                {"jsonrpc": "2.0", "id": 1, "method": "ping"},
+                {"jsonrpc": "2.0", "method": "notifications/initialized"},
+                {"jsonrpc": "2.0", "id": 2, "method": "tools/list"},
+                {"jsonrpc": "2.0", "id": 3, "method": "initialize", "params": {}}
+            ])
+            .to_string(),
+        )
+        .await;
+    let responses = client.recv().await;
+    let mut responses = responses.as_array().unwrap().clone();
+    responses.sort_by_key(|response| response["id"].as_i64());
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[0]["result"], json!({}));
+    assert!(responses[1]["result"]["tools"].is_array());
+    assert_eq!(responses[2]["error"]["code"], -32600);
+
+    // A batch of notifications only gets no response at all
+    client
+        .send_raw(r#"[{"jsonrpc": "2.0", "method": "notifications/initialized"}]"#)
+        .await;
+    client.request(4, "ping", json!({})).await;
+    assert_eq!(client.recv().await["id"], 4);
+}
+
+#[tokio::test]
+async fn test_set_level_filters_log_messages() {
+    let mut client = Client::initialized(McpServer::unconnected()).await;
+
+    client
+        .request(1, "logging/setLevel", json!({"level": "verbose"}))
+        .await;
+    assert_eq!(client.response(1).await["error"]["code"], -32602);
+
+    client
+        .request(2, "tools/call", json!({"name": "no_such_tool"}))
+        .await;
+    let message = client.recv().await;
+    assert_eq!(message["method"], "notifications/message");
+    assert_eq!(message["params"]["level"], "error");
+    assert_eq!(message["params"]["data"]["tool"], "no_such_tool");
+    assert_eq!(client.response(2).await["result"]["isError"], true);
+
+    client
+        .request(3, "logging/setLevel", json!({"level": "critical"}))
+        .await;
+    assert_eq!(client.response(3).await["result"], json!({}));
+    client
+        .request(4, "tools/call", json!({"name": "no_such_tool"}))
+        .await;
+    assert_eq!(client.recv().await["id"], 4);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_cancel_long_request_while_serving_others() {
+    let env = offline_env();
+
+    // An app that accepts the screenshot request and never answers it
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let session = json!({"addr": listener.local_addr().unwrap(), "token": "secret"});
+    let dir = env.home.join(".config").join("ghview");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("ipc-session.json"), session.to_string()).unwrap();
+    let app = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut lines = BufReader::new(stream).lines();
+        lines.next_line().await.unwrap().unwrap();
+        // Returns once the server drops the connection
+        lines.next_line().await.unwrap()
+    });
+
+    let mut client = Client::initialized(McpServer::new()).await;
+    client
+        .request(
+            1,
+            "tools/call",
+            json!({"name": "screenshot", "arguments": {"output_dir": env.home}}),
+        )
+        .await;
+    client.request(2, "ping", json!({})).await;
+    assert_eq!(client.recv().await["id"], 2);
+
+    client
+        .notify(
+            "notifications/cancelled",
+            Some(json!({"requestId": 1, "reason": "took too long"})),
+        )
+        .await;
+    let closed = tokio::time::timeout(TIMEOUT, app).await.unwrap().unwrap();
+    assert_eq!(closed, None);
+
+    // The cancelled request is never answered, even once the input closes
+    assert!(client.finish().await.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_progress_notifications_precede_response() {
+    let scenario = Scenario::new()
+        .token("gho_test", "octocat", &["repo"])
+        .pull(FakePull::new("octo-org/api", 1, "Add endpoint").review_requested());
+    let fake = FakeGitHubEnv::start(scenario)
+        .await
+        .with_env_token("gho_test");
+    fake.env.set("GHVIEW_IPC_TRANSPORT", "tcp");
+
+    let mut client = Client::initialized(McpServer::new()).await;
+    client
+        .request(
+            1,
+            "tools/call",
+            json!({
+                "name": "list_review_requests",
+                "arguments": {},
+                "_meta": {"progressToken": "list-1"}
+            }),
+        )
+        .await;
+
+    let mut progress = Vec::new();
+    let response = loop {
+        let message = client.recv().await;
+        if message["method"] == "notifications/progress" {
+            assert_eq!(message["params"]["progressToken"], "list-1");
+            progress.push(message["params"]["progress"].as_u64().unwrap());
+        } else {
+            break message;
+        }
+    };
+    assert_eq!(progress, vec![0, 1]);
+    assert_eq!(
+        response["result"]["structuredContent"]["pull_requests"][0]["title"],
+        "Add endpoint"
+    );
+}
+
+#[tokio::test]
+async fn test_pending_responses_are_written_before_exit() {
+    let mut client = Client::initialized(McpServer::unconnected()).await;
+    client.request(1, "tools/list", json!({})).await;
+    client.request(2, "ping", json!({})).await;
+    let mut ids: Vec<i64> = client
+        .finish()
+        .await
+        .iter()
+        .map(|message| message["id"].as_i64().unwrap())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec![1, 2]);
+}
@@ -0,0 +1,255 @@
+use ghview_core::actions::is_valid_name;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+const PR_URI_PREFIX: &str = "ghview://pr/";
+
+/// URI template for pull request resources
+pub const PR_URI_TEMPLATE: &str = "ghview://pr/{owner}/{repo}/{number}";
+
+/// Identifies a pull request in tool arguments and resource URIs
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrCoordinates {
+    pub owner: String,
+    pub repo: String,
+    pub number: i32,
+}
+
+impl PrCoordinates {
+    pub fn from_arguments(arguments: &Option<Value>) -> Result<Self, String> {
+        let args = arguments.as_ref().ok_or("Missing arguments")?;
+        let owner = required_name(args, "owner")?;
+        let repo = required_name(args, "repo")?;
+        let number = match args.get("number") {
+            Some(value) => value
+                .as_i64()
+                .and_then(valid_number)
+                .ok_or("Invalid argument: number must be a positive integer")?,
+            None => return Err("Missing required argument: number".to_string()),
+        };
+        Ok(Self {
+            owner,
+            repo,
+            number,
+        })
+    }
+
+    /// Parse prompt arguments, where every value (including the number) is a string
+    pub fn from_prompt_arguments(arguments: &HashMap<String, String>) -> Result<Self, String> {
+        let get = |key: &str| {
+            arguments
+                .get(key)
+                .ok_or_else(|| format!("Missing required argument: {}", key))
+        };
+        let owner = get("owner")?;
+        let repo = get("repo")?;
+        for (key, value) in [("owner", owner), ("repo", repo)] {
+            if !is_valid_name(value) {
+                return Err(format!("Invalid argument: {} must be a bare name", key));
+            }
+        }
+        let number = get("number")?
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(valid_number)
+            .ok_or("Invalid argument: number must be a positive integer")?;
+        Ok(Self {
+            owner: owner.clone(),
+            repo: repo.clone(),
+            number,
+        })
+    }
+
+    /// Parse a `ghview://pr/{owner}/{repo}/{number}` resource URI
+    pub fn from_uri(uri: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid pull request URI: {}", uri);
+        let path = uri.strip_prefix(PR_URI_PREFIX).ok_or_else(invalid)?;
+        let parts: Vec<&str> = path.split('/').collect();
+        let [owner, repo, number] = parts.as_slice() else {
+            return Err(invalid());
+        };
+        if !is_valid_name(owner) || !is_valid_name(repo) {
+            return Err(invalid());
+        }
+        let number = number
+            .parse::<i64>()
+            .ok()
+            .and_then(valid_number)
+            .ok_or_else(invalid)?;
+        Ok(Self {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            number,
+        })
+    }
+
+    pub fn to_uri(&self) -> String {
+        format!(
+            "{}{}/{}/{}",
+            PR_URI_PREFIX, self.owner, self.repo, self.number
+        )
+    }
+}
+
+fn valid_number(n: i64) -> Option<i32> {
+    (n > 0 && n <= i32::MAX as i64).then_some(n as i32)
+}
+
+/// Read a required owner/repository name argument
+fn required_name(args: &Value, key: &str) -> Result<String, String> {
+    let value = match args.get(key) {
+        Some(value) => value
+            .as_str()
+            .ok_or_else(|| format!("Invalid argument: {} must be a string", key))?,
+        None => return Err(format!("Missing required argument: {}", key)),
+    };
+    if !is_valid_name(value) {
+        return Err(format!("Invalid argument: {} must be a bare name", key));
+    }
+    Ok(value.to_string())
+}
+
+pub fn pr_coordinates_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "owner": {
+                "type": "string",
+                "description": "Repository owner (user or organization)"
+            },
+            "repo": {
+                "type": "string",
+                "description": "Repository name"
+            },
+            "number": {
+                "type": "integer",
+                "minimum": 1,
+                "description": "Pull request number"
+            }
+        },
+        "required": ["owner", "repo", "number"]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pr_coordinates_valid() {
+        let args = Some(json!({"owner": "mirakui", "repo": "ghview", "number": 42}));
+        assert_eq!(
+            PrCoordinates::from_arguments(&args).unwrap(),
+            PrCoordinates {
+                owner: "mirakui".to_string(),
+                repo: "ghview".to_string(),
+                number: 42,
+            }
+        );
+    }
+
+    #[test]
+    fn test_pr_coordinates_missing_fields() {
+        assert_eq!(
+            PrCoordinates::from_arguments(&None).unwrap_err(),
+            "Missing arguments"
+        );
+        assert_eq!(
+            PrCoordinates::from_arguments(&Some(json!({"repo": "ghview", "number": 1})))
+                .unwrap_err(),
+            "Missing required argument: owner"
+        );
+        assert_eq!(
+            PrCoordinates::from_arguments(&Some(json!({"owner": "mirakui", "repo": "ghview"})))
+                .unwrap_err(),
+            "Missing required argument: number"
+        );
+    }
+
+    #[test]
+    fn test_pr_coordinates_invalid_values() {
+        for number in [
+            json!(0),
+            json!(-3),
+            json!("42"),
+            json!(1.5),
+            json!(3_000_000_000u64),
+        ] {
+            let args = Some(json!({"owner": "mirakui", "repo": "ghview", "number": number}));
+            assert_eq!(
+                PrCoordinates::from_arguments(&args).unwrap_err(),
+                "Invalid argument: number must be a positive integer"
+            );
+        }
+
+        let args = Some(json!({"owner": "mirakui", "repo": "mirakui/ghview", "number": 1}));
+        assert_eq!(
+            PrCoordinates::from_arguments(&args).unwrap_err(),
+            "Invalid argument: repo must be a bare name"
+        );
+
+        for repo in ["..", "ghview?ref=main", "ghview%2F.."] {
+            let args = Some(json!({"owner": "mirakui", "repo": repo, "number": 1}));
+            assert!(
+                PrCoordinates::from_arguments(&args).is_err(),
+                "accepted {}",
+                repo
+            );
+        }
+
+        let args = Some(json!({"owner": 7, "repo": "ghview", "number": 1}));
+        assert_eq!(
+            PrCoordinates::from_arguments(&args).unwrap_err(),
+            "Invalid argument: owner must be a string"
+        );
+    }
+
+    #[test]
+    fn test_pr_coordinates_uri_roundtrip() {
+        let pr = PrCoordinates::from_uri("ghview://pr/mirakui/ghview/42").unwrap();
+        assert_eq!(pr.owner, "mirakui");
+        assert_eq!(pr.repo, "ghview");
+        assert_eq!(pr.number, 42);
+        assert_eq!(pr.to_uri(), "ghview://pr/mirakui/ghview/42");
+    }
+
+    #[test]
+    fn test_pr_coordinates_invalid_uri() {
+        for uri in [
+            "https://github.com/mirakui/ghview/pull/42",
+            "ghview://pr/mirakui/ghview",
+            "ghview://pr/mirakui/ghview/42/files",
+            "ghview://pr/mirakui//42",
+            "ghview://pr/mirakui/../42",
+            "ghview://pr/mirakui/ghview/zero",
+            "ghview://pr/mirakui/ghview/0",
+        ] {
+            assert!(PrCoordinates::from_uri(uri).is_err(), "accepted {}", uri);
+        }
+    }
+
+    #[test]
+    fn test_pr_coordinates_from_prompt_arguments() {
+        let mut args = HashMap::new();
+        args.insert("owner".to_string(), "mirakui".to_string());
+        args.insert("repo".to_string(), "ghview".to_string());
+        args.insert("number".to_string(), "42".to_string());
+        assert_eq!(
+            PrCoordinates::from_prompt_arguments(&args).unwrap().number,
+            42
+        );
+
+        args.insert("number".to_string(), "#42".to_string());
+        assert_eq!(
+            PrCoordinates::from_prompt_arguments(&args).unwrap_err(),
+            "Invalid argument: number must be a positive integer"
+        );
+
+        args.remove("repo");
+        assert_eq!(
+            PrCoordinates::from_prompt_arguments(&args).unwrap_err(),
+            "Missing required argument: repo"
+        );
+    }
+}
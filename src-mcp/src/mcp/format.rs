@@ -0,0 +1,379 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+// Minimal views of the models returned by ghview over IPC; unknown fields are ignored
+
+#[derive(Debug, Deserialize)]
+pub struct PrSummary {
+    pub number: i64,
+    pub title: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub draft: bool,
+    pub state: String,
+    pub updated_at: String,
+    pub user: UserSummary,
+    pub repository: RepositorySummary,
+    pub check_status: Option<CheckSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserSummary {
+    pub login: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RepositorySummary {
+    pub full_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CheckSummary {
+    pub state: String,
+    pub total_count: i64,
+    #[serde(default)]
+    pub statuses: Vec<StatusSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatusSummary {
+    pub state: String,
+    pub context: String,
+    pub description: Option<String>,
+    pub target_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrDetailsSummary {
+    #[serde(flatten)]
+    pub pr: PrSummary,
+    pub body: Option<String>,
+    pub additions: i64,
+    pub deletions: i64,
+    pub changed_files: i64,
+    #[serde(default)]
+    pub files: Vec<ChangedFileSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangedFileSummary {
+    pub filename: String,
+    pub status: String,
+    pub additions: i64,
+    pub deletions: i64,
+}
+
+fn checks_label(check_status: &Option<CheckSummary>) -> String {
+    match check_status {
+        Some(checks) if checks.total_count > 0 => {
+            format!("{} ({} checks)", checks.state, checks.total_count)
+        }
+        Some(checks) => checks.state.clone(),
+        None => "unknown".to_string(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrViewSummary {
+    pub group_by: String,
+    pub groups: Vec<PrGroupSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrGroupSummary {
+    pub label: String,
+    pub pull_requests: Vec<PrSummary>,
+}
+
+fn queue_heading(count: usize) -> String {
+    format!(
+        "{} pull request{} waiting for your review:",
+        count,
+        if count == 1 { " is" } else { "s are" }
+    )
+}
+
+fn push_pr_lines(lines: &mut Vec<String>, pr: &PrSummary) {
+    lines.push(format!(
+        "- {}#{} {}{} (by {}, checks: {})",
+        pr.repository.full_name,
+        pr.number,
+        pr.title,
+        if pr.draft { " [draft]" } else { "" },
+        pr.user.login,
+        checks_label(&pr.check_status)
+    ));
+    lines.push(format!("  {}", pr.html_url));
+}
+
+/// Render a review queue as a bulleted list
+pub fn format_pr_list(prs: &Value) -> String {
+    let prs: Vec<PrSummary> = match serde_json::from_value(prs.clone()) {
+        Ok(prs) => prs,
+        Err(e) => return format!("Unexpected pull request data: {}", e),
+    };
+
+    if prs.is_empty() {
+        return "No pull requests are waiting for your review.".to_string();
+    }
+
+    let mut lines = vec![queue_heading(prs.len())];
+    for pr in &prs {
+        push_pr_lines(&mut lines, pr);
+    }
+    lines.join("\n")
+}
+
+/// Render a grouped review queue, with a heading per group unless it is ungrouped
+pub fn format_pr_view(view: &Value) -> String {
+    let view: PrViewSummary = match serde_json::from_value(view.clone()) {
+        Ok(view) => view,
+        Err(e) => return format!("Unexpected pull request data: {}", e),
+    };
+
+    let count: usize = view.groups.iter().map(|g| g.pull_requests.len()).sum();
+    if count == 0 {
+        return "No pull requests are waiting for your review.".to_string();
+    }
+
+    let mut lines = vec![queue_heading(count)];
+    for group in &view.groups {
+        if view.group_by != "none" {
+            lines.push(format!(
+                "\n{} ({}):",
+                group.label,
+                group.pull_requests.len()
+            ));
+        }
+        for pr in &group.pull_requests {
+            push_pr_lines(&mut lines, pr);
+        }
+    }
+    lines.join("\n")
+}
+
+/// Render a single pull request
+pub fn format_pull_request(pr: &Value) -> String {
+    let pr: PrSummary = match serde_json::from_value(pr.clone()) {
+        Ok(pr) => pr,
+        Err(e) => return format!("Unexpected pull request data: {}", e),
+    };
+
+    [
+        format!("{}#{}: {}", pr.repository.full_name, pr.number, pr.title),
+        format!("Author: {}", pr.user.login),
+        format!(
+            "State: {}{}",
+            pr.state,
+            if pr.draft { " (draft)" } else { "" }
+        ),
+        format!("Checks: {}", checks_label(&pr.check_status)),
+        format!("Updated: {}", pr.updated_at),
+        format!("URL: {}", pr.html_url),
+    ]
+    .join("\n")
+}
+
+/// Render a combined check status with one line per status context
+pub fn format_check_status(owner: &str, repo: &str, number: i32, status: &Value) -> String {
+    let status: CheckSummary = match serde_json::from_value(status.clone()) {
+        Ok(status) => status,
+        Err(e) => return format!("Unexpected check status data: {}", e),
+    };
+
+    let mut lines = vec![format!(
+        "Checks for {}/{}#{}: {} ({} total)",
+        owner, repo, number, status.state, status.total_count
+    )];
+    for check in &status.statuses {
+        let mut line = format!("- {}: {}", check.context, check.state);
+        if let Some(description) = &check.description {
+            line.push_str(&format!(" - {}", description));
+        }
+        if let Some(url) = &check.target_url {
+            line.push_str(&format!(" ({})", url));
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+/// Render a pull request as a Markdown document for use as an MCP resource
+pub fn format_pr_resource(details: &Value) -> String {
+    let details: PrDetailsSummary = match serde_json::from_value(details.clone()) {
+        Ok(details) => details,
+        Err(e) => return format!("Unexpected pull request data: {}", e),
+    };
+    let pr = &details.pr;
+
+    let mut lines = vec![
+        format!("# {}#{}: {}", pr.repository.full_name, pr.number, pr.title),
+        String::new(),
+        format!("- Author: {}", pr.user.login),
+        format!(
+            "- State: {}{}",
+            pr.state,
+            if pr.draft { " (draft)" } else { "" }
+        ),
+        format!("- Updated: {}", pr.updated_at),
+        format!("- URL: {}", pr.html_url),
+        String::new(),
+        "## Description".to_string(),
+        String::new(),
+    ];
+    match details.body.as_deref().map(str::trim) {
+        Some(body) if !body.is_empty() => lines.push(body.to_string()),
+        _ => lines.push("_No description provided._".to_string()),
+    }
+
+    lines.push(String::new());
+    lines.push("## Diff summary".to_string());
+    lines.push(String::new());
+    lines.push(format!(
+        "{} file{} changed, +{} -{}",
+        details.changed_files,
+        if details.changed_files == 1 { "" } else { "s" },
+        details.additions,
+        details.deletions
+    ));
+    if !details.files.is_empty() {
+        lines.push(String::new());
+        for file in &details.files {
+            lines.push(format!(
+                "- `{}` ({}, +{} -{})",
+                file.filename, file.status, file.additions, file.deletions
+            ));
+        }
+        if (details.files.len() as i64) < details.changed_files {
+            lines.push(format!(
+                "- ... and {} more",
+                details.changed_files - details.files.len() as i64
+            ));
+        }
+    }
+
+    lines.push(String::new());
+    lines.push("## Checks".to_string());
+    lines.push(String::new());
+    match &pr.check_status {
+        Some(checks) => {
+            lines.push(format!("Overall: {}", checks_label(&pr.check_status)));
+            for check in &checks.statuses {
+                let mut line = format!("- {}: {}", check.context, check.state);
+                if let Some(description) = &check.description {
+                    line.push_str(&format!(" - {}", description));
+                }
+                lines.push(line);
+            }
+        }
+        None => lines.push("Check status unavailable.".to_string()),
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_pr() -> Value {
+        json!({
+            "id": 1,
+            "number": 42,
+            "title": "Add feature",
+            "html_url": "https://github.com/mirakui/ghview/pull/42",
+            "state": "open",
+            "draft": false,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-02T00:00:00Z",
+            "user": {"login": "alice", "id": 1, "avatar_url": "", "html_url": ""},
+            "labels": [],
+            "requested_reviewers": [],
+            "repository": {"full_name": "mirakui/ghview", "name": "ghview"},
+            "check_status": {"state": "failure", "total_count": 2, "statuses": []}
+        })
+    }
+
+    #[test]
+    fn test_format_pr_list() {
+        let text = format_pr_list(&json!([sample_pr()]));
+        assert!(text.starts_with("1 pull request is waiting for your review:"));
+        assert!(
+            text.contains("- mirakui/ghview#42 Add feature (by alice, checks: failure (2 checks))")
+        );
+    }
+
+    #[test]
+    fn test_format_pr_view_groups() {
+        let view = json!({
+            "group_by": "check_state",
+            "total": 1,
+            "groups": [
+                {"key": "failure", "label": "Failing", "count": 1, "pull_requests": [sample_pr()]}
+            ]
+        });
+        let text = format_pr_view(&view);
+        assert!(text.starts_with("1 pull request is waiting for your review:\n\nFailing (1):\n"));
+        assert!(text.contains("- mirakui/ghview#42 Add feature"));
+    }
+
+    #[test]
+    fn test_format_pr_list_empty() {
+        assert_eq!(
+            format_pr_list(&json!([])),
+            "No pull requests are waiting for your review."
+        );
+    }
+
+    #[test]
+    fn test_format_check_status() {
+        let status = json!({
+            "state": "failure",
+            "total_count": 1,
+            "statuses": [{
+                "state": "failure",
+                "context": "ci/build",
+                "description": "Build failed",
+                "target_url": "https://ci.example.com/1"
+            }]
+        });
+        let text = format_check_status("mirakui", "ghview", 42, &status);
+        assert!(text.contains("Checks for mirakui/ghview#42: failure (1 total)"));
+        assert!(text.contains("- ci/build: failure - Build failed (https://ci.example.com/1)"));
+    }
+
+    #[test]
+    fn test_format_pr_resource() {
+        let mut details = sample_pr();
+        details["body"] = json!("Fixes the thing.");
+        details["additions"] = json!(10);
+        details["deletions"] = json!(2);
+        details["changed_files"] = json!(3);
+        details["files"] = json!([
+            {"filename": "src/main.rs", "status": "modified", "additions": 8, "deletions": 2},
+            {"filename": "README.md", "status": "added", "additions": 2, "deletions": 0}
+        ]);
+
+        let text = format_pr_resource(&details);
+        assert!(text.starts_with("# mirakui/ghview#42: Add feature"));
+        assert!(text.contains("## Description\n\nFixes the thing."));
+        assert!(text.contains("3 files changed, +10 -2"));
+        assert!(text.contains("- `src/main.rs` (modified, +8 -2)"));
+        assert!(text.contains("- ... and 1 more"));
+        assert!(text.contains("Overall: failure (2 checks)"));
+    }
+
+    #[test]
+    fn test_format_pr_resource_without_body() {
+        let mut details = sample_pr();
+        details["body"] = json!(null);
+        details["additions"] = json!(0);
+        details["deletions"] = json!(0);
+        details["changed_files"] = json!(0);
+
+        let text = format_pr_resource(&details);
+        assert!(text.contains("_No description provided._"));
+        assert!(text.contains("0 files changed, +0 -0"));
+    }
+}
@@ -0,0 +1,884 @@
+//! MCP streamable HTTP transport: JSON-RPC messages are POSTed to one endpoint and
+//! answered with JSON or an SSE stream, and a GET opens an SSE stream for
+//! server-initiated messages. Clients authenticate with a bearer token.
+
+use crate::mcp::peer::Peer;
+use crate::mcp::protocol::{JsonRpcResponse, SUPPORTED_PROTOCOL_VERSIONS};
+use crate::mcp::server::McpServer;
+use anyhow::Result;
+use bytes::Bytes;
+use futures_util::stream::{self, Stream, StreamExt};
+use ghview_core::ipc::transport::{generate_token, tokens_match};
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Empty, Full, LengthLimitError, Limited, StreamBody};
+use hyper::body::{Frame, Incoming};
+use hyper::header::{HeaderMap, HeaderValue, CACHE_CONTROL, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, StatusCode};
+use hyper_util::rt::{TokioIo, TokioTimer};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::task::JoinHandle;
+
+/// Path of the single MCP endpoint
+pub const ENDPOINT: &str = "/mcp";
+
+/// Environment variable with the bearer token clients must send; random when unset
+pub const TOKEN_ENV: &str = "GHVIEW_MCP_TOKEN";
+
+const SESSION_HEADER: &str = "mcp-session-id";
+const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+
+/// Larger request bodies are rejected
+const MAX_BODY_LEN: usize = 4 * 1024 * 1024;
+
+/// Timeouts for the HTTP transport, shortened in tests
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    /// How long a client may take to send a complete request
+    request_timeout: Duration,
+    /// Sessions with no request and no open stream for this long are ended
+    session_idle: Duration,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            request_timeout: Duration::from_secs(30),
+            session_idle: Duration::from_secs(30 * 60),
+        }
+    }
+}
+
+/// Whether a browser `Origin` belongs to this machine; other sites must not reach
+/// the server through a user's browser (DNS rebinding)
+fn is_local_origin(origin: &str) -> bool {
+    let Some((_, rest)) = origin.split_once("://") else {
+        return false;
+    };
+    let host = match rest.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => rest.split([':', '/']).next().unwrap_or_default(),
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+/// An initialized client, identified by the `Mcp-Session-Id` header
+struct Session {
+    server: McpServer,
+    peer: Peer,
+    /// Server-initiated messages, held here while no GET stream is open
+    stream: Mutex<Option<UnboundedReceiver<String>>>,
+    watcher: JoinHandle<()>,
+    last_active: Mutex<Instant>,
+}
+
+impl Session {
+    fn touch(&self) {
+        *self.last_active.lock().unwrap() = Instant::now();
+    }
+
+    /// Idle for `idle` with no GET stream open; clients that never send DELETE
+    /// leave sessions like this behind
+    fn is_idle(&self, idle: Duration) -> bool {
+        self.stream.lock().unwrap().is_some() && self.last_active.lock().unwrap().elapsed() >= idle
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.watcher.abort();
+    }
+}
+
+struct HttpState {
+    server: McpServer,
+    token: String,
+    limits: Limits,
+    sessions: Mutex<HashMap<String, Arc<Session>>>,
+}
+
+impl HttpState {
+    /// End idle sessions, stopping their subscription watchers even if a request
+    /// still holds on to the session
+    fn expire_idle_sessions(&self) {
+        self.sessions.lock().unwrap().retain(|_, session| {
+            let idle = session.is_idle(self.limits.session_idle);
+            if idle {
+                session.watcher.abort();
+            }
+            !idle
+        });
+    }
+}
+
+type Body = UnsyncBoxBody<Bytes, Infallible>;
+type Response = hyper::Response<Body>;
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn accepts(headers: &HeaderMap, media_type: &str) -> bool {
+    header(headers, "accept")
+        .is_some_and(|accept| accept.contains(media_type) || accept.contains("*/*"))
+}
+
+fn empty(status: StatusCode) -> Response {
+    hyper::Response::builder()
+        .status(status)
+        .body(Empty::new().boxed_unsync())
+        .unwrap()
+}
+
+fn json(status: StatusCode, body: String) -> Response {
+    hyper::Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Full::from(body).boxed_unsync())
+        .unwrap()
+}
+
+/// A JSON-RPC error outside of any request, e.g. for a rejected HTTP request
+fn error(status: StatusCode, message: &str) -> Response {
+    let body = serde_json::to_string(&JsonRpcResponse::error(None, -32600, message)).unwrap();
+    json(status, body)
+}
+
+/// Why a request was turned away, answered with a JSON-RPC error
+struct Rejection {
+    status: StatusCode,
+    message: String,
+}
+
+fn reject(status: StatusCode, message: impl Into<String>) -> Rejection {
+    Rejection {
+        status,
+        message: message.into(),
+    }
+}
+
+impl From<Rejection> for Response {
+    fn from(rejection: Rejection) -> Self {
+        let response = error(rejection.status, &rejection.message);
+        if rejection.status == StatusCode::UNAUTHORIZED {
+            with_header(response, "www-authenticate", "Bearer")
+        } else {
+            response
+        }
+    }
+}
+
+fn with_header(mut response: Response, name: &'static str, value: &str) -> Response {
+    response
+        .headers_mut()
+        .insert(name, HeaderValue::from_str(value).unwrap());
+    response
+}
+
+/// An SSE stream sending each message as a `message` event
+fn sse(messages: impl Stream<Item = String> + Send + 'static) -> Response {
+    let frames = messages.map(|message| {
+        Ok(Frame::data(Bytes::from(format!(
+            "event: message\ndata: {}\n\n",
+            message
+        ))))
+    });
+    hyper::Response::builder()
+        .header(CONTENT_TYPE, "text/event-stream")
+        .header(CACHE_CONTROL, "no-cache")
+        .body(StreamBody::new(frames).boxed_unsync())
+        .unwrap()
+}
+
+impl McpServer {
+    /// Serve the streamable HTTP transport on `listener`, accepting only requests that
+    /// carry `token` as a bearer token
+    pub async fn serve_http(&self, listener: TcpListener, token: String) -> Result<()> {
+        self.serve_http_with(listener, token, Limits::default())
+            .await
+    }
+
+    async fn serve_http_with(
+        &self,
+        listener: TcpListener,
+        token: String,
+        limits: Limits,
+    ) -> Result<()> {
+        let state = Arc::new(HttpState {
+            server: self.clone(),
+            token,
+            limits,
+            sessions: Mutex::new(HashMap::new()),
+        });
+        let mut sweep = tokio::time::interval(limits.session_idle / 4);
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let (stream, _) = accepted?;
+                    let state = state.clone();
+                    let service = service_fn(move |request| {
+                        let state = state.clone();
+                        async move { Ok::<_, Infallible>(handle_request(&state, request).await) }
+                    });
+                    // A client that never finishes its headers would otherwise hold the
+                    // connection open; bodies are timed in `read_body`
+                    let connection = http1::Builder::new()
+                        .timer(TokioTimer::new())
+                        .header_read_timeout(limits.request_timeout)
+                        .serve_connection(TokioIo::new(stream), service);
+                    tokio::spawn(async move {
+                        let _ = connection.await;
+                    });
+                }
+                _ = sweep.tick() => state.expire_idle_sessions(),
+            }
+        }
+    }
+}
+
+async fn handle_request(state: &HttpState, request: Request<Incoming>) -> Response {
+    if let Err(rejection) = check_request(state, &request) {
+        return rejection.into();
+    }
+    match *request.method() {
+        Method::POST => handle_post(state, request).await,
+        Method::GET => handle_get(state, request.headers()),
+        Method::DELETE => match find_session(state, request.headers()) {
+            Ok((id, _)) => {
+                state.sessions.lock().unwrap().remove(&id);
+                empty(StatusCode::NO_CONTENT)
+            }
+            Err(rejection) => rejection.into(),
+        },
+        _ => with_header(
+            error(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+            "allow",
+            "GET, POST, DELETE",
+        ),
+    }
+}
+
+/// Checks every request must pass: origin, endpoint, token and protocol version
+fn check_request(state: &HttpState, request: &Request<Incoming>) -> Result<(), Rejection> {
+    let headers = request.headers();
+    if header(headers, "origin").is_some_and(|origin| !is_local_origin(origin)) {
+        return Err(reject(StatusCode::FORBIDDEN, "Forbidden origin"));
+    }
+    if request.uri().path() != ENDPOINT {
+        return Err(reject(StatusCode::NOT_FOUND, "Not found"));
+    }
+    let token = header(headers, "authorization").and_then(|value| value.strip_prefix("Bearer "));
+    if !token.is_some_and(|token| tokens_match(&state.token, token.trim())) {
+        return Err(reject(StatusCode::UNAUTHORIZED, "Unauthorized"));
+    }
+    if let Some(version) = header(headers, PROTOCOL_VERSION_HEADER) {
+        if !SUPPORTED_PROTOCOL_VERSIONS.contains(&version) {
+            return Err(reject(
+                StatusCode::BAD_REQUEST,
+                format!("Unsupported protocol version: {}", version),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn find_session(
+    state: &HttpState,
+    headers: &HeaderMap,
+) -> Result<(String, Arc<Session>), Rejection> {
+    let Some(id) = header(headers, SESSION_HEADER) else {
+        return Err(reject(
+            StatusCode::BAD_REQUEST,
+            "Missing Mcp-Session-Id header",
+        ));
+    };
+    match state.sessions.lock().unwrap().get(id) {
+        Some(session) => {
+            session.touch();
+            Ok((id.to_string(), session.clone()))
+        }
+        None => Err(reject(StatusCode::NOT_FOUND, "Session not found")),
+    }
+}
+
+fn new_session(server: &McpServer) -> Session {
+    let server = server.for_session();
+    let (tx, rx) = mpsc::unbounded_channel();
+    let peer = Peer::new(tx);
+    let watcher = server.watch_subscriptions(&peer);
+    Session {
+        server,
+        peer,
+        stream: Mutex::new(Some(rx)),
+        watcher,
+        last_active: Mutex::new(Instant::now()),
+    }
+}
+
+/// Whether a message or batch contains requests, which are owed responses
+fn has_requests(message: &Value) -> bool {
+    let is_request =
+        |message: &Value| message.get("method").is_some() && message.get("id").is_some();
+    match message {
+        Value::Array(messages) => messages.iter().any(is_request),
+        message => is_request(message),
+    }
+}
+
+/// Read the whole body, which must arrive within the request timeout
+async fn read_body(limits: &Limits, body: Incoming) -> Result<Bytes, Rejection> {
+    let read = Limited::new(body, MAX_BODY_LEN).collect();
+    match tokio::time::timeout(limits.request_timeout, read).await {
+        Ok(Ok(collected)) => Ok(collected.to_bytes()),
+        Ok(Err(e)) if e.is::<LengthLimitError>() => Err(reject(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "Request body too large",
+        )),
+        Ok(Err(e)) => Err(reject(StatusCode::BAD_REQUEST, e.to_string())),
+        Err(_) => Err(reject(StatusCode::REQUEST_TIMEOUT, "Request timed out")),
+    }
+}
+
+async fn handle_post(state: &HttpState, request: Request<Incoming>) -> Response {
+    let (parts, body) = request.into_parts();
+    let body = match read_body(&state.limits, body).await {
+        Ok(body) => body,
+        Err(rejection) => return rejection.into(),
+    };
+    let message = match serde_json::from_slice::<Value>(&body) {
+        Ok(message) => message,
+        Err(e) => {
+            let body = serde_json::to_string(&JsonRpcResponse::parse_error(e)).unwrap();
+            return json(StatusCode::BAD_REQUEST, body);
+        }
+    };
+
+    let initialize = message.get("method").and_then(Value::as_str) == Some("initialize");
+    let (session_id, session) = if initialize {
+        (generate_token(), Arc::new(new_session(&state.server)))
+    } else {
+        match find_session(state, &parts.headers) {
+            Ok(found) => found,
+            Err(rejection) => return rejection.into(),
+        }
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let peer = session.peer.with_sender(tx);
+    if !has_requests(&message) {
+        session.server.handle_message(message, &peer);
+        return empty(StatusCode::ACCEPTED);
+    }
+    let pending = session.server.handle_message(message, &peer);
+    drop(peer);
+
+    // `initialize` is handled synchronously, so whether it succeeded is known here
+    if initialize {
+        if !session.peer.is_initialized() {
+            return rx
+                .try_recv()
+                .map(|body| json(StatusCode::OK, body))
+                .unwrap_or_else(|_| error(StatusCode::BAD_REQUEST, "Initialization failed"));
+        }
+        state
+            .sessions
+            .lock()
+            .unwrap()
+            .insert(session_id.clone(), session.clone());
+    }
+
+    // Requests keep running if the client goes away; disconnecting doesn't cancel them
+    let finished = tokio::spawn(pending.finish());
+
+    if !accepts(&parts.headers, "text/event-stream") {
+        let _ = finished.await;
+        // Without a stream only the response itself can be delivered
+        let mut body = None;
+        while let Ok(message) = rx.try_recv() {
+            let is_notification = serde_json::from_str::<Value>(&message)
+                .is_ok_and(|message| message.get("method").is_some());
+            if !is_notification {
+                body = Some(message);
+            }
+        }
+        let response = match body {
+            Some(body) => json(StatusCode::OK, body),
+            None => empty(StatusCode::ACCEPTED),
+        };
+        return with_header(response, SESSION_HEADER, &session_id);
+    }
+
+    // Stream messages as they are sent, then whatever is left once the requests finish
+    let messages = stream::unfold((rx, Some(finished)), |(mut rx, mut finished)| async move {
+        let message = match finished.as_mut() {
+            Some(pending) => tokio::select! {
+                biased;
+                Some(message) = rx.recv() => Some(message),
+                _ = pending => {
+                    finished = None;
+                    rx.try_recv().ok()
+                }
+            },
+            None => rx.try_recv().ok(),
+        };
+        message.map(|message| (message, (rx, finished)))
+    });
+    with_header(sse(messages), SESSION_HEADER, &session_id)
+}
+
+/// A session's server-initiated messages while a GET stream is open
+struct OpenStream {
+    rx: Option<UnboundedReceiver<String>>,
+    // Holding the session would keep it alive after a DELETE
+    session: Weak<Session>,
+}
+
+impl Drop for OpenStream {
+    /// Hand the messages back when the client disconnects, for the next GET
+    fn drop(&mut self) {
+        if let Some(session) = self.session.upgrade() {
+            *session.stream.lock().unwrap() = self.rx.take();
+            session.touch();
+        }
+    }
+}
+
+/// Stream server-initiated messages until the client disconnects or the session ends
+fn handle_get(state: &HttpState, headers: &HeaderMap) -> Response {
+    if !accepts(headers, "text/event-stream") {
+        return error(
+            StatusCode::NOT_ACCEPTABLE,
+            "Accept must include text/event-stream",
+        );
+    }
+    let session = match find_session(state, headers) {
+        Ok((_, session)) => session,
+        Err(rejection) => return rejection.into(),
+    };
+    let Some(rx) = session.stream.lock().unwrap().take() else {
+        return error(
+            StatusCode::CONFLICT,
+            "A stream is already open for this session",
+        );
+    };
+    let open = OpenStream {
+        rx: Some(rx),
+        session: Arc::downgrade(&session),
+    };
+    let messages = stream::unfold(open, |mut open| async move {
+        let message = open.rx.as_mut()?.recv().await?;
+        Some((message, open))
+    });
+    sse(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::server::offline_env;
+    use ghview_core::test_support::FakeGitHubEnv;
+    use ghview_fake_github::{FakePull, Scenario};
+    use serde_json::json;
+    use serial_test::serial;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    const TOKEN: &str = "secret";
+    const ACCEPT_BOTH: &str = "application/json, text/event-stream";
+
+    async fn start(server: McpServer) -> String {
+        start_with(server, Limits::default()).await
+    }
+
+    async fn start_with(server: McpServer, limits: Limits) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}{}", listener.local_addr().unwrap(), ENDPOINT);
+        tokio::spawn(async move {
+            server
+                .serve_http_with(listener, TOKEN.to_string(), limits)
+                .await
+        });
+        url
+    }
+
+    fn post(
+        url: &str,
+        session: Option<&str>,
+        accept: &str,
+        body: Value,
+    ) -> reqwest::RequestBuilder {
+        let mut request = reqwest::Client::new()
+            .post(url)
+            .bearer_auth(TOKEN)
+            .header("Accept", accept)
+            .json(&body);
+        if let Some(session) = session {
+            request = request.header("Mcp-Session-Id", session);
+        }
+        request
+    }
+
+    /// The JSON-RPC messages in an SSE response body
+    async fn events(response: reqwest::Response) -> Vec<Value> {
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        response
+            .text()
+            .await
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .map(|data| serde_json::from_str(data).unwrap())
+            .collect()
+    }
+
+    /// Initialize a session, returning its id
+    async fn initialize(url: &str) -> String {
+        let response = post(
+            url,
+            None,
+            ACCEPT_BOTH,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {"protocolVersion": "2025-06-18", "capabilities": {}}
+            }),
+        )
+        .send()
+        .await
+        .unwrap();
+        assert_eq!(response.status(), 200);
+        let session = response.headers()["mcp-session-id"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let messages = events(response).await;
+        assert_eq!(messages[0]["result"]["protocolVersion"], "2025-06-18");
+
+        let response = post(
+            url,
+            Some(&session),
+            ACCEPT_BOTH,
+            json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+        )
+        .send()
+        .await
+        .unwrap();
+        assert_eq!(response.status(), 202);
+        session
+    }
+
+    #[test]
+    fn test_is_local_origin() {
+        assert!(is_local_origin("http://localhost:3000"));
+        assert!(is_local_origin("http://127.0.0.1"));
+        assert!(is_local_origin("http://[::1]:8765"));
+        assert!(!is_local_origin("https://localhost.evil.example"));
+        assert!(!is_local_origin("null"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unauthorized_requests() {
+        let url = start(McpServer::unconnected()).await;
+        let client = reqwest::Client::new();
+        let ping = json!({"jsonrpc": "2.0", "id": 1, "method": "ping"});
+
+        let response = client.post(&url).json(&ping).send().await.unwrap();
+        assert_eq!(response.status(), 401);
+        let response = client
+            .post(&url)
+            .bearer_auth("guess")
+            .json(&ping)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 401);
+
+        let response = post(&url, None, ACCEPT_BOTH, ping.clone())
+            .header("Origin", "https://evil.example")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 403);
+
+        let response = post(&url, None, ACCEPT_BOTH, ping.clone())
+            .header("MCP-Protocol-Version", "1999-01-01")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+
+        let response = client.put(&url).bearer_auth(TOKEN).send().await.unwrap();
+        assert_eq!(response.status(), 405);
+
+        let response = client
+            .post(url.replace(ENDPOINT, "/other"))
+            .bearer_auth(TOKEN)
+            .json(&ping)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 404);
+    }
+
+    /// Send raw bytes to the server, returning everything it answers
+    async fn send_raw(url: &str, request: &[u8]) -> String {
+        let addr = url.trim_start_matches("http://").trim_end_matches(ENDPOINT);
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_accepts_chunked_bodies() {
+        let url = start(McpServer::unconnected()).await;
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{}}}"#;
+        let request = format!(
+            "POST /mcp HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {}\r\n\
+             Accept: application/json\r\nConnection: close\r\nTransfer-Encoding: chunked\r\n\r\n\
+             {:x}\r\n{}\r\n0\r\n\r\n",
+            TOKEN,
+            body.len(),
+            body
+        );
+        let response = send_raw(&url, request.as_bytes()).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.contains("\"protocolVersion\":\"2025-06-18\""));
+    }
+
+    #[tokio::test]
+    async fn test_times_out_incomplete_requests() {
+        let limits = Limits {
+            request_timeout: Duration::from_millis(100),
+            ..Limits::default()
+        };
+        let url = start_with(McpServer::unconnected(), limits).await;
+
+        // Incomplete headers get the connection closed
+        let response = send_raw(&url, b"POST /mcp HTTP/1.1\r\nContent-Length: 10\r\n").await;
+        assert_eq!(response, "");
+
+        let request = format!(
+            "POST /mcp HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {}\r\n\
+             Connection: close\r\nContent-Length: 10\r\n\r\n{{",
+            TOKEN
+        );
+        let response = send_raw(&url, request.as_bytes()).await;
+        assert!(
+            response.starts_with("HTTP/1.1 408 Request Timeout\r\n"),
+            "{}",
+            response
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_lifecycle() {
+        let url = start(McpServer::unconnected()).await;
+        let session = initialize(&url).await;
+        let list_tools = json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"});
+
+        let response = post(&url, None, ACCEPT_BOTH, list_tools.clone())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+        let response = post(&url, Some("unknown"), ACCEPT_BOTH, list_tools.clone())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 404);
+
+        let response = post(&url, Some(&session), "application/json", list_tools.clone())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["id"], 2);
+        assert!(body["result"]["tools"].is_array());
+
+        let get = |session: &str| {
+            reqwest::Client::new()
+                .get(&url)
+                .bearer_auth(TOKEN)
+                .header("Accept", "text/event-stream")
+                .header("Mcp-Session-Id", session)
+                .send()
+        };
+        let stream = get(&session).await.unwrap();
+        assert_eq!(stream.status(), 200);
+        assert_eq!(stream.headers()["content-type"], "text/event-stream");
+        assert_eq!(get(&session).await.unwrap().status(), 409);
+        // Disconnecting hands the stream back for the next GET
+        drop(stream);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let stream = get(&session).await.unwrap();
+        assert_eq!(stream.status(), 200);
+
+        let response = reqwest::Client::new()
+            .delete(&url)
+            .bearer_auth(TOKEN)
+            .header("Mcp-Session-Id", &session)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 204);
+        // Ending the session closes its stream
+        assert_eq!(stream.text().await.unwrap(), "");
+        let response = post(&url, Some(&session), ACCEPT_BOTH, list_tools)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_idle_sessions_expire() {
+        let state = HttpState {
+            server: McpServer::unconnected(),
+            token: TOKEN.to_string(),
+            limits: Limits {
+                session_idle: Duration::from_millis(50),
+                ..Limits::default()
+            },
+            sessions: Mutex::new(HashMap::new()),
+        };
+        let idle = Arc::new(new_session(&state.server));
+        let streaming = Arc::new(new_session(&state.server));
+        let stream = streaming.stream.lock().unwrap().take();
+        state.sessions.lock().unwrap().extend([
+            ("idle".to_string(), idle.clone()),
+            ("streaming".to_string(), streaming.clone()),
+        ]);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        state.expire_idle_sessions();
+        let remaining: Vec<String> = state.sessions.lock().unwrap().keys().cloned().collect();
+        assert_eq!(remaining, vec!["streaming"]);
+        tokio::task::yield_now().await;
+        assert!(idle.watcher.is_finished());
+        assert!(!streaming.watcher.is_finished());
+        drop(stream);
+    }
+
+    #[tokio::test]
+    async fn test_expired_session_is_not_found() {
+        let limits = Limits {
+            session_idle: Duration::from_millis(500),
+            ..Limits::default()
+        };
+        let url = start_with(McpServer::unconnected(), limits).await;
+        let session = initialize(&url).await;
+
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        let response = post(
+            &url,
+            Some(&session),
+            ACCEPT_BOTH,
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+        )
+        .send()
+        .await
+        .unwrap();
+        assert_eq!(response.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_failed_initialize_creates_no_session() {
+        let url = start(McpServer::unconnected()).await;
+        let response = post(
+            &url,
+            None,
+            ACCEPT_BOTH,
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        )
+        .send()
+        .await
+        .unwrap();
+        assert!(response.headers().get("mcp-session-id").is_none());
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["code"], -32602);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_streams_progress_before_response() {
+        let scenario = Scenario::new()
+            .token("gho_test", "octocat", &["repo"])
+            .pull(FakePull::new("octo-org/api", 1, "Add endpoint").review_requested());
+        let fake = FakeGitHubEnv::start(scenario)
+            .await
+            .with_env_token("gho_test");
+        fake.env.set("GHVIEW_IPC_TRANSPORT", "tcp");
+
+        let url = start(McpServer::new()).await;
+        let session = initialize(&url).await;
+        let response = post(
+            &url,
+            Some(&session),
+            ACCEPT_BOTH,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": {
+                    "name": "list_review_requests",
+                    "_meta": {"progressToken": "p"}
+                }
+            }),
+        )
+        .send()
+        .await
+        .unwrap();
+        let messages = events(response).await;
+        let methods: Vec<&str> = messages
+            .iter()
+            .map(|message| message["method"].as_str().unwrap_or("response"))
+            .collect();
+        assert_eq!(
+            methods,
+            vec![
+                "notifications/progress",
+                "notifications/progress",
+                "response"
+            ]
+        );
+        assert_eq!(
+            messages[2]["result"]["structuredContent"]["pull_requests"][0]["title"],
+            "Add endpoint"
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_requests_without_app_fail_cleanly() {
+        let _env = offline_env();
+        let url = start(McpServer::new()).await;
+        let session = initialize(&url).await;
+        let response = post(
+            &url,
+            Some(&session),
+            "application/json",
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": {"name": "get_pull_request", "arguments": {"owner": "o", "repo": "r", "number": 1}}
+            }),
+        )
+        .send()
+        .await
+        .unwrap();
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["result"]["isError"], true);
+    }
+}
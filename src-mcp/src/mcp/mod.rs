@@ -1,4 +1,15 @@
+mod actions;
+mod automation;
+#[cfg(test)]
+mod conformance;
+mod coordinates;
+mod format;
+pub mod http;
+mod peer;
+mod prompts;
 mod protocol;
+mod resources;
+mod screenshot;
 mod server;
 
 pub use server::McpServer;
@@ -0,0 +1,152 @@
+use crate::mcp::protocol::{JsonRpcNotification, LogLevel};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// What the client negotiated during initialization
+struct PeerState {
+    protocol_version: Option<&'static str>,
+    log_level: LogLevel,
+}
+
+/// One connected client: its negotiated state and the channel its messages are written to
+#[derive(Clone)]
+pub struct Peer {
+    tx: UnboundedSender<String>,
+    state: Arc<Mutex<PeerState>>,
+}
+
+impl Peer {
+    pub fn new(tx: UnboundedSender<String>) -> Self {
+        Self {
+            tx,
+            state: Arc::new(Mutex::new(PeerState {
+                protocol_version: None,
+                log_level: LogLevel::Info,
+            })),
+        }
+    }
+
+    /// The same client, with its messages written to `tx` instead, e.g. the response
+    /// stream of one HTTP request
+    pub fn with_sender(&self, tx: UnboundedSender<String>) -> Self {
+        Self {
+            tx,
+            state: self.state.clone(),
+        }
+    }
+
+    /// Queue a message for the client; false once the connection is gone
+    pub fn send(&self, message: &impl Serialize) -> bool {
+        match serde_json::to_string(message) {
+            Ok(json) => self.tx.send(json).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    pub fn notify(&self, method: &str, params: Value) -> bool {
+        self.send(&JsonRpcNotification::new(method, params))
+    }
+
+    /// Whether `initialize` has been answered, after which regular requests are allowed
+    pub fn is_initialized(&self) -> bool {
+        self.state.lock().unwrap().protocol_version.is_some()
+    }
+
+    pub fn set_protocol_version(&self, version: &'static str) {
+        self.state.lock().unwrap().protocol_version = Some(version);
+    }
+
+    pub fn set_log_level(&self, level: LogLevel) {
+        self.state.lock().unwrap().log_level = level;
+    }
+
+    /// Send a `notifications/message` if `level` is at or above the client's chosen level
+    pub fn log(&self, level: LogLevel, logger: &str, data: Value) {
+        if level < self.state.lock().unwrap().log_level {
+            return;
+        }
+        self.notify(
+            "notifications/message",
+            json!({ "level": level, "logger": logger, "data": data }),
+        );
+    }
+}
+
+/// Reports progress of one request when the client asked for it with a progress token
+pub struct Progress {
+    peer: Peer,
+    token: Option<Value>,
+    total: u64,
+}
+
+impl Progress {
+    /// Progress over `total` steps, using the token from the request's `_meta`, if any
+    pub fn new(peer: &Peer, params: Option<&Value>, total: u64) -> Self {
+        let token = params
+            .and_then(|params| params.pointer("/_meta/progressToken"))
+            .filter(|token| token.is_string() || token.is_i64() || token.is_u64())
+            .cloned();
+        Self {
+            peer: peer.clone(),
+            token,
+            total,
+        }
+    }
+
+    /// Report that `step` of the total steps are done
+    pub fn report(&self, step: u64, message: &str) {
+        let Some(token) = &self.token else {
+            return;
+        };
+        self.peer.notify(
+            "notifications/progress",
+            json!({
+                "progressToken": token,
+                "progress": step,
+                "total": self.total,
+                "message": message,
+            }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn test_log_respects_level() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let peer = Peer::new(tx);
+        peer.log(LogLevel::Debug, "tools", json!("hidden"));
+        peer.set_log_level(LogLevel::Debug);
+        peer.log(LogLevel::Debug, "tools", json!("shown"));
+
+        let message: Value = serde_json::from_str(&rx.try_recv().unwrap()).unwrap();
+        assert_eq!(message["method"], "notifications/message");
+        assert_eq!(
+            message["params"],
+            json!({"level": "debug", "logger": "tools", "data": "shown"})
+        );
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_progress_only_with_token() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let peer = Peer::new(tx);
+        Progress::new(&peer, Some(&json!({})), 2).report(1, "ignored");
+        assert!(rx.try_recv().is_err());
+
+        let params = json!({"_meta": {"progressToken": 7}});
+        Progress::new(&peer, Some(&params), 2).report(1, "Fetching");
+        let message: Value = serde_json::from_str(&rx.try_recv().unwrap()).unwrap();
+        assert_eq!(
+            message["params"],
+            json!({"progressToken": 7, "progress": 1, "total": 2, "message": "Fetching"})
+        );
+    }
+}
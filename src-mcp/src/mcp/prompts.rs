@@ -0,0 +1,289 @@
+use crate::mcp::coordinates::PrCoordinates;
+use crate::mcp::format::{format_check_status, format_pr_list, format_pr_resource};
+use crate::mcp::protocol::*;
+use crate::mcp::server::McpServer;
+use serde_json::Value;
+
+fn pr_arguments() -> Vec<PromptArgument> {
+    vec![
+        PromptArgument {
+            name: "owner".to_string(),
+            description: "Repository owner (user or organization)".to_string(),
+            required: true,
+        },
+        PromptArgument {
+            name: "repo".to_string(),
+            description: "Repository name".to_string(),
+            required: true,
+        },
+        PromptArgument {
+            name: "number".to_string(),
+            description: "Pull request number".to_string(),
+            required: true,
+        },
+    ]
+}
+
+pub fn prompts() -> Vec<Prompt> {
+    vec![
+        Prompt {
+            name: "review_pr".to_string(),
+            description: "Review a pull request using its description, diff summary and checks"
+                .to_string(),
+            arguments: pr_arguments(),
+        },
+        Prompt {
+            name: "summarize_review_queue".to_string(),
+            description: "Summarize the pull requests waiting for your review and suggest an order"
+                .to_string(),
+            arguments: vec![],
+        },
+        Prompt {
+            name: "explain_ci_failure".to_string(),
+            description: "Explain why CI is failing on a pull request".to_string(),
+            arguments: pr_arguments(),
+        },
+    ]
+}
+
+fn review_pr_message(details: &Value) -> String {
+    format!(
+        "Please review the following pull request. Point out bugs, risky changes and \
+         missing tests, and finish with a recommendation to approve, comment or request \
+         changes.\n\n{}",
+        format_pr_resource(details)
+    )
+}
+
+fn summarize_queue_message(prs: &Value) -> String {
+    format!(
+        "Here is my current GitHub review queue. Summarize it in a few bullet points, \
+         group related pull requests, and suggest which ones I should review first \
+         (consider failing checks, drafts and how long they have been waiting).\n\n{}",
+        format_pr_list(prs)
+    )
+}
+
+fn explain_ci_failure_message(pr: &PrCoordinates, details: &Value) -> String {
+    let checks = details
+        .get("check_status")
+        .filter(|status| !status.is_null())
+        .map(|status| format_check_status(&pr.owner, &pr.repo, pr.number, status))
+        .unwrap_or_else(|| "Check status unavailable.".to_string());
+    format!(
+        "CI is not passing on {}/{}#{}. Using the check results and the pull request \
+         below, explain the most likely cause of each failing check and what the author \
+         should change. Follow the check links if you can access them.\n\n{}\n\n{}",
+        pr.owner,
+        pr.repo,
+        pr.number,
+        checks,
+        format_pr_resource(details)
+    )
+}
+
+impl McpServer {
+    pub(super) fn handle_list_prompts(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
+        let result = ListPromptsResult { prompts: prompts() };
+        JsonRpcResponse::success(request.id.clone(), serde_json::to_value(result).unwrap())
+    }
+
+    pub(super) async fn handle_get_prompt(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
+        let params: GetPromptParams = match request.params.as_ref() {
+            Some(p) => match serde_json::from_value(p.clone()) {
+                Ok(params) => params,
+                Err(e) => {
+                    return JsonRpcResponse::error(
+                        request.id.clone(),
+                        -32602,
+                        format!("Invalid params: {}", e),
+                    );
+                }
+            },
+            None => {
+                return JsonRpcResponse::error(request.id.clone(), -32602, "Missing params");
+            }
+        };
+
+        let result = match params.name.as_str() {
+            "review_pr" => self.get_review_pr(&params).await,
+            "summarize_review_queue" => self.get_summarize_review_queue().await,
+            "explain_ci_failure" => self.get_explain_ci_failure(&params).await,
+            _ => Err((-32602, format!("Unknown prompt: {}", params.name))),
+        };
+
+        match result {
+            Ok(result) => {
+                JsonRpcResponse::success(request.id.clone(), serde_json::to_value(result).unwrap())
+            }
+            Err((code, message)) => JsonRpcResponse::error(request.id.clone(), code, message),
+        }
+    }
+
+    async fn get_review_pr(
+        &self,
+        params: &GetPromptParams,
+    ) -> Result<GetPromptResult, (i32, String)> {
+        let pr =
+            PrCoordinates::from_prompt_arguments(&params.arguments).map_err(|e| (-32602, e))?;
+        let details = self
+            .backend
+            .get_pull_request_details(&pr.owner, &pr.repo, pr.number)
+            .await
+            .map_err(|e| (-32603, format!("Failed to get pull request: {}", e)))?;
+
+        Ok(GetPromptResult {
+            description: format!("Review {}/{}#{}", pr.owner, pr.repo, pr.number),
+            messages: vec![PromptMessage::user(review_pr_message(&details))],
+        })
+    }
+
+    async fn get_summarize_review_queue(&self) -> Result<GetPromptResult, (i32, String)> {
+        let prs = self
+            .backend
+            .list_review_requests()
+            .await
+            .map_err(|e| (-32603, format!("Failed to list review requests: {}", e)))?;
+
+        Ok(GetPromptResult {
+            description: "Summarize my review queue".to_string(),
+            messages: vec![PromptMessage::user(summarize_queue_message(&prs))],
+        })
+    }
+
+    async fn get_explain_ci_failure(
+        &self,
+        params: &GetPromptParams,
+    ) -> Result<GetPromptResult, (i32, String)> {
+        let pr =
+            PrCoordinates::from_prompt_arguments(&params.arguments).map_err(|e| (-32602, e))?;
+        let details = self
+            .backend
+            .get_pull_request_details(&pr.owner, &pr.repo, pr.number)
+            .await
+            .map_err(|e| (-32603, format!("Failed to get pull request: {}", e)))?;
+
+        Ok(GetPromptResult {
+            description: format!(
+                "Explain CI failures on {}/{}#{}",
+                pr.owner, pr.repo, pr.number
+            ),
+            messages: vec![PromptMessage::user(explain_ci_failure_message(
+                &pr, &details,
+            ))],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::server::offline_env;
+    use serde_json::json;
+    use serial_test::serial;
+
+    fn get_prompt(params: Value) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "prompts/get".to_string(),
+            params: Some(params),
+        }
+    }
+
+    fn sample_details() -> Value {
+        json!({
+            "number": 42,
+            "title": "Add feature",
+            "html_url": "https://github.com/mirakui/ghview/pull/42",
+            "state": "open",
+            "draft": false,
+            "updated_at": "2024-01-02T00:00:00Z",
+            "user": {"login": "alice"},
+            "repository": {"full_name": "mirakui/ghview"},
+            "check_status": {
+                "state": "failure",
+                "total_count": 1,
+                "statuses": [{"state": "failure", "context": "ci/test", "description": "2 failed", "target_url": null}]
+            },
+            "body": "Adds the feature.",
+            "additions": 5,
+            "deletions": 1,
+            "changed_files": 1,
+            "files": []
+        })
+    }
+
+    #[tokio::test]
+    async fn test_list_prompts() {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "prompts/list".to_string(),
+            params: None,
+        };
+        let response = McpServer::unconnected().handle_request(&request).await;
+        let prompts = response.result.unwrap()["prompts"].clone();
+        let names: Vec<&str> = prompts
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["review_pr", "summarize_review_queue", "explain_ci_failure"]
+        );
+        assert_eq!(prompts[0]["arguments"][2]["name"], "number");
+        assert_eq!(prompts[0]["arguments"][2]["required"], true);
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt_validates_arguments() {
+        let response = McpServer::unconnected()
+            .handle_request(&get_prompt(json!({
+                "name": "review_pr",
+                "arguments": {"owner": "mirakui", "repo": "ghview"}
+            })))
+            .await;
+        let error = response.error.unwrap();
+        assert_eq!(error.code, -32602);
+        assert_eq!(error.message, "Missing required argument: number");
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt_unknown_name() {
+        let response = McpServer::unconnected()
+            .handle_request(&get_prompt(json!({"name": "write_my_code"})))
+            .await;
+        let error = response.error.unwrap();
+        assert_eq!(error.code, -32602);
+        assert_eq!(error.message, "Unknown prompt: write_my_code");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_prompt_reports_fetch_failure() {
+        let _env = offline_env();
+        let response = McpServer::unconnected()
+            .handle_request(&get_prompt(json!({"name": "summarize_review_queue"})))
+            .await;
+        assert_eq!(response.error.unwrap().code, -32603);
+    }
+
+    #[test]
+    fn test_prompt_messages_embed_pr_data() {
+        let pr = PrCoordinates {
+            owner: "mirakui".to_string(),
+            repo: "ghview".to_string(),
+            number: 42,
+        };
+        let review = review_pr_message(&sample_details());
+        assert!(review.contains("# mirakui/ghview#42: Add feature"));
+        assert!(review.contains("Adds the feature."));
+
+        let ci = explain_ci_failure_message(&pr, &sample_details());
+        assert!(ci.contains("Checks for mirakui/ghview#42: failure (1 total)"));
+        assert!(ci.contains("- ci/test: failure - 2 failed"));
+    }
+}
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 
-pub const PROTOCOL_VERSION: &str = "2024-11-05";
+/// Protocol revisions this server speaks, newest first
+pub const SUPPORTED_PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
 pub const SERVER_NAME: &str = "ghview-mcp";
 pub const SERVER_VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -17,7 +19,7 @@ pub struct JsonRpcRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonRpcResponse {
     pub jsonrpc: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Null only when the request's id couldn't be determined
     pub id: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub result: Option<Value>,
@@ -25,6 +27,25 @@ pub struct JsonRpcResponse {
     pub error: Option<JsonRpcError>,
 }
 
+/// A server-initiated message that expects no response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcNotification {
+    pub jsonrpc: String,
+    pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl JsonRpcNotification {
+    pub fn new(method: impl Into<String>, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            method: method.into(),
+            params: Some(params),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonRpcError {
     pub code: i32,
@@ -43,6 +64,11 @@ impl JsonRpcResponse {
         }
     }
 
+    /// The reply to a message that isn't valid JSON, whose id is unknown
+    pub fn parse_error(error: serde_json::Error) -> Self {
+        Self::error(None, -32700, format!("Parse error: {}", error))
+    }
+
     pub fn error(id: Option<Value>, code: i32, message: impl Into<String>) -> Self {
         Self {
             jsonrpc: "2.0".to_string(),
@@ -57,6 +83,53 @@ impl JsonRpcResponse {
     }
 }
 
+/// The revision to use with a client: the one it asked for when supported, otherwise
+/// the newest, which the client may reject by disconnecting
+pub fn negotiate_protocol_version(requested: &str) -> &'static str {
+    SUPPORTED_PROTOCOL_VERSIONS
+        .into_iter()
+        .find(|version| *version == requested)
+        .unwrap_or(SUPPORTED_PROTOCOL_VERSIONS[0])
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitializeParams {
+    #[serde(rename = "protocolVersion")]
+    pub protocol_version: String,
+    #[serde(default)]
+    pub capabilities: Value,
+    #[serde(rename = "clientInfo", default)]
+    pub client_info: Option<Value>,
+}
+
+/// Params of `notifications/cancelled`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelledParams {
+    #[serde(rename = "requestId")]
+    pub request_id: Value,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Syslog severities used by `logging/setLevel` and `notifications/message`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetLevelParams {
+    pub level: LogLevel,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerInfo {
     pub name: String,
@@ -67,6 +140,23 @@ pub struct ServerInfo {
 pub struct ServerCapabilities {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<ToolsCapability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourcesCapability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<PromptsCapability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logging: Option<LoggingCapability>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingCapability {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourcesCapability {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscribe: Option<bool>,
+    #[serde(rename = "listChanged", skip_serializing_if = "Option::is_none")]
+    pub list_changed: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,6 +165,12 @@ pub struct ToolsCapability {
     pub list_changed: Option<bool>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptsCapability {
+    #[serde(rename = "listChanged", skip_serializing_if = "Option::is_none")]
+    pub list_changed: Option<bool>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InitializeResult {
     #[serde(rename = "protocolVersion")]
@@ -86,6 +182,106 @@ pub struct InitializeResult {
     pub instructions: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Resource {
+    pub uri: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListResourcesResult {
+    pub resources: Vec<Resource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceTemplate {
+    #[serde(rename = "uriTemplate")]
+    pub uri_template: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListResourceTemplatesResult {
+    #[serde(rename = "resourceTemplates")]
+    pub resource_templates: Vec<ResourceTemplate>,
+}
+
+/// Params shared by `resources/read`, `resources/subscribe` and `resources/unsubscribe`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceUriParams {
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextResourceContents {
+    pub uri: String,
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadResourceResult {
+    pub contents: Vec<TextResourceContents>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prompt {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub arguments: Vec<PromptArgument>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptArgument {
+    pub name: String,
+    pub description: String,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListPromptsResult {
+    pub prompts: Vec<Prompt>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetPromptParams {
+    pub name: String,
+    /// Prompt arguments are always strings in MCP
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptMessage {
+    pub role: String,
+    pub content: Content,
+}
+
+impl PromptMessage {
+    pub fn user(text: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: Content::text(text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetPromptResult {
+    pub description: String,
+    pub messages: Vec<PromptMessage>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tool {
     pub name: String,
@@ -106,16 +302,39 @@ pub struct CallToolParams {
     pub arguments: Option<Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TextContent {
-    #[serde(rename = "type")]
-    pub content_type: String,
-    pub text: String,
+/// A content block in a tool result or prompt message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Content {
+    Text {
+        text: String,
+    },
+    /// Base64-encoded image data
+    Image {
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+}
+
+impl Content {
+    pub fn text(text: impl Into<String>) -> Self {
+        Content::Text { text: text.into() }
+    }
+
+    pub fn image(data: impl Into<String>, mime_type: impl Into<String>) -> Self {
+        Content::Image {
+            data: data.into(),
+            mime_type: mime_type.into(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CallToolResult {
-    pub content: Vec<TextContent>,
+    pub content: Vec<Content>,
+    #[serde(rename = "structuredContent", skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
     #[serde(rename = "isError", skip_serializing_if = "Option::is_none")]
     pub is_error: Option<bool>,
 }
@@ -123,21 +342,101 @@ pub struct CallToolResult {
 impl CallToolResult {
     pub fn success(text: impl Into<String>) -> Self {
         Self {
-            content: vec![TextContent {
-                content_type: "text".to_string(),
-                text: text.into(),
-            }],
+            content: vec![Content::text(text)],
+            structured_content: None,
+            is_error: None,
+        }
+    }
+
+    /// A successful result carrying a human-readable summary plus machine-readable data
+    pub fn structured(text: impl Into<String>, data: Value) -> Self {
+        Self {
+            content: vec![Content::text(text)],
+            structured_content: Some(data),
+            is_error: None,
+        }
+    }
+
+    /// A successful result made of several content blocks, e.g. a caption and an image
+    pub fn contents(content: Vec<Content>) -> Self {
+        Self {
+            content,
+            structured_content: None,
             is_error: None,
         }
     }
 
     pub fn error(text: impl Into<String>) -> Self {
         Self {
-            content: vec![TextContent {
-                content_type: "text".to_string(),
-                text: text.into(),
-            }],
+            content: vec![Content::text(text)],
+            structured_content: None,
             is_error: Some(true),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_text_content_serialization() {
+        let value = serde_json::to_value(Content::text("hello")).unwrap();
+        assert_eq!(value, json!({"type": "text", "text": "hello"}));
+    }
+
+    #[test]
+    fn test_image_content_serialization() {
+        let value = serde_json::to_value(Content::image("iVBORw0KGgo=", "image/png")).unwrap();
+        assert_eq!(
+            value,
+            json!({"type": "image", "data": "iVBORw0KGgo=", "mimeType": "image/png"})
+        );
+
+        let parsed: Content = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, Content::image("iVBORw0KGgo=", "image/png"));
+    }
+
+    #[test]
+    fn test_call_tool_result_with_mixed_content() {
+        let result = CallToolResult::contents(vec![
+            Content::text("/tmp/shot.png"),
+            Content::image("AAAA", "image/png"),
+        ]);
+        let value = serde_json::to_value(result).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "content": [
+                    {"type": "text", "text": "/tmp/shot.png"},
+                    {"type": "image", "data": "AAAA", "mimeType": "image/png"}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_negotiate_protocol_version() {
+        assert_eq!(negotiate_protocol_version("2024-11-05"), "2024-11-05");
+        assert_eq!(negotiate_protocol_version("2025-03-26"), "2025-03-26");
+        assert_eq!(negotiate_protocol_version("1999-01-01"), "2025-06-18");
+    }
+
+    #[test]
+    fn test_log_levels_are_ordered_by_severity() {
+        let level: LogLevel = serde_json::from_value(json!("warning")).unwrap();
+        assert!(LogLevel::Error >= level);
+        assert!(LogLevel::Info < level);
+        assert!(serde_json::from_value::<LogLevel>(json!("verbose")).is_err());
+    }
+
+    #[test]
+    fn test_error_result_serialization() {
+        let value = serde_json::to_value(CallToolResult::error("boom")).unwrap();
+        assert_eq!(
+            value,
+            json!({"content": [{"type": "text", "text": "boom"}], "isError": true})
+        );
+    }
+}
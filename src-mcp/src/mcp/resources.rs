@@ -0,0 +1,340 @@
+use crate::backend::Backend;
+use crate::mcp::coordinates::{PrCoordinates, PR_URI_TEMPLATE};
+use crate::mcp::format::{format_pr_resource, PrSummary};
+use crate::mcp::peer::Peer;
+use crate::mcp::protocol::*;
+use crate::mcp::server::McpServer;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const PR_MIME_TYPE: &str = "text/markdown";
+
+/// How often subscribed pull requests are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Subscribed resource URIs mapped to a fingerprint of the last observed state
+#[derive(Clone, Default)]
+pub struct Subscriptions(Arc<Mutex<HashMap<String, Option<String>>>>);
+
+impl Subscriptions {
+    pub fn insert(&self, uri: String) {
+        self.0.lock().unwrap().entry(uri).or_insert(None);
+    }
+
+    pub fn remove(&self, uri: &str) -> bool {
+        self.0.lock().unwrap().remove(uri).is_some()
+    }
+
+    pub fn uris(&self) -> Vec<String> {
+        self.0.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// Store the latest fingerprint, returning whether it differs from a previously seen one
+    pub fn record(&self, uri: &str, fingerprint: String) -> bool {
+        let mut subscriptions = self.0.lock().unwrap();
+        match subscriptions.get_mut(uri) {
+            Some(last) => {
+                let changed = last.as_ref().is_some_and(|prev| *prev != fingerprint);
+                *last = Some(fingerprint);
+                changed
+            }
+            None => false,
+        }
+    }
+}
+
+/// The parts of a pull request that, when changed, change its resource content
+fn fingerprint(pr: &Value) -> Option<String> {
+    let updated_at = pr.get("updated_at")?.as_str()?;
+    let check_state = pr
+        .pointer("/check_status/state")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    Some(format!("{}|{}", updated_at, check_state))
+}
+
+/// Poll subscribed pull requests and emit `notifications/resources/updated` on change
+pub async fn watch_subscriptions(backend: Backend, subscriptions: Subscriptions, peer: Peer) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        for uri in subscriptions.uris() {
+            let Ok(pr) = PrCoordinates::from_uri(&uri) else {
+                continue;
+            };
+            let Ok(data) = backend
+                .get_pull_request(&pr.owner, &pr.repo, pr.number)
+                .await
+            else {
+                continue;
+            };
+            let Some(fingerprint) = fingerprint(&data) else {
+                continue;
+            };
+
+            if subscriptions.record(&uri, fingerprint)
+                && !peer.notify("notifications/resources/updated", json!({ "uri": uri }))
+            {
+                return;
+            }
+        }
+    }
+}
+
+fn uri_param(request: &JsonRpcRequest) -> Result<String, Box<JsonRpcResponse>> {
+    let params = request
+        .params
+        .as_ref()
+        .ok_or_else(|| JsonRpcResponse::error(request.id.clone(), -32602, "Missing params"))
+        .map_err(Box::new)?;
+    let params: ResourceUriParams = serde_json::from_value(params.clone()).map_err(|e| {
+        Box::new(JsonRpcResponse::error(
+            request.id.clone(),
+            -32602,
+            format!("Invalid params: {}", e),
+        ))
+    })?;
+    Ok(params.uri)
+}
+
+fn resource_coordinates(
+    request: &JsonRpcRequest,
+) -> Result<(String, PrCoordinates), Box<JsonRpcResponse>> {
+    let uri = uri_param(request)?;
+    let pr = PrCoordinates::from_uri(&uri).map_err(|_| {
+        Box::new(JsonRpcResponse::error(
+            request.id.clone(),
+            -32002,
+            format!("Resource not found: {}", uri),
+        ))
+    })?;
+    Ok((uri, pr))
+}
+
+impl McpServer {
+    pub(super) async fn handle_list_resources(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
+        let prs = match self.backend.list_review_requests().await {
+            Ok(prs) => prs,
+            Err(e) => {
+                return JsonRpcResponse::error(
+                    request.id.clone(),
+                    -32603,
+                    format!("Failed to list pull requests: {}", e),
+                )
+            }
+        };
+
+        let prs: Vec<PrSummary> = match serde_json::from_value(prs) {
+            Ok(prs) => prs,
+            Err(e) => {
+                return JsonRpcResponse::error(
+                    request.id.clone(),
+                    -32603,
+                    format!("Unexpected pull request data: {}", e),
+                )
+            }
+        };
+
+        let resources = prs
+            .into_iter()
+            .filter_map(|pr| {
+                let (owner, repo) = pr.repository.full_name.split_once('/')?;
+                let uri = PrCoordinates {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    number: pr.number as i32,
+                }
+                .to_uri();
+                Some(Resource {
+                    uri,
+                    name: format!("{}#{}", pr.repository.full_name, pr.number),
+                    description: Some(pr.title),
+                    mime_type: Some(PR_MIME_TYPE.to_string()),
+                })
+            })
+            .collect();
+
+        let result = ListResourcesResult { resources };
+        JsonRpcResponse::success(request.id.clone(), serde_json::to_value(result).unwrap())
+    }
+
+    pub(super) fn handle_list_resource_templates(
+        &self,
+        request: &JsonRpcRequest,
+    ) -> JsonRpcResponse {
+        let result = ListResourceTemplatesResult {
+            resource_templates: vec![ResourceTemplate {
+                uri_template: PR_URI_TEMPLATE.to_string(),
+                name: "Pull request".to_string(),
+                description: Some(
+                    "Description, diff summary and checks of a GitHub pull request".to_string(),
+                ),
+                mime_type: Some(PR_MIME_TYPE.to_string()),
+            }],
+        };
+        JsonRpcResponse::success(request.id.clone(), serde_json::to_value(result).unwrap())
+    }
+
+    pub(super) async fn handle_read_resource(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
+        let (uri, pr) = match resource_coordinates(request) {
+            Ok(parsed) => parsed,
+            Err(response) => return *response,
+        };
+
+        match self
+            .backend
+            .get_pull_request_details(&pr.owner, &pr.repo, pr.number)
+            .await
+        {
+            Ok(details) => {
+                let result = ReadResourceResult {
+                    contents: vec![TextResourceContents {
+                        uri,
+                        mime_type: Some(PR_MIME_TYPE.to_string()),
+                        text: format_pr_resource(&details),
+                    }],
+                };
+                JsonRpcResponse::success(request.id.clone(), serde_json::to_value(result).unwrap())
+            }
+            Err(e) => JsonRpcResponse::error(
+                request.id.clone(),
+                -32603,
+                format!("Failed to read {}: {}", uri, e),
+            ),
+        }
+    }
+
+    pub(super) fn handle_subscribe(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
+        match resource_coordinates(request) {
+            Ok((uri, _)) => {
+                self.subscriptions.insert(uri);
+                JsonRpcResponse::success(request.id.clone(), json!({}))
+            }
+            Err(response) => *response,
+        }
+    }
+
+    pub(super) fn handle_unsubscribe(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
+        match uri_param(request) {
+            Ok(uri) => {
+                self.subscriptions.remove(&uri);
+                JsonRpcResponse::success(request.id.clone(), json!({}))
+            }
+            Err(response) => *response,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::server::offline_env;
+    use serial_test::serial;
+
+    fn request(method: &str, params: Option<Value>) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    #[test]
+    fn test_subscriptions_record_detects_changes() {
+        let subscriptions = Subscriptions::default();
+        let uri = "ghview://pr/mirakui/ghview/1";
+        subscriptions.insert(uri.to_string());
+
+        // The first observation only establishes a baseline
+        assert!(!subscriptions.record(uri, "a".to_string()));
+        assert!(!subscriptions.record(uri, "a".to_string()));
+        assert!(subscriptions.record(uri, "b".to_string()));
+        assert!(!subscriptions.record("ghview://pr/other/repo/2", "a".to_string()));
+    }
+
+    #[test]
+    fn test_fingerprint_includes_check_state() {
+        let pending =
+            json!({"updated_at": "2024-01-01T00:00:00Z", "check_status": {"state": "pending"}});
+        let success =
+            json!({"updated_at": "2024-01-01T00:00:00Z", "check_status": {"state": "success"}});
+        assert_ne!(fingerprint(&pending), fingerprint(&success));
+        assert_eq!(fingerprint(&json!({})), None);
+    }
+
+    #[tokio::test]
+    async fn test_list_resource_templates() {
+        let response = McpServer::unconnected()
+            .handle_request(&request("resources/templates/list", None))
+            .await;
+        let result = response.result.unwrap();
+        assert_eq!(
+            result["resourceTemplates"][0]["uriTemplate"],
+            "ghview://pr/{owner}/{repo}/{number}"
+        );
+        assert_eq!(result["resourceTemplates"][0]["mimeType"], "text/markdown");
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_and_unsubscribe() {
+        let server = McpServer::unconnected();
+        let uri = "ghview://pr/mirakui/ghview/42";
+
+        let response = server
+            .handle_request(&request("resources/subscribe", Some(json!({"uri": uri}))))
+            .await;
+        assert!(response.error.is_none());
+        assert_eq!(server.subscriptions.uris(), vec![uri.to_string()]);
+
+        let response = server
+            .handle_request(&request("resources/unsubscribe", Some(json!({"uri": uri}))))
+            .await;
+        assert!(response.error.is_none());
+        assert!(server.subscriptions.uris().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_rejects_unknown_uri() {
+        let server = McpServer::unconnected();
+        let response = server
+            .handle_request(&request(
+                "resources/subscribe",
+                Some(json!({"uri": "file:///etc/passwd"})),
+            ))
+            .await;
+        assert_eq!(response.error.unwrap().code, -32002);
+        assert!(server.subscriptions.uris().is_empty());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_read_resource_errors() {
+        let _env = offline_env();
+        let server = McpServer::unconnected();
+
+        let response = server
+            .handle_request(&request("resources/read", None))
+            .await;
+        assert_eq!(response.error.unwrap().code, -32602);
+
+        let response = server
+            .handle_request(&request(
+                "resources/read",
+                Some(json!({"uri": "ghview://pr/mirakui/ghview"})),
+            ))
+            .await;
+        assert_eq!(response.error.unwrap().code, -32002);
+
+        let response = server
+            .handle_request(&request(
+                "resources/read",
+                Some(json!({"uri": "ghview://pr/mirakui/ghview/1"})),
+            ))
+            .await;
+        assert_eq!(response.error.unwrap().code, -32603);
+    }
+}
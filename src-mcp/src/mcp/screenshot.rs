@@ -0,0 +1,256 @@
+use serde_json::{json, Map, Value};
+use std::path::PathBuf;
+
+/// Validated arguments of the `screenshot` tool
+#[derive(Debug, PartialEq)]
+pub struct ScreenshotOptions {
+    /// Return the image as base64 content instead of only the file path
+    pub inline: bool,
+    /// Where ghview writes the PNG; `None` means a temporary directory
+    pub output_dir: Option<String>,
+    pub max_width: Option<u64>,
+    pub max_height: Option<u64>,
+    pub region: Option<Value>,
+    pub element: Option<String>,
+}
+
+impl ScreenshotOptions {
+    pub fn from_arguments(arguments: &Option<Value>) -> Result<Self, String> {
+        let empty = Value::Object(Map::new());
+        let args = arguments.as_ref().unwrap_or(&empty);
+
+        let inline = match args.get("inline") {
+            Some(value) => value
+                .as_bool()
+                .ok_or("Invalid argument: inline must be a boolean")?,
+            None => false,
+        };
+
+        let output_dir = match args.get("output_dir") {
+            Some(value) => Some(
+                value
+                    .as_str()
+                    .filter(|dir| !dir.is_empty())
+                    .ok_or("Invalid argument: output_dir must be a non-empty string")?
+                    .to_string(),
+            ),
+            None => None,
+        };
+        if output_dir.is_none() && !inline {
+            return Err("Missing required argument: output_dir".to_string());
+        }
+
+        let region = match args.get("region") {
+            Some(value) => Some(parse_region(value)?),
+            None => None,
+        };
+
+        let element = match args.get("element") {
+            Some(value) => Some(
+                value
+                    .as_str()
+                    .filter(|selector| !selector.trim().is_empty())
+                    .ok_or("Invalid argument: element must be a non-empty CSS selector")?
+                    .to_string(),
+            ),
+            None => None,
+        };
+        if region.is_some() && element.is_some() {
+            return Err(
+                "Invalid arguments: specify either region or element, not both".to_string(),
+            );
+        }
+
+        Ok(Self {
+            inline,
+            output_dir,
+            max_width: positive_integer(args, "max_width")?,
+            max_height: positive_integer(args, "max_height")?,
+            region,
+            element,
+        })
+    }
+
+    /// Directory ghview should write to, falling back to a temporary directory
+    pub fn resolve_output_dir(&self) -> PathBuf {
+        match &self.output_dir {
+            Some(dir) => PathBuf::from(dir),
+            None => std::env::temp_dir().join("ghview-mcp-screenshots"),
+        }
+    }
+
+    /// Parameters for the ghview `screenshot` IPC method
+    pub fn ipc_params(&self, output_dir: &str) -> Value {
+        let mut params = json!({ "output_dir": output_dir });
+        if let Some(max_width) = self.max_width {
+            params["max_width"] = json!(max_width);
+        }
+        if let Some(max_height) = self.max_height {
+            params["max_height"] = json!(max_height);
+        }
+        if let Some(region) = &self.region {
+            params["region"] = region.clone();
+        }
+        if let Some(element) = &self.element {
+            params["element"] = json!(element);
+        }
+        params
+    }
+}
+
+fn positive_integer(args: &Value, key: &str) -> Result<Option<u64>, String> {
+    match args.get(key) {
+        Some(value) => value
+            .as_u64()
+            .filter(|n| *n > 0 && *n <= u32::MAX as u64)
+            .map(Some)
+            .ok_or_else(|| format!("Invalid argument: {} must be a positive integer", key)),
+        None => Ok(None),
+    }
+}
+
+fn parse_region(value: &Value) -> Result<Value, String> {
+    let coordinate = |key: &str, allow_zero: bool| {
+        value
+            .get(key)
+            .and_then(Value::as_u64)
+            .filter(|n| (allow_zero || *n > 0) && *n <= u32::MAX as u64)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid argument: region.{} must be a {} integer",
+                    key,
+                    if allow_zero {
+                        "non-negative"
+                    } else {
+                        "positive"
+                    }
+                )
+            })
+    };
+
+    if !value.is_object() {
+        return Err("Invalid argument: region must be an object".to_string());
+    }
+    Ok(json!({
+        "x": coordinate("x", true)?,
+        "y": coordinate("y", true)?,
+        "width": coordinate("width", false)?,
+        "height": coordinate("height", false)?,
+    }))
+}
+
+pub fn screenshot_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "output_dir": {
+                "type": "string",
+                "description": "Directory to save the screenshot file (required unless inline is true)"
+            },
+            "inline": {
+                "type": "boolean",
+                "description": "Return the PNG as image content instead of only the file path"
+            },
+            "max_width": {
+                "type": "integer",
+                "minimum": 1,
+                "description": "Downscale the image to at most this many pixels wide"
+            },
+            "max_height": {
+                "type": "integer",
+                "minimum": 1,
+                "description": "Downscale the image to at most this many pixels high"
+            },
+            "region": {
+                "type": "object",
+                "description": "Crop to this rectangle in window coordinates (logical pixels)",
+                "properties": {
+                    "x": { "type": "integer", "minimum": 0 },
+                    "y": { "type": "integer", "minimum": 0 },
+                    "width": { "type": "integer", "minimum": 1 },
+                    "height": { "type": "integer", "minimum": 1 }
+                },
+                "required": ["x", "y", "width", "height"]
+            },
+            "element": {
+                "type": "string",
+                "description": "Crop to the element matching this CSS selector"
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screenshot_options_defaults() {
+        let options =
+            ScreenshotOptions::from_arguments(&Some(json!({"output_dir": "/tmp/shots"}))).unwrap();
+        assert!(!options.inline);
+        assert_eq!(options.output_dir.as_deref(), Some("/tmp/shots"));
+        assert_eq!(
+            options.ipc_params("/tmp/shots"),
+            json!({"output_dir": "/tmp/shots"})
+        );
+    }
+
+    #[test]
+    fn test_screenshot_options_inline_without_output_dir() {
+        let options = ScreenshotOptions::from_arguments(&Some(json!({
+            "inline": true,
+            "max_width": 800,
+            "region": {"x": 0, "y": 10, "width": 400, "height": 300}
+        })))
+        .unwrap();
+        assert!(options.inline);
+        assert_eq!(options.output_dir, None);
+        assert_eq!(
+            options.ipc_params("/tmp/x"),
+            json!({
+                "output_dir": "/tmp/x",
+                "max_width": 800,
+                "region": {"x": 0, "y": 10, "width": 400, "height": 300}
+            })
+        );
+    }
+
+    #[test]
+    fn test_screenshot_options_invalid() {
+        let error = |args: Value| ScreenshotOptions::from_arguments(&Some(args)).unwrap_err();
+
+        assert_eq!(
+            ScreenshotOptions::from_arguments(&None).unwrap_err(),
+            "Missing required argument: output_dir"
+        );
+        assert_eq!(
+            error(json!({"inline": "yes"})),
+            "Invalid argument: inline must be a boolean"
+        );
+        assert_eq!(
+            error(json!({"inline": true, "max_height": 0})),
+            "Invalid argument: max_height must be a positive integer"
+        );
+        assert_eq!(
+            error(json!({"inline": true, "region": {"x": 0, "y": 0, "width": 0, "height": 1}})),
+            "Invalid argument: region.width must be a positive integer"
+        );
+        assert_eq!(
+            error(json!({"inline": true, "region": {"x": -1, "y": 0, "width": 1, "height": 1}})),
+            "Invalid argument: region.x must be a non-negative integer"
+        );
+        assert_eq!(
+            error(json!({"inline": true, "element": " "})),
+            "Invalid argument: element must be a non-empty CSS selector"
+        );
+        assert_eq!(
+            error(json!({
+                "inline": true,
+                "element": "#pr-list",
+                "region": {"x": 0, "y": 0, "width": 1, "height": 1}
+            })),
+            "Invalid arguments: specify either region or element, not both"
+        );
+    }
+}
@@ -1,57 +1,328 @@
-use crate::ipc::IpcClient;
+use crate::backend::Backend;
+use crate::mcp::actions::{action_tools, is_action, mcp_settings};
+use crate::mcp::automation::{automation_tools, is_automation};
+use crate::mcp::coordinates::{pr_coordinates_schema, PrCoordinates};
+use crate::mcp::format::{
+    format_check_status, format_pr_list, format_pr_view, format_pull_request,
+};
+use crate::mcp::peer::{Peer, Progress};
 use crate::mcp::protocol::*;
+use crate::mcp::resources::{watch_subscriptions, Subscriptions};
+use crate::mcp::screenshot::{screenshot_schema, ScreenshotOptions};
 use anyhow::Result;
+use base64::Engine;
 use serde_json::{json, Value};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::{AbortHandle, JoinHandle, JoinSet};
 
+#[derive(Clone, Default)]
 pub struct McpServer {
-    ipc_client: IpcClient,
+    pub(super) backend: Backend,
+    pub(super) subscriptions: Subscriptions,
+    in_flight: InFlight,
+}
+
+/// Requests still being handled, keyed by their JSON-encoded id, so they can be cancelled
+#[derive(Clone, Default)]
+struct InFlight(Arc<Mutex<HashMap<String, AbortHandle>>>);
+
+impl InFlight {
+    fn key(id: &Value) -> String {
+        id.to_string()
+    }
+
+    fn remove(&self, id: &Value) {
+        self.0.lock().unwrap().remove(&Self::key(id));
+    }
+
+    /// Abort the request with `id`, returning whether it was still running
+    fn cancel(&self, id: &Value) -> bool {
+        match self.0.lock().unwrap().remove(&Self::key(id)) {
+            Some(handle) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Responses still owed for one message from the client
+pub(super) struct Pending {
+    requests: JoinSet<JsonRpcResponse>,
+    /// Responses collected so far when the message was a batch
+    batch: Option<Vec<JsonRpcResponse>>,
+    peer: Peer,
+}
+
+impl Pending {
+    /// Whether there is nothing left to send
+    pub(super) fn is_empty(&self) -> bool {
+        self.requests.is_empty() && self.batch.as_ref().is_none_or(Vec::is_empty)
+    }
+
+    /// Wait for the requests to finish and send their responses; a batch is answered
+    /// with one array, leaving out cancelled requests
+    pub(super) async fn finish(mut self) {
+        while let Some(result) = self.requests.join_next().await {
+            let Ok(response) = result else {
+                continue;
+            };
+            match self.batch.as_mut() {
+                Some(responses) => responses.push(response),
+                None => {
+                    self.peer.send(&response);
+                }
+            }
+        }
+        if let Some(responses) = self.batch.filter(|responses| !responses.is_empty()) {
+            self.peer.send(&responses);
+        }
+    }
+}
+
+/// How an incoming message is dealt with
+enum Incoming {
+    /// Already handled; notifications and client responses get no reply
+    Done(Option<JsonRpcResponse>),
+    /// A request to handle concurrently with others
+    Request(JsonRpcRequest),
 }
 
 impl McpServer {
-    pub async fn new() -> Result<Self> {
-        let ipc_client = IpcClient::connect().await?;
-        Ok(Self { ipc_client })
+    /// A server that starts whether or not ghview is running; the app is connected
+    /// to on demand
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A server for one more client, with its own subscriptions and requests but the
+    /// same connection to the app
+    pub(super) fn for_session(&self) -> Self {
+        Self {
+            backend: self.backend.clone(),
+            ..Self::default()
+        }
     }
 
     pub async fn run(&self) -> Result<()> {
-        let stdin = tokio::io::stdin();
-        let mut stdout = tokio::io::stdout();
-        let mut reader = BufReader::new(stdin);
-        let mut line = String::new();
+        self.serve(tokio::io::stdin(), tokio::io::stdout()).await
+    }
+
+    /// Serve one client over newline-delimited JSON-RPC. Requests run concurrently and
+    /// each response is written as soon as it's ready.
+    pub async fn serve<R, W>(&self, reader: R, writer: W) -> Result<()>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let mut lines = BufReader::new(reader).lines();
+
+        // Responses and server-initiated notifications share the output through one writer
+        let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+        let writer = tokio::spawn(async move {
+            let mut writer = writer;
+            while let Some(message) = rx.recv().await {
+                writer.write_all(message.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+            Ok::<(), std::io::Error>(())
+        });
+        let peer = Peer::new(tx);
+        let watcher = self.watch_subscriptions(&peer);
 
+        let mut tasks = JoinSet::new();
         loop {
-            line.clear();
-            let bytes_read = reader.read_line(&mut line).await?;
-            if bytes_read == 0 {
-                break;
+            tokio::select! {
+                line = lines.next_line() => {
+                    let Some(line) = line? else {
+                        break;
+                    };
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    let pending = match serde_json::from_str::<Value>(line) {
+                        Ok(message) => self.handle_message(message, &peer),
+                        Err(e) => {
+                            peer.send(&JsonRpcResponse::parse_error(e));
+                            continue;
+                        }
+                    };
+                    if !pending.is_empty() {
+                        tasks.spawn(pending.finish());
+                    }
+                }
+                Some(_) = tasks.join_next(), if !tasks.is_empty() => {}
             }
+        }
 
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
+        // Requests read before the input closed still get their responses
+        while tasks.join_next().await.is_some() {}
+
+        watcher.abort();
+        let _ = watcher.await;
+        drop(peer);
+        writer.await??;
+        Ok(())
+    }
+
+    /// Send `notifications/resources/updated` to `peer` for this server's subscriptions
+    pub(super) fn watch_subscriptions(&self, peer: &Peer) -> JoinHandle<()> {
+        tokio::spawn(watch_subscriptions(
+            self.backend.clone(),
+            self.subscriptions.clone(),
+            peer.clone(),
+        ))
+    }
+
+    /// Handle one message or batch from the client. Notifications and `initialize` are
+    /// handled before this returns; requests start running and their responses are
+    /// sent to `peer` by the returned `Pending`.
+    pub(super) fn handle_message(&self, message: Value, peer: &Peer) -> Pending {
+        let mut pending = Pending {
+            requests: JoinSet::new(),
+            batch: None,
+            peer: peer.clone(),
+        };
+        match message {
+            Value::Array(messages) if messages.is_empty() => {
+                peer.send(&JsonRpcResponse::error(
+                    None,
+                    -32600,
+                    "Invalid Request: empty batch",
+                ));
+            }
+            Value::Array(messages) => {
+                let mut responses = Vec::new();
+                for message in messages {
+                    match self.receive(message, peer, true) {
+                        Incoming::Done(response) => responses.extend(response),
+                        Incoming::Request(request) => {
+                            self.start(request, peer, &mut pending.requests)
+                        }
+                    }
+                }
+                pending.batch = Some(responses);
             }
+            message => match self.receive(message, peer, false) {
+                Incoming::Done(response) => {
+                    if let Some(response) = response {
+                        peer.send(&response);
+                    }
+                }
+                Incoming::Request(request) => self.start(request, peer, &mut pending.requests),
+            },
+        }
+        pending
+    }
 
-            let response = match serde_json::from_str::<JsonRpcRequest>(line) {
-                Ok(request) => self.handle_request(&request).await,
-                Err(e) => JsonRpcResponse::error(None, -32700, format!("Parse error: {}", e)),
-            };
+    /// Validate a message and handle what must happen in order: notifications and
+    /// `initialize`, which later requests depend on
+    fn receive(&self, message: Value, peer: &Peer, in_batch: bool) -> Incoming {
+        // This server sends no requests, so responses from the client are not expected
+        if message.get("method").is_none()
+            && (message.get("result").is_some() || message.get("error").is_some())
+        {
+            return Incoming::Done(None);
+        }
+
+        let id = message.get("id").cloned().filter(|id| !id.is_null());
+        let request = match serde_json::from_value::<JsonRpcRequest>(message) {
+            Ok(request) if request.jsonrpc == "2.0" => request,
+            Ok(_) => {
+                return Incoming::Done(Some(JsonRpcResponse::error(
+                    id,
+                    -32600,
+                    "Invalid Request: jsonrpc must be \"2.0\"",
+                )))
+            }
+            Err(e) => {
+                return Incoming::Done(Some(JsonRpcResponse::error(
+                    id,
+                    -32600,
+                    format!("Invalid Request: {}", e),
+                )))
+            }
+        };
 
-            let response_json = serde_json::to_string(&response)?;
-            stdout.write_all(response_json.as_bytes()).await?;
-            stdout.write_all(b"\n").await?;
-            stdout.flush().await?;
+        if request.id.is_none() {
+            self.handle_notification(&request, peer);
+            return Incoming::Done(None);
+        }
+        match request.method.as_str() {
+            "initialize" if in_batch => Incoming::Done(Some(JsonRpcResponse::error(
+                request.id.clone(),
+                -32600,
+                "Invalid Request: initialize must not be part of a batch",
+            ))),
+            "initialize" => Incoming::Done(Some(self.handle_initialize(&request, peer))),
+            "ping" => Incoming::Request(request),
+            _ if !peer.is_initialized() => Incoming::Done(Some(JsonRpcResponse::error(
+                request.id.clone(),
+                -32600,
+                "Invalid Request: server not initialized",
+            ))),
+            _ => Incoming::Request(request),
         }
+    }
 
-        Ok(())
+    /// Handle `request` in `tasks`, registered so that `notifications/cancelled` can abort it
+    fn start(&self, request: JsonRpcRequest, peer: &Peer, tasks: &mut JoinSet<JsonRpcResponse>) {
+        let key = InFlight::key(request.id.as_ref().unwrap_or(&Value::Null));
+        let server = self.clone();
+        let peer = peer.clone();
+
+        // Held across the spawn so a fast request can't finish before it's registered
+        let mut in_flight = self.in_flight.0.lock().unwrap();
+        let handle = tasks.spawn(async move {
+            let response = server.dispatch(&request, &peer).await;
+            server.in_flight.remove(&request.id.unwrap_or_default());
+            response
+        });
+        in_flight.insert(key, handle);
+    }
+
+    fn handle_notification(&self, notification: &JsonRpcRequest, peer: &Peer) {
+        if notification.method != "notifications/cancelled" {
+            // Nothing to do for `notifications/initialized` and others
+            return;
+        }
+        let Some(params) = notification
+            .params
+            .clone()
+            .and_then(|params| serde_json::from_value::<CancelledParams>(params).ok())
+        else {
+            return;
+        };
+        if self.in_flight.cancel(&params.request_id) {
+            peer.log(
+                LogLevel::Debug,
+                "requests",
+                json!({
+                    "cancelled": params.request_id,
+                    "reason": params.reason,
+                }),
+            );
+        }
     }
 
-    async fn handle_request(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
+    pub(super) async fn dispatch(&self, request: &JsonRpcRequest, peer: &Peer) -> JsonRpcResponse {
         match request.method.as_str() {
-            "initialize" => self.handle_initialize(request),
-            "initialized" => JsonRpcResponse::success(request.id.clone(), json!({})),
             "tools/list" => self.handle_list_tools(request),
-            "tools/call" => self.handle_call_tool(request).await,
+            "tools/call" => self.handle_call_tool(request, peer).await,
+            "resources/list" => self.handle_list_resources(request).await,
+            "resources/templates/list" => self.handle_list_resource_templates(request),
+            "resources/read" => self.handle_read_resource(request).await,
+            "resources/subscribe" => self.handle_subscribe(request),
+            "resources/unsubscribe" => self.handle_unsubscribe(request),
+            "prompts/list" => self.handle_list_prompts(request),
+            "prompts/get" => self.handle_get_prompt(request).await,
+            "logging/setLevel" => handle_set_level(request, peer),
             "ping" => JsonRpcResponse::success(request.id.clone(), json!({})),
             _ => JsonRpcResponse::error(
                 request.id.clone(),
@@ -61,13 +332,39 @@ impl McpServer {
         }
     }
 
-    fn handle_initialize(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
+    fn handle_initialize(&self, request: &JsonRpcRequest, peer: &Peer) -> JsonRpcResponse {
+        let params = match request
+            .params
+            .clone()
+            .map(serde_json::from_value::<InitializeParams>)
+        {
+            Some(Ok(params)) => params,
+            Some(Err(e)) => {
+                return JsonRpcResponse::error(
+                    request.id.clone(),
+                    -32602,
+                    format!("Invalid params: {}", e),
+                )
+            }
+            None => return JsonRpcResponse::error(request.id.clone(), -32602, "Missing params"),
+        };
+        let protocol_version = negotiate_protocol_version(&params.protocol_version);
+        peer.set_protocol_version(protocol_version);
+
         let result = InitializeResult {
-            protocol_version: PROTOCOL_VERSION.to_string(),
+            protocol_version: protocol_version.to_string(),
             capabilities: ServerCapabilities {
                 tools: Some(ToolsCapability {
                     list_changed: Some(false),
                 }),
+                resources: Some(ResourcesCapability {
+                    subscribe: Some(true),
+                    list_changed: Some(false),
+                }),
+                prompts: Some(PromptsCapability {
+                    list_changed: Some(false),
+                }),
+                logging: Some(LoggingCapability {}),
             },
             server_info: ServerInfo {
                 name: SERVER_NAME.to_string(),
@@ -75,7 +372,21 @@ impl McpServer {
             },
             instructions: Some(
                 "MCP server for ghview - a GitHub PR viewer application. \
-                 Use the 'screenshot' tool to capture the ghview window."
+                 Use 'list_review_requests' to see pull requests awaiting review, \
+                 'get_pull_request' and 'get_check_status' to inspect one, \
+                 'refresh' to reload the list in the app, \
+                 and 'screenshot' to capture the ghview window. \
+                 Before a screenshot, 'navigate', 'select_pr', 'resize_window' and \
+                 'set_theme' set up the window and 'wait_for' waits until it is ready. \
+                 'submit_review', 'add_comment' and 'rerun_failed_checks' act on \
+                 GitHub when enabled in ghview's config; pass dry_run to preview \
+                 what they would send. \
+                 Pull request data comes straight from GitHub when the app isn't \
+                 running; only 'screenshot' and the window tools need it open. \
+                 Each open pull request is also available as a resource \
+                 at ghview://pr/{owner}/{repo}/{number}, and the 'review_pr', \
+                 'summarize_review_queue' and 'explain_ci_failure' prompts provide \
+                 ready-made review flows."
                     .to_string(),
             ),
         };
@@ -84,28 +395,73 @@ impl McpServer {
     }
 
     fn handle_list_tools(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
-        let tools = vec![Tool {
-            name: "screenshot".to_string(),
-            description:
-                "Capture a screenshot of the ghview window and save it to the specified directory"
-                    .to_string(),
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "output_dir": {
-                        "type": "string",
-                        "description": "Directory to save the screenshot file"
+        let mut tools = vec![
+            Tool {
+                name: "list_review_requests".to_string(),
+                description:
+                    "List open pull requests where your review is requested or you are assigned"
+                        .to_string(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {
+                        "limit": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Maximum number of pull requests to return"
+                        },
+                        "group_by": {
+                            "type": "string",
+                            "enum": ["none", "repository", "check_state", "review_status", "age"],
+                            "description": "Group the list; defaults to the view settings in ghview's config"
+                        },
+                        "sort": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Sort keys applied in order (updated, created, checks, review, \
+                                            requested, repository, title); prefix with - for descending, \
+                                            e.g. [\"checks\", \"created\"]"
+                        }
                     }
-                },
-                "required": ["output_dir"]
-            }),
-        }];
+                }),
+            },
+            Tool {
+                name: "get_pull_request".to_string(),
+                description: "Get details and check status of a single pull request".to_string(),
+                input_schema: pr_coordinates_schema(),
+            },
+            Tool {
+                name: "get_check_status".to_string(),
+                description: "Get the combined CI status of a pull request's head commit"
+                    .to_string(),
+                input_schema: pr_coordinates_schema(),
+            },
+            Tool {
+                name: "refresh".to_string(),
+                description: "Reload the review queue in the ghview window and return it; \
+                              fetches it from GitHub when ghview isn't running"
+                    .to_string(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {}
+                }),
+            },
+            Tool {
+                name: "screenshot".to_string(),
+                description:
+                    "Capture a screenshot of the ghview window, optionally cropped and \
+                              scaled, and save it to a directory or return it inline as a PNG image"
+                        .to_string(),
+                input_schema: screenshot_schema(),
+            },
+        ];
+        tools.extend(automation_tools());
+        tools.extend(action_tools(&mcp_settings()));
 
         let result = ListToolsResult { tools };
         JsonRpcResponse::success(request.id.clone(), serde_json::to_value(result).unwrap())
     }
 
-    async fn handle_call_tool(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
+    async fn handle_call_tool(&self, request: &JsonRpcRequest, peer: &Peer) -> JsonRpcResponse {
         let params: CallToolParams = match request.params.as_ref() {
             Some(p) => match serde_json::from_value(p.clone()) {
                 Ok(params) => params,
@@ -122,26 +478,383 @@ impl McpServer {
             }
         };
 
+        let progress = Progress::new(peer, request.params.as_ref(), 1);
         let result = match params.name.as_str() {
-            "screenshot" => self.call_screenshot(&params.arguments).await,
+            "screenshot" => self.call_screenshot(&params.arguments, &progress).await,
+            "list_review_requests" => {
+                self.call_list_review_requests(&params.arguments, &progress)
+                    .await
+            }
+            "get_pull_request" => self.call_get_pull_request(&params.arguments).await,
+            "get_check_status" => self.call_get_check_status(&params.arguments).await,
+            "refresh" => self.call_refresh(&progress).await,
+            name if is_automation(name) => {
+                self.call_automation(name, &params.arguments, &progress)
+                    .await
+            }
+            name if is_action(name) => {
+                self.call_action(name, &params.arguments, peer, &progress)
+                    .await
+            }
             _ => CallToolResult::error(format!("Unknown tool: {}", params.name)),
         };
+        if result.is_error == Some(true) {
+            if let Some(Content::Text { text }) = result.content.first() {
+                peer.log(
+                    LogLevel::Error,
+                    "tools",
+                    json!({ "tool": params.name, "error": text }),
+                );
+            }
+        }
 
         JsonRpcResponse::success(request.id.clone(), serde_json::to_value(result).unwrap())
     }
 
-    async fn call_screenshot(&self, arguments: &Option<Value>) -> CallToolResult {
-        let output_dir = match arguments {
-            Some(args) => match args.get("output_dir").and_then(|v| v.as_str()) {
-                Some(dir) => dir.to_string(),
-                None => return CallToolResult::error("Missing required argument: output_dir"),
+    async fn call_screenshot(
+        &self,
+        arguments: &Option<Value>,
+        progress: &Progress,
+    ) -> CallToolResult {
+        let options = match ScreenshotOptions::from_arguments(arguments) {
+            Ok(options) => options,
+            Err(e) => return CallToolResult::error(e),
+        };
+        let output_dir = options.resolve_output_dir();
+        let params = options.ipc_params(&output_dir.to_string_lossy());
+
+        progress.report(0, "Capturing the ghview window");
+        let result = match self.backend.screenshot(params).await {
+            Ok(result) => result,
+            Err(e) => return CallToolResult::error(format!("Screenshot failed: {}", e)),
+        };
+        progress.report(1, "Captured the ghview window");
+        let summary = serde_json::to_string_pretty(&result).unwrap_or_default();
+        if !options.inline {
+            return CallToolResult::success(summary);
+        }
+
+        let Some(path) = result.get("path").and_then(Value::as_str) else {
+            return CallToolResult::error("Screenshot failed: ghview did not return a file path");
+        };
+        let png = match tokio::fs::read(path).await {
+            Ok(png) => png,
+            Err(e) => return CallToolResult::error(format!("Failed to read screenshot: {}", e)),
+        };
+        let data = base64::engine::general_purpose::STANDARD.encode(&png);
+
+        // Without an explicit output_dir the file only existed to carry the image over
+        if options.output_dir.is_none() {
+            let _ = tokio::fs::remove_file(path).await;
+            return CallToolResult::contents(vec![Content::image(data, "image/png")]);
+        }
+        CallToolResult::contents(vec![
+            Content::text(summary),
+            Content::image(data, "image/png"),
+        ])
+    }
+
+    async fn call_list_review_requests(
+        &self,
+        arguments: &Option<Value>,
+        progress: &Progress,
+    ) -> CallToolResult {
+        let limit = match arguments.as_ref().and_then(|args| args.get("limit")) {
+            Some(value) => match value.as_u64().filter(|n| *n > 0) {
+                Some(n) => Some(n as usize),
+                None => {
+                    return CallToolResult::error(
+                        "Invalid argument: limit must be a positive integer",
+                    )
+                }
             },
-            None => return CallToolResult::error("Missing arguments"),
+            None => None,
+        };
+
+        let mut params = serde_json::Map::new();
+        if let Some(group_by) = arguments.as_ref().and_then(|args| args.get("group_by")) {
+            if !group_by.is_string() {
+                return CallToolResult::error("Invalid argument: group_by must be a string");
+            }
+            params.insert("group_by".to_string(), group_by.clone());
+        }
+        if let Some(sort) = arguments.as_ref().and_then(|args| args.get("sort")) {
+            let valid = sort
+                .as_array()
+                .is_some_and(|keys| keys.iter().all(Value::is_string));
+            if !valid {
+                return CallToolResult::error("Invalid argument: sort must be an array of strings");
+            }
+            params.insert("sort".to_string(), sort.clone());
+        }
+
+        progress.report(0, "Fetching review requests");
+        match self.backend.get_pr_view(Value::Object(params)).await {
+            Ok(mut view) => {
+                progress.report(1, "Fetched review requests");
+                if let Some(limit) = limit {
+                    truncate_view(&mut view, limit);
+                }
+                let prs: Vec<Value> = view["groups"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|group| group["pull_requests"].as_array())
+                    .flatten()
+                    .cloned()
+                    .collect();
+                let groups: Vec<Value> = view["groups"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|group| json!({ "key": group["key"], "label": group["label"], "count": group["count"] }))
+                    .collect();
+                CallToolResult::structured(
+                    format_pr_view(&view),
+                    json!({ "pull_requests": prs, "groups": groups }),
+                )
+            }
+            Err(e) => CallToolResult::error(format!("Failed to list review requests: {}", e)),
+        }
+    }
+
+    async fn call_get_pull_request(&self, arguments: &Option<Value>) -> CallToolResult {
+        let pr = match PrCoordinates::from_arguments(arguments) {
+            Ok(pr) => pr,
+            Err(e) => return CallToolResult::error(e),
+        };
+
+        match self
+            .backend
+            .get_pull_request(&pr.owner, &pr.repo, pr.number)
+            .await
+        {
+            Ok(data) => CallToolResult::structured(format_pull_request(&data), data),
+            Err(e) => CallToolResult::error(format!("Failed to get pull request: {}", e)),
+        }
+    }
+
+    async fn call_get_check_status(&self, arguments: &Option<Value>) -> CallToolResult {
+        let pr = match PrCoordinates::from_arguments(arguments) {
+            Ok(pr) => pr,
+            Err(e) => return CallToolResult::error(e),
+        };
+
+        match self
+            .backend
+            .get_check_status(&pr.owner, &pr.repo, pr.number)
+            .await
+        {
+            Ok(data) => CallToolResult::structured(
+                format_check_status(&pr.owner, &pr.repo, pr.number, &data),
+                data,
+            ),
+            Err(e) => CallToolResult::error(format!("Failed to get check status: {}", e)),
+        }
+    }
+
+    async fn call_refresh(&self, progress: &Progress) -> CallToolResult {
+        progress.report(0, "Reloading review requests");
+        match self.backend.refresh().await {
+            Ok(prs) => {
+                progress.report(1, "Reloaded review requests");
+                CallToolResult::structured(format_pr_list(&prs), json!({ "pull_requests": prs }))
+            }
+            Err(e) => CallToolResult::error(format!("Refresh failed: {}", e)),
+        }
+    }
+}
+
+fn handle_set_level(request: &JsonRpcRequest, peer: &Peer) -> JsonRpcResponse {
+    match request
+        .params
+        .clone()
+        .map(serde_json::from_value::<SetLevelParams>)
+    {
+        Some(Ok(params)) => {
+            peer.set_log_level(params.level);
+            JsonRpcResponse::success(request.id.clone(), json!({}))
+        }
+        Some(Err(e)) => {
+            JsonRpcResponse::error(request.id.clone(), -32602, format!("Invalid params: {}", e))
+        }
+        None => JsonRpcResponse::error(request.id.clone(), -32602, "Missing params"),
+    }
+}
+
+/// Keep the first `limit` pull requests of a view, dropping groups left empty
+fn truncate_view(view: &mut Value, limit: usize) {
+    let Some(groups) = view.get_mut("groups").and_then(Value::as_array_mut) else {
+        return;
+    };
+    let mut remaining = limit;
+    groups.retain_mut(|group| {
+        let Some(prs) = group.get_mut("pull_requests").and_then(Value::as_array_mut) else {
+            return false;
         };
+        prs.truncate(remaining);
+        remaining -= prs.len();
+        let count = prs.len();
+        group["count"] = json!(count);
+        count > 0
+    });
+}
 
-        match self.ipc_client.screenshot(&output_dir).await {
-            Ok(result) => CallToolResult::success(result),
-            Err(e) => CallToolResult::error(format!("Screenshot failed: {}", e)),
+#[cfg(test)]
+impl McpServer {
+    /// A server whose app connection has gone stale, for exercising request handling
+    pub(super) fn unconnected() -> Self {
+        Self {
+            backend: Backend::stale(),
+            ..Self::default()
         }
     }
+
+    /// Handle `request` for an initialized client whose notifications are discarded
+    pub(super) async fn handle_request(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let peer = Peer::new(tx);
+        peer.set_protocol_version(SUPPORTED_PROTOCOL_VERSIONS[0]);
+        self.dispatch(request, &peer).await
+    }
+}
+
+/// No app to reach and no GitHub credentials, so every data request fails offline
+#[cfg(test)]
+pub(super) fn offline_env() -> ghview_core::test_support::IsolatedEnv {
+    let env = ghview_core::test_support::IsolatedEnv::new();
+    env.set("GHVIEW_IPC_TRANSPORT", "tcp");
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    fn call_request(name: &str, arguments: Value) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "tools/call".to_string(),
+            params: Some(json!({ "name": name, "arguments": arguments })),
+        }
+    }
+
+    async fn call_error_text(name: &str, arguments: Value) -> String {
+        let response = McpServer::unconnected()
+            .handle_request(&call_request(name, arguments))
+            .await;
+        let result = response.result.unwrap();
+        assert_eq!(result["isError"], json!(true));
+        result["content"][0]["text"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_list_tools_declares_pr_tools() {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "tools/list".to_string(),
+            params: None,
+        };
+        let response = McpServer::unconnected().handle_request(&request).await;
+        let tools = response.result.unwrap()["tools"].clone();
+        let names: Vec<&str> = tools
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        for name in [
+            "list_review_requests",
+            "get_pull_request",
+            "get_check_status",
+            "refresh",
+            "screenshot",
+        ] {
+            assert!(names.contains(&name), "missing tool {}", name);
+        }
+
+        let get_pr = tools
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|t| t["name"] == "get_pull_request")
+            .unwrap();
+        assert_eq!(
+            get_pr["inputSchema"]["required"],
+            json!(["owner", "repo", "number"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_rejects_invalid_arguments_before_ipc() {
+        assert_eq!(
+            call_error_text(
+                "get_pull_request",
+                json!({"owner": "mirakui", "repo": "ghview"})
+            )
+            .await,
+            "Missing required argument: number"
+        );
+        assert_eq!(
+            call_error_text(
+                "get_check_status",
+                json!({"owner": "", "repo": "ghview", "number": 1})
+            )
+            .await,
+            "Invalid argument: owner must be a bare name"
+        );
+        assert_eq!(
+            call_error_text("list_review_requests", json!({"limit": 0})).await,
+            "Invalid argument: limit must be a positive integer"
+        );
+        assert_eq!(
+            call_error_text("list_review_requests", json!({"sort": "checks"})).await,
+            "Invalid argument: sort must be an array of strings"
+        );
+    }
+
+    #[test]
+    fn test_truncate_view_drops_empty_groups() {
+        let mut view = json!({
+            "groups": [
+                {"key": "a", "count": 2, "pull_requests": [1, 2]},
+                {"key": "b", "count": 2, "pull_requests": [3, 4]},
+                {"key": "c", "count": 1, "pull_requests": [5]}
+            ]
+        });
+        truncate_view(&mut view, 3);
+        assert_eq!(
+            view["groups"],
+            json!([
+                {"key": "a", "count": 2, "pull_requests": [1, 2]},
+                {"key": "b", "count": 1, "pull_requests": [3]}
+            ])
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_call_tool_reports_fetch_failure() {
+        let _env = offline_env();
+        let text = call_error_text(
+            "get_pull_request",
+            json!({"owner": "mirakui", "repo": "ghview", "number": 1}),
+        )
+        .await;
+        assert!(text.starts_with("Failed to get pull request:"));
+    }
+
+    #[tokio::test]
+    async fn test_screenshot_validates_options_before_ipc() {
+        assert_eq!(
+            call_error_text("screenshot", json!({})).await,
+            "Missing required argument: output_dir"
+        );
+        assert!(call_error_text("screenshot", json!({"inline": true}))
+            .await
+            .starts_with("Screenshot failed:"));
+    }
 }
@@ -0,0 +1,509 @@
+//! `ghview <command>`: the review queue from a terminal. Requests go through the
+//! running app over IPC when it answers, and straight to the GitHub API otherwise.
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::ffi::OsString;
+use std::fmt::Write as _;
+use std::io::Read;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use crate::ipc::protocol::PullRequestParams;
+use ghview_core::auth::{
+    check_auth_status, login_with_token, run_device_flow, AuthStatus, DeviceFlowResult,
+};
+use ghview_core::auth_provider::AuthSource;
+use ghview_core::config;
+use ghview_core::export::{check_emoji, check_label, format_wait};
+use ghview_core::github::fetch_pull_request_details;
+use ghview_core::ipc::IpcClient;
+use ghview_core::models::{PullRequestDetails, PullRequestWithChecks, SlaStatus};
+use ghview_core::view::{pr_view, GroupBy, PullRequestView, SortKey, ViewOptions};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "ghview",
+    version,
+    about = "Pull requests waiting for your review"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List pull requests waiting for your review, after filter rules
+    List {
+        /// Print the grouped list as JSON
+        #[arg(long)]
+        json: bool,
+        /// none, repository, check_state, review_status or age
+        #[arg(long, value_parser = parse_group_by)]
+        group_by: Option<GroupBy>,
+        /// Comma-separated sort keys, e.g. `checks,-updated`
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+        sort: Option<Vec<SortKey>>,
+    },
+    /// Show a pull request with its checks and changed files
+    Show {
+        /// `owner/repo#123` or a pull request URL
+        pull_request: PullRequestRef,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Open a pull request, or else your review requests, in the browser
+    Open {
+        /// `owner/repo#123` or a pull request URL
+        pull_request: Option<PullRequestRef>,
+    },
+    /// Log in to GitHub with the device flow
+    Login {
+        /// Read a personal access token from standard input instead
+        #[arg(long)]
+        with_token: bool,
+    },
+    /// Show who is logged in and whether the app is running
+    Status {
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+fn parse_group_by(value: &str) -> Result<GroupBy, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| format!("unknown grouping: {}", value))
+}
+
+/// A pull request named on the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequestRef {
+    pub owner: String,
+    pub repo: String,
+    pub number: i32,
+}
+
+impl FromStr for PullRequestRef {
+    type Err = String;
+
+    /// `owner/repo#123`, or a URL ending in `owner/repo/pull/123`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("expected owner/repo#123 or a pull request URL, got {}", s);
+        let (full_name, number) = match s.split_once('#') {
+            Some((full_name, number)) => (full_name.to_string(), number),
+            None => {
+                let parts: Vec<&str> = s.trim_end_matches('/').rsplitn(4, '/').collect();
+                match parts.as_slice() {
+                    [number, "pull", repo, rest] => {
+                        let owner = rest.rsplit('/').next().unwrap_or_default();
+                        (format!("{}/{}", owner, repo), *number)
+                    }
+                    _ => return Err(invalid()),
+                }
+            }
+        };
+        let (owner, repo) = full_name.split_once('/').ok_or_else(invalid)?;
+        let number: i32 = number.parse().map_err(|_| invalid())?;
+        if owner.is_empty() || repo.is_empty() || repo.contains('/') || number <= 0 {
+            return Err(invalid());
+        }
+        Ok(Self {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            number,
+        })
+    }
+}
+
+/// Where requests are answered: the running app, or this process calling GitHub
+enum Backend {
+    App(IpcClient),
+    Direct,
+}
+
+impl Backend {
+    async fn detect() -> Self {
+        match IpcClient::connect().ok() {
+            Some(client) if client.ping().await => Backend::App(client),
+            _ => Backend::Direct,
+        }
+    }
+
+    async fn view(&self, options: ViewOptions) -> Result<PullRequestView> {
+        match self {
+            Backend::App(client) => Ok(client.request("get_pr_view", options).await?),
+            Backend::Direct => Ok(pr_view(options).await?),
+        }
+    }
+
+    async fn details(&self, pr: &PullRequestRef) -> Result<PullRequestDetails> {
+        match self {
+            Backend::App(client) => {
+                let params = PullRequestParams {
+                    owner: pr.owner.clone(),
+                    repo: pr.repo.clone(),
+                    number: pr.number,
+                };
+                Ok(client.request("get_pull_request_details", params).await?)
+            }
+            Backend::Direct => {
+                Ok(fetch_pull_request_details(&pr.owner, &pr.repo, pr.number).await?)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Status {
+    app_running: bool,
+    config_path: Option<String>,
+    auth: AuthStatus,
+}
+
+/// Run the subcommand in `args` (program name first), or return `None` when there is
+/// none and the app should start instead.
+///
+/// Release builds on Windows have no console attached, so run the CLI from a debug
+/// build or through `cargo run` there.
+pub fn run_from_args<I, T>(args: I) -> Option<ExitCode>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+    // macOS passes `-psn_…` to apps launched from the Finder
+    match args.get(1).and_then(|arg| arg.to_str()) {
+        None => return None,
+        Some(arg) if arg.starts_with("-psn_") => return None,
+        Some(_) => {}
+    }
+
+    let cli = Cli::parse_from(args);
+    if let Err(e) = config::init() {
+        eprintln!("warning: using default settings: {}", e);
+    }
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return Some(ExitCode::FAILURE);
+        }
+    };
+    Some(match runtime.block_on(run(cli.command)) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            ExitCode::FAILURE
+        }
+    })
+}
+
+async fn run(command: Command) -> Result<ExitCode> {
+    match command {
+        Command::List {
+            json,
+            group_by,
+            sort,
+        } => {
+            let view = Backend::detect()
+                .await
+                .view(ViewOptions { group_by, sort })
+                .await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&view)?);
+            } else {
+                print!("{}", format_view(&view));
+            }
+        }
+        Command::Show { pull_request, json } => {
+            let details = Backend::detect().await.details(&pull_request).await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&details)?);
+            } else {
+                print!("{}", format_details(&details));
+            }
+        }
+        Command::Open { pull_request } => {
+            let url = web_url(pull_request.as_ref());
+            tauri_plugin_opener::open_url(&url, None::<&str>)
+                .with_context(|| format!("Failed to open {}", url))?;
+            println!("Opened {}", url);
+        }
+        Command::Login { with_token } => return login(with_token).await,
+        Command::Status { json } => {
+            let status = Status {
+                app_running: matches!(Backend::detect().await, Backend::App(_)),
+                config_path: config::config_path()
+                    .ok()
+                    .map(|path| path.display().to_string()),
+                auth: check_auth_status().await?,
+            };
+            if json {
+                println!("{}", serde_json::to_string_pretty(&status)?);
+            } else {
+                print!("{}", format_status(&status));
+            }
+            if !status.auth.authenticated {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn login(with_token: bool) -> Result<ExitCode> {
+    let status = if with_token {
+        let mut token = String::new();
+        std::io::stdin()
+            .read_to_string(&mut token)
+            .context("Failed to read the token from standard input")?;
+        login_with_token(token).await?
+    } else {
+        let result = run_device_flow(
+            |init| {
+                println!("First copy your one-time code: {}", init.user_code);
+                println!("Then open {} and enter it", init.verification_uri);
+            },
+            |_| {},
+        )
+        .await;
+        match result {
+            DeviceFlowResult::Authorized { status } => status,
+            DeviceFlowResult::Expired => anyhow::bail!("The code expired, run login again"),
+            DeviceFlowResult::Denied => anyhow::bail!("Authorization was denied"),
+            DeviceFlowResult::Cancelled => anyhow::bail!("Login cancelled"),
+            DeviceFlowResult::Error { message } => anyhow::bail!(message),
+        }
+    };
+    println!(
+        "Logged in as {}",
+        status.username.as_deref().unwrap_or("unknown user")
+    );
+    if !status.missing_scopes.is_empty() {
+        eprintln!(
+            "warning: token is missing scopes: {}",
+            status.missing_scopes.join(", ")
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The pull request's page, or the review requests page when none is given
+fn web_url(pull_request: Option<&PullRequestRef>) -> String {
+    let settings = config::current();
+    let base = settings.github.oauth_url.trim_end_matches('/');
+    match pull_request {
+        Some(pr) => format!("{}/{}/{}/pull/{}", base, pr.owner, pr.repo, pr.number),
+        None => format!("{}/pulls/review-requested", base),
+    }
+}
+
+fn format_item(pr: &PullRequestWithChecks) -> String {
+    let p = &pr.pull_request;
+    let mut line = format!(
+        "{} {}#{}  {}",
+        check_emoji(pr),
+        p.repository.full_name,
+        p.number,
+        p.title
+    );
+    if p.draft {
+        line.push_str(" (draft)");
+    }
+    let _ = write!(line, "  @{}", p.user.login);
+    if let Some(sla) = &pr.review_sla {
+        let _ = write!(line, ", waiting {}", format_wait(sla.waiting_secs));
+        if sla.status == SlaStatus::Breached {
+            line.push_str(" (overdue)");
+        }
+    }
+    line
+}
+
+fn format_view(view: &PullRequestView) -> String {
+    if view.total == 0 {
+        return "No pull requests waiting for your review.\n".to_string();
+    }
+    let mut out = String::new();
+    for (i, group) in view.groups.iter().enumerate() {
+        if view.group_by != GroupBy::None {
+            if i > 0 {
+                out.push('\n');
+            }
+            let _ = writeln!(out, "{} ({})", group.label, group.count);
+        }
+        for pr in &group.pull_requests {
+            let _ = writeln!(out, "{}", format_item(pr));
+        }
+    }
+    out
+}
+
+fn format_details(details: &PullRequestDetails) -> String {
+    let pr = &details.pull_request;
+    let p = &pr.pull_request;
+    let mut out = String::new();
+    let _ = writeln!(out, "{} #{}", p.title, p.number);
+    let _ = writeln!(
+        out,
+        "{} · {:?}{} · by @{}",
+        p.repository.full_name,
+        p.state,
+        if p.draft { " (draft)" } else { "" },
+        p.user.login
+    );
+    let _ = writeln!(out, "{}", p.html_url);
+    match &pr.check_status {
+        Some(checks) => {
+            let _ = writeln!(
+                out,
+                "Checks: {} {} ({})",
+                check_emoji(pr),
+                check_label(pr),
+                checks.total_count
+            );
+        }
+        None => out.push_str("Checks: none\n"),
+    }
+    let _ = writeln!(
+        out,
+        "+{} -{} in {} files",
+        details.additions, details.deletions, details.changed_files
+    );
+    for file in &details.files {
+        let _ = writeln!(
+            out,
+            "  {:<8} {} (+{} -{})",
+            file.status, file.filename, file.additions, file.deletions
+        );
+    }
+    if let Some(body) = details.body.as_deref().filter(|b| !b.trim().is_empty()) {
+        let _ = write!(out, "\n{}\n", body.trim_end());
+    }
+    out
+}
+
+fn format_status(status: &Status) -> String {
+    let auth = &status.auth;
+    let mut out = String::new();
+    match (&auth.username, auth.authenticated) {
+        (Some(username), true) => {
+            let _ = write!(out, "Logged in as {}", username);
+            match auth.source {
+                Some(AuthSource::Environment) => out.push_str(" (token from environment)"),
+                Some(AuthSource::Stored) => out.push_str(" (stored token)"),
+                Some(AuthSource::GhCli) => out.push_str(" (gh CLI token)"),
+                None => {}
+            }
+            out.push('\n');
+        }
+        _ => out.push_str("Not logged in; run `ghview login`\n"),
+    }
+    if !auth.missing_scopes.is_empty() {
+        let _ = writeln!(out, "Missing scopes: {}", auth.missing_scopes.join(", "));
+    }
+    let _ = writeln!(
+        out,
+        "App: {}",
+        if status.app_running {
+            "running"
+        } else {
+            "not running"
+        }
+    );
+    if let Some(path) = &status.config_path {
+        let _ = writeln!(out, "Config: {}", path);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ghview_core::test_support::FakeGitHubEnv;
+    use ghview_fake_github::{FakePull, Scenario};
+    use serial_test::serial;
+
+    fn pr_ref(owner: &str, repo: &str, number: i32) -> PullRequestRef {
+        PullRequestRef {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            number,
+        }
+    }
+
+    #[test]
+    fn test_parse_pull_request_ref() {
+        assert_eq!(
+            "octo-org/api#12".parse::<PullRequestRef>(),
+            Ok(pr_ref("octo-org", "api", 12))
+        );
+        assert_eq!(
+            "https://github.com/octo-org/api/pull/12/".parse::<PullRequestRef>(),
+            Ok(pr_ref("octo-org", "api", 12))
+        );
+        for invalid in ["octo-org/api", "api#12", "octo-org/api#x", "a/b/c#1", "#1"] {
+            assert!(invalid.parse::<PullRequestRef>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_run_from_args_without_command_starts_app() {
+        assert!(run_from_args(["ghview"]).is_none());
+        assert!(run_from_args(["ghview", "-psn_0_12345"]).is_none());
+    }
+
+    #[test]
+    fn test_parse_list_arguments() {
+        let cli = Cli::try_parse_from([
+            "ghview",
+            "list",
+            "--group-by",
+            "check_state",
+            "--sort",
+            "checks,-updated",
+        ])
+        .unwrap();
+        let Command::List { group_by, sort, .. } = cli.command else {
+            panic!("expected list");
+        };
+        assert_eq!(group_by, Some(GroupBy::CheckState));
+        assert_eq!(
+            sort,
+            Some(vec!["checks".parse().unwrap(), "-updated".parse().unwrap()])
+        );
+        assert!(Cli::try_parse_from(["ghview", "list", "--group-by", "size"]).is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_direct_backend_against_fake_server() {
+        let scenario = Scenario::new()
+            .token("gho_test", "octocat", &["repo"])
+            .pull(FakePull::new("octo-org/api", 1, "Add endpoint").review_requested())
+            .pull(FakePull::new("octo-org/web", 2, "Fix layout").review_requested());
+        let _fake = FakeGitHubEnv::start(scenario)
+            .await
+            .with_env_token("gho_test");
+
+        let view = Backend::Direct
+            .view(ViewOptions {
+                group_by: Some(GroupBy::Repository),
+                sort: None,
+            })
+            .await
+            .unwrap();
+        let text = format_view(&view);
+        assert!(text.starts_with("octo-org/api (1)\n"), "{}", text);
+        assert!(text.contains("octo-org/web#2  Fix layout  @"), "{}", text);
+
+        let details = Backend::Direct
+            .details(&pr_ref("octo-org", "api", 1))
+            .await
+            .unwrap();
+        assert!(format_details(&details).starts_with("Add endpoint #1\n"));
+    }
+}
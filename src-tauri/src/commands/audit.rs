@@ -0,0 +1,7 @@
+use ghview_core::audit::{self, AuditEntry, AuditError};
+
+/// Actions taken on GitHub through ghview, newest first
+#[tauri::command]
+pub fn get_audit_log(limit: Option<usize>) -> Result<Vec<AuditEntry>, AuditError> {
+    audit::read_entries(limit)
+}
@@ -1,224 +1,61 @@
-use serde::{Deserialize, Serialize};
-use thiserror::Error;
+use ghview_core::auth::{
+    self, complete_device_flow, request_device_code, AuthError, AuthStatus, DeviceFlowInit,
+};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
 
-use super::credential::{delete_credentials, load_credentials, save_credentials, Credentials};
-use super::debug::{log_request, log_response, log_response_error};
-
-// GitHub OAuth App credentials for ghview
-// Device flow doesn't require a client secret
-const GITHUB_CLIENT_ID: &str = "Iv23li78KgNyGR5C061j";
-
-#[derive(Debug, Error)]
-pub enum AuthError {
-    #[error("Credential error: {0}")]
-    Credential(String),
-    #[error("Network error: {0}")]
-    Network(#[from] reqwest::Error),
-    #[error("OAuth error: {0}")]
-    OAuth(String),
-    #[error("Not authenticated")]
-    NotAuthenticated,
-}
-
-impl Serialize for AuthError {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(&self.to_string())
-    }
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AuthStatus {
-    pub authenticated: bool,
-    pub username: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct DeviceCodeResponse {
-    device_code: String,
-    user_code: String,
-    verification_uri: String,
-    expires_in: u32,
-    interval: u32,
-}
-
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct AccessTokenResponse {
-    access_token: Option<String>,
-    token_type: Option<String>,
-    scope: Option<String>,
-    error: Option<String>,
-    error_description: Option<String>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct DeviceFlowInit {
-    pub user_code: String,
-    pub verification_uri: String,
-    pub device_code: String,
-    pub expires_in: u32,
-    pub interval: u32,
-}
-
-#[derive(Debug, Deserialize)]
-struct GitHubUser {
-    login: String,
-}
+/// Cancels the running device flow when its sender is used or dropped
+#[derive(Default)]
+pub struct DeviceFlowState(Mutex<Option<oneshot::Sender<()>>>);
 
 #[tauri::command]
 pub async fn check_auth_status() -> Result<AuthStatus, AuthError> {
-    match load_credentials() {
-        Ok(credentials) => {
-            // Verify token by fetching user info
-            let client = reqwest::Client::new();
-            let url = "https://api.github.com/user";
-            log_request("GET", url);
-            let response = client
-                .get(url)
-                .header(
-                    "Authorization",
-                    format!("Bearer {}", credentials.access_token),
-                )
-                .header("User-Agent", "ghview")
-                .header("Accept", "application/vnd.github+json")
-                .send()
-                .await?;
-            log_response(url, response.status());
-
-            if response.status().is_success() {
-                let user: GitHubUser = response.json().await?;
-                Ok(AuthStatus {
-                    authenticated: true,
-                    username: Some(user.login),
-                })
-            } else {
-                // Token is invalid, remove it
-                let _ = delete_credentials();
-                Ok(AuthStatus {
-                    authenticated: false,
-                    username: None,
-                })
-            }
-        }
-        Err(_) => Ok(AuthStatus {
-            authenticated: false,
-            username: None,
-        }),
-    }
+    auth::check_auth_status().await
 }
 
+/// Log in with a pasted personal access token after checking it against `/user`
 #[tauri::command]
-pub async fn start_device_flow() -> Result<DeviceFlowInit, AuthError> {
-    let client = reqwest::Client::new();
-    let url = "https://github.com/login/device/code";
-
-    log_request("POST", url);
-    let response = client
-        .post(url)
-        .header("Accept", "application/json")
-        .form(&[("client_id", GITHUB_CLIENT_ID), ("scope", "repo")])
-        .send()
-        .await?;
-    log_response(url, response.status());
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response.text().await.unwrap_or_default();
-        log_response_error(url, status, &error_text);
-        return Err(AuthError::OAuth(format!(
-            "Failed to start device flow: {}",
-            error_text
-        )));
-    }
-
-    let device_code_response: DeviceCodeResponse = response.json().await?;
-
-    Ok(DeviceFlowInit {
-        user_code: device_code_response.user_code,
-        verification_uri: device_code_response.verification_uri,
-        device_code: device_code_response.device_code,
-        expires_in: device_code_response.expires_in,
-        interval: device_code_response.interval,
-    })
+pub async fn login_with_token(token: String) -> Result<AuthStatus, AuthError> {
+    auth::login_with_token(token).await
 }
 
+/// Request a device code and poll for the token in the background
+///
+/// Emits `device-flow-progress` after each pending poll and `device-flow-complete`
+/// with a `DeviceFlowResult` when the flow ends. Starting a new flow cancels the
+/// previous one.
 #[tauri::command]
-pub async fn poll_device_flow(device_code: String) -> Result<AuthStatus, AuthError> {
-    let client = reqwest::Client::new();
-    let url = "https://github.com/login/oauth/access_token";
+pub async fn start_device_flow(
+    app: AppHandle,
+    state: tauri::State<'_, DeviceFlowState>,
+) -> Result<DeviceFlowInit, AuthError> {
+    let init = request_device_code().await?;
 
-    log_request("POST", url);
-    let response = client
-        .post(url)
-        .header("Accept", "application/json")
-        .form(&[
-            ("client_id", GITHUB_CLIENT_ID),
-            ("device_code", &device_code),
-            ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
-        ])
-        .send()
-        .await?;
-    log_response(url, response.status());
+    let (cancel_tx, cancel_rx) = oneshot::channel();
+    *state.0.lock().unwrap() = Some(cancel_tx);
 
-    let token_response: AccessTokenResponse = response.json().await?;
+    let flow = init.clone();
+    tauri::async_runtime::spawn(async move {
+        let progress_app = app.clone();
+        let result = complete_device_flow(&flow, cancel_rx, move |progress| {
+            let _ = progress_app.emit("device-flow-progress", progress);
+        })
+        .await;
+        let _ = app.emit("device-flow-complete", result);
+    });
 
-    if let Some(error) = token_response.error {
-        if error == "authorization_pending" {
-            return Ok(AuthStatus {
-                authenticated: false,
-                username: None,
-            });
-        }
-        return Err(AuthError::OAuth(
-            token_response
-                .error_description
-                .unwrap_or(error.to_string()),
-        ));
-    }
-
-    if let Some(access_token) = token_response.access_token {
-        // Store the token
-        let credentials = Credentials {
-            access_token: access_token.clone(),
-        };
-        save_credentials(&credentials).map_err(|e| AuthError::Credential(e.to_string()))?;
-
-        // Get username
-        let user_url = "https://api.github.com/user";
-        log_request("GET", user_url);
-        let user_response = client
-            .get(user_url)
-            .header("Authorization", format!("Bearer {}", access_token))
-            .header("User-Agent", "ghview")
-            .header("Accept", "application/vnd.github+json")
-            .send()
-            .await?;
-        log_response(user_url, user_response.status());
+    Ok(init)
+}
 
-        if user_response.status().is_success() {
-            let user: GitHubUser = user_response.json().await?;
-            return Ok(AuthStatus {
-                authenticated: true,
-                username: Some(user.login),
-            });
-        }
+#[tauri::command]
+pub fn cancel_device_flow(state: tauri::State<'_, DeviceFlowState>) {
+    if let Some(cancel) = state.0.lock().unwrap().take() {
+        let _ = cancel.send(());
     }
-
-    Err(AuthError::OAuth(
-        "Failed to obtain access token".to_string(),
-    ))
 }
 
 #[tauri::command]
 pub async fn logout() -> Result<(), AuthError> {
-    delete_credentials().map_err(|e| AuthError::Credential(e.to_string()))?;
-    Ok(())
-}
-
-pub fn get_stored_token() -> Result<String, AuthError> {
-    let credentials = load_credentials().map_err(|_| AuthError::NotAuthenticated)?;
-    Ok(credentials.access_token)
+    auth::logout().await
 }
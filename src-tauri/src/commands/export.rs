@@ -0,0 +1,31 @@
+use ghview_core::config;
+use ghview_core::export::{render, ExportError, ExportFormat};
+use ghview_core::github::review_requested_prs;
+use ghview_core::view::build_view;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Render the filtered review queue in `[view]` order, optionally copying it to the clipboard
+#[tauri::command]
+pub async fn export_pull_requests(
+    app: AppHandle,
+    format: ExportFormat,
+    copy_to_clipboard: Option<bool>,
+) -> Result<String, ExportError> {
+    let prs = review_requested_prs().await?;
+    let settings = config::current();
+    let view = build_view(
+        prs,
+        settings.view.group_by,
+        &settings.view.sort,
+        chrono::Utc::now(),
+    );
+    let content = render(format, &view)?;
+
+    if copy_to_clipboard.unwrap_or(false) {
+        app.clipboard()
+            .write_text(content.clone())
+            .map_err(|e| ExportError::Clipboard(e.to_string()))?;
+    }
+    Ok(content)
+}
@@ -0,0 +1,8 @@
+use ghview_core::filters::{self, FilterPreview, FilterRule};
+use ghview_core::github::GitHubError;
+
+/// Show what `rules` (or the configured rules) would do to the current PR list
+#[tauri::command]
+pub async fn preview_filters(rules: Option<Vec<FilterRule>>) -> Result<FilterPreview, GitHubError> {
+    filters::preview_filters(rules).await
+}
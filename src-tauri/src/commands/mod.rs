@@ -1,4 +1,10 @@
+//! Tauri commands; each is a thin wrapper around `ghview_core`
+
+pub mod audit;
 pub mod auth;
-pub mod credential;
-pub mod debug;
+pub mod export;
+pub mod filters;
 pub mod github;
+pub mod sla;
+pub mod stats;
+pub mod view;
@@ -0,0 +1,8 @@
+use ghview_core::github::GitHubError;
+use ghview_core::sla::{self, SlaSummary};
+
+/// Review SLA counts over the (filtered) PR list, overall and per repository
+#[tauri::command]
+pub async fn fetch_sla_summary() -> Result<SlaSummary, GitHubError> {
+    sla::fetch_sla_summary().await
+}
@@ -0,0 +1,19 @@
+use ghview_core::stats::{self, ReviewStats, StatsError, StatsFormat};
+
+#[tauri::command]
+pub async fn get_review_stats(
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<ReviewStats, StatsError> {
+    stats::get_review_stats(from, to).await
+}
+
+/// Write review stats for the range to `exports/` in the config directory and return the path
+#[tauri::command]
+pub async fn export_review_stats(
+    from: Option<String>,
+    to: Option<String>,
+    format: StatsFormat,
+) -> Result<String, StatsError> {
+    stats::export_review_stats(from, to, format).await
+}
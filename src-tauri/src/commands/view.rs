@@ -0,0 +1,8 @@
+use ghview_core::github::GitHubError;
+use ghview_core::view::{pr_view, PullRequestView, ViewOptions};
+
+/// The filtered PR list, grouped and sorted by `options` or else `[view]` in config.toml
+#[tauri::command]
+pub async fn fetch_pr_view(options: Option<ViewOptions>) -> Result<PullRequestView, GitHubError> {
+    pr_view(options.unwrap_or_default()).await
+}
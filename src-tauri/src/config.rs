@@ -0,0 +1,69 @@
+use ghview_core::config::{
+    config_path, current, load_from, save_to, set_current, ConfigError, Settings, CONFIG_VERSION,
+};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+/// Emitted with the new `Settings` whenever they change
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+/// Emitted with an error message when an edited config file can't be applied
+pub const SETTINGS_ERROR_EVENT: &str = "settings-error";
+
+/// How often the config file is checked for changes
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Reload the config file whenever it changes and tell the UI
+pub fn watch(app: AppHandle) {
+    let Ok(path) = config_path() else {
+        return;
+    };
+    tauri::async_runtime::spawn(async move {
+        let mut last_modified = modified(&path);
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+            let now_modified = modified(&path);
+            if now_modified == last_modified {
+                continue;
+            }
+            last_modified = now_modified;
+
+            match load_from(&path) {
+                Ok(settings) if settings != *current() => {
+                    tracing::info!("Reloaded {}", path.display());
+                    set_current(settings.clone());
+                    let _ = app.emit(SETTINGS_CHANGED_EVENT, &settings);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!("Ignoring config change: {}", e);
+                    let _ = app.emit(SETTINGS_ERROR_EVENT, e.to_string());
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_settings() -> Settings {
+    (*current()).clone()
+}
+
+/// Validate and save `settings`, then apply them
+#[tauri::command]
+pub fn update_settings(app: AppHandle, settings: Settings) -> Result<Settings, ConfigError> {
+    let settings = Settings {
+        version: CONFIG_VERSION,
+        ..settings
+    };
+    settings.validate()?;
+    save_to(&config_path()?, &settings)?;
+    set_current(settings.clone());
+    let _ = app.emit(SETTINGS_CHANGED_EVENT, &settings);
+    Ok(settings)
+}
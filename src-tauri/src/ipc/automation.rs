@@ -0,0 +1,282 @@
+use crate::ipc::protocol::{
+    NavigateParams, PullRequestParams, SetThemeParams, UiTheme, WaitForParams, WaitForResult,
+    WindowSize,
+};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, LogicalSize, Manager, Theme, WebviewWindow};
+use tokio::sync::oneshot;
+
+/// Views the window can be asked to show with `navigate`
+pub const UI_VIEWS: [&str; 2] = ["pull_requests", "account"];
+
+/// Bounds accepted by `resize_window`, in logical pixels
+const MIN_WINDOW_SIZE: u32 = 200;
+const MAX_WINDOW_SIZE: u32 = 8192;
+
+const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Condition checks waiting for the webview to call `report_ui_condition`
+#[derive(Default)]
+pub struct ConditionRequests {
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<u64, oneshot::Sender<ConditionReport>>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConditionReport {
+    satisfied: bool,
+    /// Set when the check threw, e.g. on an invalid selector
+    error: Option<String>,
+}
+
+#[tauri::command]
+pub fn report_ui_condition(
+    requests: tauri::State<'_, ConditionRequests>,
+    request_id: u64,
+    satisfied: bool,
+    error: Option<String>,
+) {
+    if let Some(tx) = requests.pending.lock().unwrap().remove(&request_id) {
+        let _ = tx.send(ConditionReport { satisfied, error });
+    }
+}
+
+fn main_window(app: &AppHandle) -> Result<WebviewWindow> {
+    app.get_webview_window("main")
+        .context("Main window not found")
+}
+
+fn validate_view(view: &str) -> Result<()> {
+    if !UI_VIEWS.contains(&view) {
+        anyhow::bail!(
+            "Unknown view: {} (expected one of {})",
+            view,
+            UI_VIEWS.join(", ")
+        );
+    }
+    Ok(())
+}
+
+fn validate_size(size: &WindowSize) -> Result<()> {
+    let range = MIN_WINDOW_SIZE..=MAX_WINDOW_SIZE;
+    if !range.contains(&size.width) || !range.contains(&size.height) {
+        anyhow::bail!(
+            "Window size {}x{} is outside {}..={} pixels",
+            size.width,
+            size.height,
+            MIN_WINDOW_SIZE,
+            MAX_WINDOW_SIZE
+        );
+    }
+    Ok(())
+}
+
+/// Ask the frontend to bring `view` into sight
+pub fn navigate(app: &AppHandle, params: &NavigateParams) -> Result<()> {
+    validate_view(&params.view)?;
+    app.emit("ui-navigate", &params.view)
+        .context("Failed to emit ui-navigate event")
+}
+
+/// Ask the frontend to highlight a pull request and scroll to it
+pub fn select_pr(app: &AppHandle, params: &PullRequestParams) -> Result<()> {
+    app.emit("ui-select-pr", params)
+        .context("Failed to emit ui-select-pr event")
+}
+
+/// Resize the main window's content area; returns the size it ended up with
+pub fn resize_window(app: &AppHandle, size: &WindowSize) -> Result<WindowSize> {
+    validate_size(size)?;
+    let window = main_window(app)?;
+    window
+        .set_size(LogicalSize::new(size.width, size.height))
+        .context("Failed to resize window")?;
+
+    let scale = window.scale_factor()?;
+    let inner = window.inner_size()?.to_logical::<f64>(scale);
+    Ok(WindowSize {
+        width: inner.width.round() as u32,
+        height: inner.height.round() as u32,
+    })
+}
+
+/// Force light or dark mode, which the stylesheet picks up through
+/// `prefers-color-scheme`, or go back to following the system
+pub fn set_theme(app: &AppHandle, params: &SetThemeParams) -> Result<()> {
+    let theme = match params.theme {
+        UiTheme::Light => Some(Theme::Light),
+        UiTheme::Dark => Some(Theme::Dark),
+        UiTheme::System => None,
+    };
+    main_window(app)?
+        .set_theme(theme)
+        .context("Failed to set theme")
+}
+
+/// Poll the condition in `params` until it holds, returning how long that took
+pub async fn wait_for(app: &AppHandle, params: &WaitForParams) -> Result<WaitForResult> {
+    let timeout = wait_timeout(params)?;
+    let window = main_window(app)?;
+    let requests = app.state::<ConditionRequests>();
+    let started = Instant::now();
+
+    loop {
+        let request_id = requests.next_id.fetch_add(1, Ordering::Relaxed);
+        let script = condition_script(params, request_id)?;
+        let (tx, rx) = oneshot::channel();
+        requests.pending.lock().unwrap().insert(request_id, tx);
+
+        if let Err(e) = window.eval(&script) {
+            requests.pending.lock().unwrap().remove(&request_id);
+            return Err(e).context("Failed to evaluate condition");
+        }
+
+        // A page that is still loading may never answer; keep polling until the deadline
+        let remaining = timeout.saturating_sub(started.elapsed());
+        let report = tokio::time::timeout(remaining, rx).await;
+        requests.pending.lock().unwrap().remove(&request_id);
+        if let Ok(Ok(report)) = report {
+            if let Some(error) = report.error {
+                anyhow::bail!("Condition failed: {}", error);
+            }
+            if report.satisfied {
+                return Ok(WaitForResult {
+                    elapsed_ms: started.elapsed().as_millis() as u64,
+                });
+            }
+        }
+
+        if started.elapsed() + POLL_INTERVAL >= timeout {
+            anyhow::bail!(
+                "Timed out after {} ms waiting for {}",
+                timeout.as_millis(),
+                describe_condition(params)
+            );
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+fn wait_timeout(params: &WaitForParams) -> Result<Duration> {
+    if params.selector.is_none() && params.text.is_none() {
+        anyhow::bail!("Specify a selector, text or both");
+    }
+    match params.timeout_ms.map(Duration::from_millis) {
+        Some(timeout) if timeout.is_zero() || timeout > MAX_WAIT_TIMEOUT => anyhow::bail!(
+            "timeout_ms must be between 1 and {}",
+            MAX_WAIT_TIMEOUT.as_millis()
+        ),
+        Some(timeout) => Ok(timeout),
+        None => Ok(DEFAULT_WAIT_TIMEOUT),
+    }
+}
+
+fn describe_condition(params: &WaitForParams) -> String {
+    let mut parts = Vec::new();
+    if let Some(selector) = &params.selector {
+        parts.push(format!("selector {:?}", selector));
+    }
+    if let Some(text) = &params.text {
+        parts.push(format!("text {:?}", text));
+    }
+    parts.join(" and ")
+}
+
+/// Script that checks the condition once and reports back through `report_ui_condition`
+fn condition_script(params: &WaitForParams, request_id: u64) -> Result<String> {
+    // JSON string encoding doubles as JavaScript string escaping; null means "not given"
+    let selector = serde_json::to_string(&params.selector)?;
+    let text = serde_json::to_string(&params.text)?;
+    Ok(format!(
+        "(() => {{\
+           const report = (satisfied, error) => window.__TAURI_INTERNALS__.invoke(\
+             'report_ui_condition', {{ requestId: {id}, satisfied, error }});\
+           const selector = {selector}, text = {text};\
+           try {{\
+             const el = selector === null ? document.body : document.querySelector(selector);\
+             report(!!el && (text === null || (el.textContent || '').includes(text)), null);\
+           }} catch (e) {{\
+             report(false, String(e));\
+           }}\
+         }})()",
+        id = request_id,
+        selector = selector,
+        text = text
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_view() {
+        assert!(validate_view("pull_requests").is_ok());
+        assert!(validate_view("account").is_ok());
+        let error = validate_view("settings").unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "Unknown view: settings (expected one of pull_requests, account)"
+        );
+    }
+
+    #[test]
+    fn test_validate_size() {
+        let size = |width, height| WindowSize { width, height };
+        assert!(validate_size(&size(1280, 800)).is_ok());
+        assert!(validate_size(&size(MIN_WINDOW_SIZE, MAX_WINDOW_SIZE)).is_ok());
+        assert!(validate_size(&size(100, 800)).is_err());
+        assert!(validate_size(&size(1280, 10_000)).is_err());
+    }
+
+    #[test]
+    fn test_wait_timeout() {
+        let params = |timeout_ms| WaitForParams {
+            selector: Some(".pr-card".to_string()),
+            timeout_ms,
+            ..WaitForParams::default()
+        };
+        assert_eq!(wait_timeout(&params(None)).unwrap(), DEFAULT_WAIT_TIMEOUT);
+        assert_eq!(
+            wait_timeout(&params(Some(500))).unwrap(),
+            Duration::from_millis(500)
+        );
+        assert!(wait_timeout(&params(Some(0))).is_err());
+        assert!(wait_timeout(&params(Some(120_000))).is_err());
+        assert!(wait_timeout(&WaitForParams::default()).is_err());
+    }
+
+    #[test]
+    fn test_condition_script_escapes_arguments() {
+        let params = WaitForParams {
+            selector: Some(r#"[data-title="it's"]"#.to_string()),
+            text: Some("3 pull requests".to_string()),
+            ..WaitForParams::default()
+        };
+        let script = condition_script(&params, 7).unwrap();
+        assert!(script.contains("requestId: 7"));
+        assert!(script.contains(r#"selector = "[data-title=\"it's\"]""#));
+        assert!(script.contains(r#"text = "3 pull requests""#));
+        assert!(!script.contains("eval"));
+    }
+
+    #[test]
+    fn test_describe_condition() {
+        let params = WaitForParams {
+            selector: Some(".pr-card".to_string()),
+            text: Some("Ready".to_string()),
+            ..WaitForParams::default()
+        };
+        assert_eq!(
+            describe_condition(&params),
+            r#"selector ".pr-card" and text "Ready""#
+        );
+    }
+}
@@ -1,4 +1,6 @@
+pub mod automation;
 pub mod protocol;
+pub mod screenshot;
 pub mod server;
 
 pub use server::start_ipc_server;
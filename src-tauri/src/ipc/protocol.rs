@@ -1,43 +1,97 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct IpcRequest {
-    pub method: String,
-    pub params: serde_json::Value,
+pub struct ScreenshotParams {
+    pub output_dir: String,
+    /// Downscale (preserving aspect ratio) so the image fits within these bounds
+    #[serde(default)]
+    pub max_width: Option<u32>,
+    #[serde(default)]
+    pub max_height: Option<u32>,
+    /// Crop to this area of the window, in logical pixels
+    #[serde(default)]
+    pub region: Option<ScreenshotRegion>,
+    /// Crop to the element matching this CSS selector
+    #[serde(default)]
+    pub element: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenshotRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct IpcResponse {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub result: Option<serde_json::Value>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
+pub struct ScreenshotResult {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
 }
 
-impl IpcResponse {
-    pub fn success(result: serde_json::Value) -> Self {
-        Self {
-            result: Some(result),
-            error: None,
-        }
-    }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestParams {
+    pub owner: String,
+    pub repo: String,
+    pub number: i32,
+}
 
-    pub fn error(message: impl Into<String>) -> Self {
-        Self {
-            result: None,
-            error: Some(message.into()),
-        }
-    }
+/// Inclusive `YYYY-MM-DD` bounds; both default as in the `get_review_stats` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewStatsParams {
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
 }
 
+/// A part of the window to bring into view, e.g. `pull_requests`
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ScreenshotParams {
-    pub output_dir: String,
+pub struct NavigateParams {
+    pub view: String,
+}
+
+/// Inner size of the window in logical pixels
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UiTheme {
+    Light,
+    Dark,
+    /// Follow the operating system
+    System,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ScreenshotResult {
-    pub path: String,
+pub struct SetThemeParams {
+    pub theme: UiTheme,
+}
+
+/// A condition on the page, polled until it holds; every given part must be true.
+/// Only declarative checks are accepted, never script to run in the webview.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WaitForParams {
+    /// CSS selector that must match an element
+    #[serde(default)]
+    pub selector: Option<String>,
+    /// Text the matched element (or the whole page) must contain
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaitForResult {
+    pub elapsed_ms: u64,
 }
 
 #[cfg(test)]
@@ -45,37 +99,60 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_ipc_request_serialization() {
-        let request = IpcRequest {
-            method: "screenshot".to_string(),
-            params: serde_json::json!({"output_dir": "/tmp"}),
-        };
-        let json = serde_json::to_string(&request).unwrap();
-        let parsed: IpcRequest = serde_json::from_str(&json).unwrap();
-        assert_eq!(parsed.method, "screenshot");
+    fn test_screenshot_params_deserialization() {
+        let json = r#"{"output_dir": "/tmp/screenshots"}"#;
+        let params: ScreenshotParams = serde_json::from_str(json).unwrap();
+        assert_eq!(params.output_dir, "/tmp/screenshots");
+        assert!(params.region.is_none());
+        assert!(params.element.is_none());
     }
 
     #[test]
-    fn test_ipc_response_success() {
-        let response = IpcResponse::success(serde_json::json!({"path": "/tmp/screenshot.png"}));
-        assert!(response.result.is_some());
-        assert!(response.error.is_none());
+    fn test_screenshot_params_with_options() {
+        let json = r##"{
+            "output_dir": "/tmp",
+            "max_width": 640,
+            "region": {"x": 10, "y": 20, "width": 300, "height": 200},
+            "element": "#pr-list"
+        }"##;
+        let params: ScreenshotParams = serde_json::from_str(json).unwrap();
+        assert_eq!(params.max_width, Some(640));
+        assert_eq!(params.max_height, None);
+        assert_eq!(
+            params.region,
+            Some(ScreenshotRegion {
+                x: 10,
+                y: 20,
+                width: 300,
+                height: 200
+            })
+        );
+        assert_eq!(params.element, Some("#pr-list".to_string()));
     }
 
     #[test]
-    fn test_ipc_response_error() {
-        let response = IpcResponse::error("Failed to capture screenshot");
-        assert!(response.result.is_none());
-        assert_eq!(
-            response.error,
-            Some("Failed to capture screenshot".to_string())
-        );
+    fn test_pull_request_params_deserialization() {
+        let json = r#"{"owner": "mirakui", "repo": "ghview", "number": 42}"#;
+        let params: PullRequestParams = serde_json::from_str(json).unwrap();
+        assert_eq!(params.owner, "mirakui");
+        assert_eq!(params.repo, "ghview");
+        assert_eq!(params.number, 42);
     }
 
     #[test]
-    fn test_screenshot_params_deserialization() {
-        let json = r#"{"output_dir": "/tmp/screenshots"}"#;
-        let params: ScreenshotParams = serde_json::from_str(json).unwrap();
-        assert_eq!(params.output_dir, "/tmp/screenshots");
+    fn test_set_theme_params_deserialization() {
+        let params: SetThemeParams = serde_json::from_str(r#"{"theme": "dark"}"#).unwrap();
+        assert_eq!(params.theme, UiTheme::Dark);
+        assert!(serde_json::from_str::<SetThemeParams>(r#"{"theme": "sepia"}"#).is_err());
+    }
+
+    #[test]
+    fn test_wait_for_params_deserialization() {
+        let json = r#"{"selector": ".pr-card", "timeout_ms": 2000}"#;
+        let params: WaitForParams = serde_json::from_str(json).unwrap();
+        assert_eq!(params.selector.as_deref(), Some(".pr-card"));
+        assert_eq!(params.text, None);
+        assert_eq!(params.timeout_ms, Some(2000));
+        assert!(serde_json::from_str::<WaitForParams>(r#"{"expression": "true"}"#).is_err());
     }
 }
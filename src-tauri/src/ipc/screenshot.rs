@@ -0,0 +1,328 @@
+use crate::ipc::protocol::{ScreenshotParams, ScreenshotRegion, ScreenshotResult};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, WebviewWindow};
+use tokio::sync::oneshot;
+use xcap::image::imageops::FilterType;
+use xcap::image::DynamicImage;
+
+/// How long to wait for the webview to report an element's position
+const ELEMENT_QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Element lookups waiting for the webview to call `report_element_bounds`
+#[derive(Default)]
+pub struct ElementBoundsRequests {
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<u64, oneshot::Sender<Option<ElementBounds>>>>,
+}
+
+/// `DOMRect` of an element relative to the webview viewport
+#[derive(Debug, Clone, Deserialize)]
+pub struct ElementBounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl ElementBounds {
+    /// Convert to a window region, shifting by the space taken by window decorations
+    fn to_region(&self, offset_x: f64, offset_y: f64) -> ScreenshotRegion {
+        let x = (self.x + offset_x).max(0.0).round();
+        let y = (self.y + offset_y).max(0.0).round();
+        ScreenshotRegion {
+            x: x as u32,
+            y: y as u32,
+            width: self.width.max(0.0).round() as u32,
+            height: self.height.max(0.0).round() as u32,
+        }
+    }
+}
+
+#[tauri::command]
+pub fn report_element_bounds(
+    requests: tauri::State<'_, ElementBoundsRequests>,
+    request_id: u64,
+    bounds: Option<ElementBounds>,
+) {
+    if let Some(tx) = requests.pending.lock().unwrap().remove(&request_id) {
+        let _ = tx.send(bounds);
+    }
+}
+
+pub async fn capture_screenshot(
+    app: &AppHandle,
+    params: &ScreenshotParams,
+) -> Result<ScreenshotResult> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let window = app
+        .get_webview_window("main")
+        .context("Main window not found")?;
+
+    if params.max_width == Some(0) || params.max_height == Some(0) {
+        anyhow::bail!("max_width and max_height must be greater than zero");
+    }
+
+    let region = match (&params.region, &params.element) {
+        (Some(_), Some(_)) => anyhow::bail!("Specify either region or element, not both"),
+        (Some(region), None) => Some(region.clone()),
+        (None, Some(selector)) => Some(query_element_region(app, &window, selector).await?),
+        (None, None) => None,
+    };
+
+    let output_path = Path::new(&params.output_dir);
+    if !output_path.exists() {
+        std::fs::create_dir_all(output_path).context("Failed to create output directory")?;
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    let filename = format!("ghview-screenshot-{}.png", timestamp);
+    let file_path = output_path.join(&filename);
+
+    // Use xcap to capture the window
+    let image = capture_window_image()?;
+    let image =
+        apply_screenshot_options(image, region.as_ref(), params.max_width, params.max_height)?;
+
+    // Save as PNG
+    image
+        .save(&file_path)
+        .context("Failed to save screenshot")?;
+
+    Ok(ScreenshotResult {
+        path: file_path.to_string_lossy().to_string(),
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+/// Ask the webview for the bounding box of the element matching `selector`
+async fn query_element_region(
+    app: &AppHandle,
+    window: &WebviewWindow,
+    selector: &str,
+) -> Result<ScreenshotRegion> {
+    let requests = app.state::<ElementBoundsRequests>();
+    let request_id = requests.next_id.fetch_add(1, Ordering::Relaxed);
+    let (tx, rx) = oneshot::channel();
+    requests.pending.lock().unwrap().insert(request_id, tx);
+
+    // JSON string encoding doubles as JavaScript string escaping
+    let selector_literal = serde_json::to_string(selector)?;
+    let script = format!(
+        "(() => {{\
+           const el = document.querySelector({selector});\
+           const r = el ? el.getBoundingClientRect() : null;\
+           window.__TAURI_INTERNALS__.invoke('report_element_bounds', {{\
+             requestId: {id},\
+             bounds: r ? {{ x: r.x, y: r.y, width: r.width, height: r.height }} : null\
+           }});\
+         }})()",
+        selector = selector_literal,
+        id = request_id
+    );
+
+    if let Err(e) = window.eval(&script) {
+        requests.pending.lock().unwrap().remove(&request_id);
+        return Err(e).context("Failed to query element");
+    }
+
+    let bounds = match tokio::time::timeout(ELEMENT_QUERY_TIMEOUT, rx).await {
+        Ok(Ok(bounds)) => bounds,
+        _ => {
+            requests.pending.lock().unwrap().remove(&request_id);
+            anyhow::bail!("Timed out waiting for element bounds");
+        }
+    };
+    let bounds = bounds.with_context(|| format!("No element matches selector: {}", selector))?;
+
+    // The captured image includes the title bar, the viewport does not
+    let scale = window.scale_factor()?;
+    let outer = window.outer_size()?;
+    let inner = window.inner_size()?;
+    let offset_x = (outer.width.saturating_sub(inner.width) as f64 / scale) / 2.0;
+    let offset_y = outer.height.saturating_sub(inner.height) as f64 / scale;
+
+    Ok(bounds.to_region(offset_x, offset_y))
+}
+
+/// Crop to `region` (clamped to the image) and downscale to fit the maximum dimensions
+pub fn apply_screenshot_options(
+    image: DynamicImage,
+    region: Option<&ScreenshotRegion>,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+) -> Result<DynamicImage> {
+    let mut image = image;
+
+    if let Some(region) = region {
+        if region.width == 0
+            || region.height == 0
+            || region.x >= image.width()
+            || region.y >= image.height()
+        {
+            anyhow::bail!(
+                "Region {}x{}+{}+{} is outside the {}x{} window",
+                region.width,
+                region.height,
+                region.x,
+                region.y,
+                image.width(),
+                image.height()
+            );
+        }
+        let width = region.width.min(image.width() - region.x);
+        let height = region.height.min(image.height() - region.y);
+        image = image.crop_imm(region.x, region.y, width, height);
+    }
+
+    let max_width = max_width.unwrap_or(u32::MAX).min(image.width());
+    let max_height = max_height.unwrap_or(u32::MAX).min(image.height());
+    if max_width < image.width() || max_height < image.height() {
+        image = image.resize(max_width, max_height, FilterType::Lanczos3);
+    }
+
+    Ok(image)
+}
+
+/// Capture the ghview window at its logical size
+fn capture_window_image() -> Result<DynamicImage> {
+    use xcap::Window;
+
+    // Find the ghview window by app_name (more reliable than title)
+    let windows = Window::all().context("Failed to enumerate windows")?;
+    let ghview_window = windows
+        .into_iter()
+        .find(|w| {
+            w.app_name()
+                .map(|name| name.to_lowercase() == "ghview")
+                .unwrap_or(false)
+        })
+        .context("ghview window not found (is ghview running?)")?;
+
+    // Get logical window size (before scaling)
+    let logical_width = ghview_window
+        .width()
+        .context("Failed to get window width")?;
+    let logical_height = ghview_window
+        .height()
+        .context("Failed to get window height")?;
+
+    // Capture the window (this returns the actual pixel size on Retina displays)
+    let image = ghview_window
+        .capture_image()
+        .context("Failed to capture window image")?;
+
+    // Resize to logical size if the captured image is larger (Retina display)
+    let resized = if image.width() > logical_width || image.height() > logical_height {
+        DynamicImage::ImageRgba8(image).resize_exact(
+            logical_width,
+            logical_height,
+            FilterType::Lanczos3,
+        )
+    } else {
+        DynamicImage::ImageRgba8(image)
+    };
+
+    Ok(resized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blank(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::new_rgba8(width, height)
+    }
+
+    fn region(x: u32, y: u32, width: u32, height: u32) -> ScreenshotRegion {
+        ScreenshotRegion {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_apply_options_noop() {
+        let image = apply_screenshot_options(blank(800, 600), None, None, None).unwrap();
+        assert_eq!((image.width(), image.height()), (800, 600));
+    }
+
+    #[test]
+    fn test_apply_options_crops_region() {
+        let image = apply_screenshot_options(
+            blank(800, 600),
+            Some(&region(100, 50, 200, 100)),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!((image.width(), image.height()), (200, 100));
+    }
+
+    #[test]
+    fn test_apply_options_clamps_region_to_image() {
+        let image = apply_screenshot_options(
+            blank(800, 600),
+            Some(&region(700, 500, 300, 300)),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!((image.width(), image.height()), (100, 100));
+    }
+
+    #[test]
+    fn test_apply_options_rejects_region_outside_image() {
+        assert!(apply_screenshot_options(
+            blank(800, 600),
+            Some(&region(800, 0, 10, 10)),
+            None,
+            None
+        )
+        .is_err());
+        assert!(
+            apply_screenshot_options(blank(800, 600), Some(&region(0, 0, 0, 10)), None, None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_apply_options_downscales_preserving_aspect_ratio() {
+        let image = apply_screenshot_options(blank(800, 600), None, Some(400), None).unwrap();
+        assert_eq!((image.width(), image.height()), (400, 300));
+
+        let image = apply_screenshot_options(blank(800, 600), None, Some(400), Some(100)).unwrap();
+        assert_eq!(image.height(), 100);
+        assert!(image.width() <= 400);
+    }
+
+    #[test]
+    fn test_apply_options_never_upscales() {
+        let image =
+            apply_screenshot_options(blank(200, 100), None, Some(1000), Some(1000)).unwrap();
+        assert_eq!((image.width(), image.height()), (200, 100));
+    }
+
+    #[test]
+    fn test_element_bounds_to_region() {
+        let bounds = ElementBounds {
+            x: 10.4,
+            y: -3.0,
+            width: 120.6,
+            height: 40.0,
+        };
+        assert_eq!(bounds.to_region(0.0, 28.0), region(10, 25, 121, 40));
+    }
+}
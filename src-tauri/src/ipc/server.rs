@@ -1,57 +1,138 @@
-use crate::ipc::protocol::{IpcRequest, IpcResponse, ScreenshotParams, ScreenshotResult};
+use crate::ipc::automation;
+use crate::ipc::protocol::{
+    NavigateParams, PullRequestParams, ReviewStatsParams, ScreenshotParams, SetThemeParams,
+    WaitForParams, WindowSize,
+};
+use crate::ipc::screenshot::capture_screenshot;
 use anyhow::{Context, Result};
+use ghview_core::github::{
+    fetch_check_status, fetch_pull_request, fetch_pull_request_details, review_requested_prs,
+};
+use ghview_core::ipc::protocol::{IpcRequest, IpcResponse};
+use ghview_core::ipc::transport::{
+    generate_token, tokens_match, write_session_file, IpcSession, IpcTransport,
+};
+use ghview_core::sla::fetch_sla_summary;
+use ghview_core::stats::{review_stats, StatsRange};
+use ghview_core::view::{pr_view, view_of, ViewOptions};
+use std::future::Future;
+use std::net::SocketAddr;
 use std::path::Path;
 use std::sync::Arc;
-use tauri::{AppHandle, Manager};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+#[cfg(unix)]
 use tokio::net::UnixListener;
 
-#[cfg(target_os = "macos")]
-const SOCKET_PATH: &str = "/tmp/ghview.sock";
-
-#[cfg(target_os = "linux")]
-const SOCKET_PATH: &str = "/tmp/ghview.sock";
+pub async fn start_ipc_server(app: AppHandle) -> Result<()> {
+    let app = Arc::new(app);
 
-#[cfg(target_os = "windows")]
-const SOCKET_PATH: &str = r"\\.\pipe\ghview";
+    match IpcTransport::from_env()? {
+        #[cfg(unix)]
+        IpcTransport::Unix(path) => serve_unix(&path, app).await,
+        IpcTransport::Tcp(addr) => serve_tcp(addr, app).await,
+    }
+}
 
-pub async fn start_ipc_server(app: AppHandle) -> Result<()> {
-    let socket_path = Path::new(SOCKET_PATH);
+#[cfg(unix)]
+async fn serve_unix(socket_path: &Path, app: Arc<AppHandle>) -> Result<()> {
     if socket_path.exists() {
         std::fs::remove_file(socket_path).context("Failed to remove existing socket")?;
     }
 
-    let listener = UnixListener::bind(SOCKET_PATH).context("Failed to bind Unix socket")?;
-    let app = Arc::new(app);
+    let listener = UnixListener::bind(socket_path).context("Failed to bind Unix socket")?;
+
+    tracing::info!("IPC server listening on {}", socket_path.display());
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let app = Arc::clone(&app);
+                tokio::spawn(async move {
+                    // Unix sockets are protected by filesystem permissions
+                    if let Err(e) = handle_connection(stream, app, None).await {
+                        tracing::error!("IPC connection error: {}", e);
+                    }
+                });
+            }
+            Err(e) => {
+                tracing::error!("IPC accept error: {}", e);
+            }
+        }
+    }
+}
+
+async fn serve_tcp(addr: SocketAddr, app: Arc<AppHandle>) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .context("Failed to bind TCP socket")?;
+    let local_addr = listener.local_addr()?;
+
+    // Any local process can reach a loopback port, so every request must carry
+    // the token that only the current user can read from the session file
+    let token: Arc<str> = Arc::from(generate_token());
+    let session_path = write_session_file(&IpcSession {
+        addr: local_addr,
+        token: token.to_string(),
+    })?;
 
-    log::info!("IPC server listening on {}", SOCKET_PATH);
+    tracing::info!(
+        "IPC server listening on {} (session file: {})",
+        local_addr,
+        session_path.display()
+    );
 
     loop {
         match listener.accept().await {
             Ok((stream, _)) => {
                 let app = Arc::clone(&app);
+                let token = Arc::clone(&token);
                 tokio::spawn(async move {
-                    if let Err(e) = handle_connection(stream, app).await {
-                        log::error!("IPC connection error: {}", e);
+                    if let Err(e) = handle_connection(stream, app, Some(token)).await {
+                        tracing::error!("IPC connection error: {}", e);
                     }
                 });
             }
             Err(e) => {
-                log::error!("IPC accept error: {}", e);
+                tracing::error!("IPC accept error: {}", e);
             }
         }
     }
 }
 
-async fn handle_connection(stream: tokio::net::UnixStream, app: Arc<AppHandle>) -> Result<()> {
-    let (reader, mut writer) = stream.into_split();
+async fn handle_connection<S>(stream: S, app: Arc<AppHandle>, token: Option<Arc<str>>) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    serve_connection(stream, token.as_deref(), |request| async move {
+        handle_request(&request, &app).await
+    })
+    .await
+}
+
+/// Read a single request from the stream, authorize it and write back the response
+async fn serve_connection<S, F, Fut>(
+    stream: S,
+    expected_token: Option<&str>,
+    handler: F,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    F: FnOnce(IpcRequest) -> Fut,
+    Fut: Future<Output = IpcResponse>,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
     let mut reader = BufReader::new(reader);
     let mut line = String::new();
 
     reader.read_line(&mut line).await?;
 
     let response = match serde_json::from_str::<IpcRequest>(&line) {
-        Ok(request) => handle_request(&request, &app).await,
+        Ok(request) => match authorize(&request, expected_token) {
+            Ok(()) => handler(request).await,
+            Err(response) => response,
+        },
         Err(e) => IpcResponse::error(format!("Invalid request: {}", e)),
     };
 
@@ -63,9 +144,40 @@ async fn handle_connection(stream: tokio::net::UnixStream, app: Arc<AppHandle>)
     Ok(())
 }
 
+fn authorize(request: &IpcRequest, expected_token: Option<&str>) -> Result<(), IpcResponse> {
+    let Some(expected) = expected_token else {
+        return Ok(());
+    };
+
+    match request.token.as_deref() {
+        Some(provided) if tokens_match(expected, provided) => Ok(()),
+        Some(_) => {
+            tracing::warn!("Rejected IPC request with invalid token");
+            Err(IpcResponse::error("Unauthorized: invalid token"))
+        }
+        None => {
+            tracing::warn!("Rejected IPC request without token");
+            Err(IpcResponse::error("Unauthorized: missing token"))
+        }
+    }
+}
+
 async fn handle_request(request: &IpcRequest, app: &AppHandle) -> IpcResponse {
     match request.method.as_str() {
         "screenshot" => handle_screenshot(request, app).await,
+        "list_review_requests" => handle_list_review_requests().await,
+        "get_pr_view" => handle_get_pr_view(request).await,
+        "get_sla_summary" => handle_get_sla_summary().await,
+        "get_review_stats" => handle_get_review_stats(request).await,
+        "get_pull_request" => handle_get_pull_request(request).await,
+        "get_pull_request_details" => handle_get_pull_request_details(request).await,
+        "get_check_status" => handle_get_check_status(request).await,
+        "refresh" => handle_refresh(app).await,
+        "navigate" => handle_navigate(request, app),
+        "select_pr" => handle_select_pr(request, app),
+        "resize_window" => handle_resize_window(request, app),
+        "set_theme" => handle_set_theme(request, app),
+        "wait_for" => handle_wait_for(request, app).await,
         "ping" => IpcResponse::success(serde_json::json!({"pong": true})),
         _ => IpcResponse::error(format!("Unknown method: {}", request.method)),
     }
@@ -77,85 +189,271 @@ async fn handle_screenshot(request: &IpcRequest, app: &AppHandle) -> IpcResponse
         Err(e) => return IpcResponse::error(format!("Invalid screenshot params: {}", e)),
     };
 
-    match capture_screenshot(app, &params.output_dir).await {
-        Ok(path) => {
-            let result = ScreenshotResult { path };
-            IpcResponse::success(serde_json::to_value(result).unwrap())
-        }
+    match capture_screenshot(app, &params).await {
+        Ok(result) => IpcResponse::success(serde_json::to_value(result).unwrap()),
         Err(e) => IpcResponse::error(format!("Screenshot failed: {}", e)),
     }
 }
 
-async fn capture_screenshot(app: &AppHandle, output_dir: &str) -> Result<String> {
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    // Verify the window exists
-    let _window = app
-        .get_webview_window("main")
-        .context("Main window not found")?;
-
-    let output_path = Path::new(output_dir);
-    if !output_path.exists() {
-        std::fs::create_dir_all(output_path).context("Failed to create output directory")?;
-    }
-
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_millis();
-    let filename = format!("ghview-screenshot-{}.png", timestamp);
-    let file_path = output_path.join(&filename);
-
-    // Use xcap to capture the window
-    capture_window_screenshot(&file_path)?;
-
-    Ok(file_path.to_string_lossy().to_string())
-}
-
-fn capture_window_screenshot(output_path: &Path) -> Result<()> {
-    use xcap::image::imageops::FilterType;
-    use xcap::image::DynamicImage;
-    use xcap::Window;
-
-    // Find the ghview window by app_name (more reliable than title)
-    let windows = Window::all().context("Failed to enumerate windows")?;
-    let ghview_window = windows
-        .into_iter()
-        .find(|w| {
-            w.app_name()
-                .map(|name| name.to_lowercase() == "ghview")
-                .unwrap_or(false)
-        })
-        .context("ghview window not found (is ghview running?)")?;
-
-    // Get logical window size (before scaling)
-    let logical_width = ghview_window
-        .width()
-        .context("Failed to get window width")?;
-    let logical_height = ghview_window
-        .height()
-        .context("Failed to get window height")?;
-
-    // Capture the window (this returns the actual pixel size on Retina displays)
-    let image = ghview_window
-        .capture_image()
-        .context("Failed to capture window image")?;
-
-    // Resize to logical size if the captured image is larger (Retina display)
-    let resized = if image.width() > logical_width || image.height() > logical_height {
-        DynamicImage::ImageRgba8(image).resize_exact(
-            logical_width,
-            logical_height,
-            FilterType::Lanczos3,
-        )
+async fn handle_list_review_requests() -> IpcResponse {
+    match review_requested_prs().await {
+        Ok(prs) => IpcResponse::success(serde_json::to_value(prs).unwrap()),
+        Err(e) => IpcResponse::error(format!("Failed to list review requests: {}", e)),
+    }
+}
+
+async fn handle_get_pr_view(request: &IpcRequest) -> IpcResponse {
+    let options: ViewOptions = if request.params.is_null() {
+        ViewOptions::default()
     } else {
-        DynamicImage::ImageRgba8(image)
+        match serde_json::from_value(request.params.clone()) {
+            Ok(options) => options,
+            Err(e) => return IpcResponse::error(format!("Invalid view params: {}", e)),
+        }
     };
 
-    // Save as PNG
-    resized
-        .save(output_path)
-        .context("Failed to save screenshot")?;
+    match pr_view(options).await {
+        Ok(view) => IpcResponse::success(serde_json::to_value(view).unwrap()),
+        Err(e) => IpcResponse::error(format!("Failed to get pull request view: {}", e)),
+    }
+}
+
+async fn handle_get_sla_summary() -> IpcResponse {
+    match fetch_sla_summary().await {
+        Ok(summary) => IpcResponse::success(serde_json::to_value(summary).unwrap()),
+        Err(e) => IpcResponse::error(format!("Failed to get SLA summary: {}", e)),
+    }
+}
 
-    Ok(())
+async fn handle_get_review_stats(request: &IpcRequest) -> IpcResponse {
+    let params: ReviewStatsParams = if request.params.is_null() {
+        ReviewStatsParams::default()
+    } else {
+        match serde_json::from_value(request.params.clone()) {
+            Ok(params) => params,
+            Err(e) => return IpcResponse::error(format!("Invalid review stats params: {}", e)),
+        }
+    };
+    let range = match StatsRange::parse(
+        params.from.as_deref(),
+        params.to.as_deref(),
+        chrono::Utc::now().date_naive(),
+    ) {
+        Ok(range) => range,
+        Err(e) => return IpcResponse::error(e.to_string()),
+    };
+
+    match review_stats(range).await {
+        Ok(stats) => IpcResponse::success(serde_json::to_value(stats).unwrap()),
+        Err(e) => IpcResponse::error(format!("Failed to get review stats: {}", e)),
+    }
+}
+
+async fn handle_get_pull_request(request: &IpcRequest) -> IpcResponse {
+    let params: PullRequestParams = match serde_json::from_value(request.params.clone()) {
+        Ok(p) => p,
+        Err(e) => return IpcResponse::error(format!("Invalid pull request params: {}", e)),
+    };
+
+    match fetch_pull_request(&params.owner, &params.repo, params.number).await {
+        Ok(pr) => IpcResponse::success(serde_json::to_value(pr).unwrap()),
+        Err(e) => IpcResponse::error(format!("Failed to get pull request: {}", e)),
+    }
+}
+
+async fn handle_get_pull_request_details(request: &IpcRequest) -> IpcResponse {
+    let params: PullRequestParams = match serde_json::from_value(request.params.clone()) {
+        Ok(p) => p,
+        Err(e) => return IpcResponse::error(format!("Invalid pull request params: {}", e)),
+    };
+
+    match fetch_pull_request_details(&params.owner, &params.repo, params.number).await {
+        Ok(details) => IpcResponse::success(serde_json::to_value(details).unwrap()),
+        Err(e) => IpcResponse::error(format!("Failed to get pull request details: {}", e)),
+    }
+}
+
+async fn handle_get_check_status(request: &IpcRequest) -> IpcResponse {
+    let params: PullRequestParams = match serde_json::from_value(request.params.clone()) {
+        Ok(p) => p,
+        Err(e) => return IpcResponse::error(format!("Invalid pull request params: {}", e)),
+    };
+
+    match fetch_check_status(&params.owner, &params.repo, params.number).await {
+        Ok(status) => IpcResponse::success(serde_json::to_value(status).unwrap()),
+        Err(e) => IpcResponse::error(format!("Failed to get check status: {}", e)),
+    }
+}
+
+async fn handle_refresh(app: &AppHandle) -> IpcResponse {
+    match review_requested_prs().await {
+        Ok(prs) => {
+            // Push the fresh list to the window, as it views it, so it doesn't have to refetch
+            let view = view_of(prs.clone(), ViewOptions::default());
+            if let Err(e) = app.emit("prs-updated", &view) {
+                tracing::warn!("Failed to emit prs-updated event: {}", e);
+            }
+            IpcResponse::success(serde_json::to_value(prs).unwrap())
+        }
+        Err(e) => IpcResponse::error(format!("Refresh failed: {}", e)),
+    }
+}
+
+fn handle_navigate(request: &IpcRequest, app: &AppHandle) -> IpcResponse {
+    let params: NavigateParams = match serde_json::from_value(request.params.clone()) {
+        Ok(p) => p,
+        Err(e) => return IpcResponse::error(format!("Invalid navigate params: {}", e)),
+    };
+
+    match automation::navigate(app, &params) {
+        Ok(()) => IpcResponse::success(serde_json::json!({ "view": params.view })),
+        Err(e) => IpcResponse::error(format!("Navigate failed: {}", e)),
+    }
+}
+
+fn handle_select_pr(request: &IpcRequest, app: &AppHandle) -> IpcResponse {
+    let params: PullRequestParams = match serde_json::from_value(request.params.clone()) {
+        Ok(p) => p,
+        Err(e) => return IpcResponse::error(format!("Invalid pull request params: {}", e)),
+    };
+
+    match automation::select_pr(app, &params) {
+        Ok(()) => IpcResponse::success(serde_json::to_value(params).unwrap()),
+        Err(e) => IpcResponse::error(format!("Select failed: {}", e)),
+    }
+}
+
+fn handle_resize_window(request: &IpcRequest, app: &AppHandle) -> IpcResponse {
+    let params: WindowSize = match serde_json::from_value(request.params.clone()) {
+        Ok(p) => p,
+        Err(e) => return IpcResponse::error(format!("Invalid resize params: {}", e)),
+    };
+
+    match automation::resize_window(app, &params) {
+        Ok(size) => IpcResponse::success(serde_json::to_value(size).unwrap()),
+        Err(e) => IpcResponse::error(format!("Resize failed: {}", e)),
+    }
+}
+
+fn handle_set_theme(request: &IpcRequest, app: &AppHandle) -> IpcResponse {
+    let params: SetThemeParams = match serde_json::from_value(request.params.clone()) {
+        Ok(p) => p,
+        Err(e) => return IpcResponse::error(format!("Invalid theme params: {}", e)),
+    };
+
+    match automation::set_theme(app, &params) {
+        Ok(()) => IpcResponse::success(serde_json::to_value(params).unwrap()),
+        Err(e) => IpcResponse::error(format!("Set theme failed: {}", e)),
+    }
+}
+
+async fn handle_wait_for(request: &IpcRequest, app: &AppHandle) -> IpcResponse {
+    let params: WaitForParams = match serde_json::from_value(request.params.clone()) {
+        Ok(p) => p,
+        Err(e) => return IpcResponse::error(format!("Invalid wait params: {}", e)),
+    };
+
+    match automation::wait_for(app, &params).await {
+        Ok(result) => IpcResponse::success(serde_json::to_value(result).unwrap()),
+        Err(e) => IpcResponse::error(format!("Wait failed: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn roundtrip(expected_token: Option<&str>, request_line: &str) -> IpcResponse {
+        let (client, server) = tokio::io::duplex(4096);
+        let expected_token = expected_token.map(str::to_string);
+        let server_task = tokio::spawn(async move {
+            serve_connection(server, expected_token.as_deref(), |_request| async {
+                IpcResponse::success(serde_json::json!({"pong": true}))
+            })
+            .await
+        });
+
+        let (reader, mut writer) = tokio::io::split(client);
+        writer.write_all(request_line.as_bytes()).await.unwrap();
+        writer.write_all(b"\n").await.unwrap();
+
+        let mut response_line = String::new();
+        BufReader::new(reader)
+            .read_line(&mut response_line)
+            .await
+            .unwrap();
+        server_task.await.unwrap().unwrap();
+
+        serde_json::from_str(&response_line).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_connection_without_auth_accepts_request() {
+        let response = roundtrip(None, r#"{"method": "ping", "params": {}}"#).await;
+        assert!(response.error.is_none());
+        assert_eq!(response.result, Some(serde_json::json!({"pong": true})));
+    }
+
+    #[tokio::test]
+    async fn test_connection_accepts_valid_token() {
+        let response = roundtrip(
+            Some("secret"),
+            r#"{"method": "ping", "params": {}, "token": "secret"}"#,
+        )
+        .await;
+        assert!(response.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_connection_rejects_missing_token() {
+        let response = roundtrip(Some("secret"), r#"{"method": "ping", "params": {}}"#).await;
+        assert!(response.result.is_none());
+        assert_eq!(
+            response.error,
+            Some("Unauthorized: missing token".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connection_rejects_wrong_token() {
+        let response = roundtrip(
+            Some("secret"),
+            r#"{"method": "ping", "params": {}, "token": "guess"}"#,
+        )
+        .await;
+        assert!(response.result.is_none());
+        assert_eq!(
+            response.error,
+            Some("Unauthorized: invalid token".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tcp_connection_rejects_wrong_token() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            serve_connection(stream, Some("secret"), |_request| async {
+                IpcResponse::success(serde_json::json!({"pong": true}))
+            })
+            .await
+        });
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"{\"method\": \"ping\", \"params\": {}, \"token\": \"guess\"}\n")
+            .await
+            .unwrap();
+        let mut response_line = String::new();
+        BufReader::new(stream)
+            .read_line(&mut response_line)
+            .await
+            .unwrap();
+        server_task.await.unwrap().unwrap();
+
+        let response: IpcResponse = serde_json::from_str(&response_line).unwrap();
+        assert!(response.result.is_none());
+        assert!(response.error.unwrap().starts_with("Unauthorized"));
+    }
 }
@@ -1,11 +1,27 @@
+pub mod cli;
 mod commands;
+mod config;
 mod ipc;
-mod models;
+mod logging;
 
 use commands::{
-    auth::{check_auth_status, logout, poll_device_flow, start_device_flow},
+    audit::get_audit_log,
+    auth::{
+        cancel_device_flow, check_auth_status, login_with_token, logout, start_device_flow,
+        DeviceFlowState,
+    },
+    export::export_pull_requests,
+    filters::preview_filters,
     github::fetch_review_requested_prs,
+    sla::fetch_sla_summary,
+    stats::{export_review_stats, get_review_stats},
+    view::fetch_pr_view,
 };
+use config::{get_settings, update_settings};
+use ghview_core::logging::LogConfig;
+use ipc::automation::{report_ui_condition, ConditionRequests};
+use ipc::screenshot::{report_element_bounds, ElementBoundsRequests};
+use logging::export_logs;
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -15,13 +31,26 @@ fn greet(name: &str) -> String {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Load settings before logging so the configured filter applies, but report
+    // problems only once logging is up
+    let config_result = ghview_core::config::init();
+    let _log_guard = ghview_core::logging::init(&LogConfig::from_env());
+    if let Err(e) = config_result {
+        tracing::error!("Using default settings: {}", e);
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .manage(ElementBoundsRequests::default())
+        .manage(ConditionRequests::default())
+        .manage(DeviceFlowState::default())
         .setup(|app| {
+            config::watch(app.handle().clone());
             let handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 if let Err(e) = ipc::start_ipc_server(handle).await {
-                    eprintln!("Failed to start IPC server: {}", e);
+                    tracing::error!("Failed to start IPC server: {}", e);
                 }
             });
             Ok(())
@@ -30,9 +59,22 @@ pub fn run() {
             greet,
             check_auth_status,
             start_device_flow,
-            poll_device_flow,
+            cancel_device_flow,
+            login_with_token,
             logout,
-            fetch_review_requested_prs
+            fetch_review_requested_prs,
+            preview_filters,
+            fetch_pr_view,
+            fetch_sla_summary,
+            get_review_stats,
+            export_review_stats,
+            export_pull_requests,
+            report_element_bounds,
+            report_ui_condition,
+            export_logs,
+            get_audit_log,
+            get_settings,
+            update_settings
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
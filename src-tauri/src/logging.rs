@@ -0,0 +1,7 @@
+use ghview_core::logging::{export_recent_logs, LogError};
+
+/// Write the recent log lines to a file in the log directory and return its path
+#[tauri::command]
+pub fn export_logs() -> Result<String, LogError> {
+    export_recent_logs()
+}
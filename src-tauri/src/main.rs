@@ -1,6 +1,13 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-fn main() {
-    ghview_lib::run()
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    // `ghview <command>` runs the CLI; without one the app starts
+    if let Some(code) = ghview_lib::cli::run_from_args(std::env::args_os()) {
+        return code;
+    }
+    ghview_lib::run();
+    ExitCode::SUCCESS
 }
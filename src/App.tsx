@@ -1,5 +1,10 @@
-import { Auth, PrList } from "./components";
-import { useGitHubAuth, useGitHubPRs } from "./hooks";
+import { AuditLog, Auth, PrList } from "./components";
+import {
+  useGitHubAuth,
+  useGitHubPRs,
+  useSettings,
+  useUiAutomation,
+} from "./hooks";
 import "./App.css";
 
 function App() {
@@ -9,30 +14,43 @@ function App() {
     error: authError,
     deviceFlow,
     startLogin,
-    pollLogin,
+    cancelLogin,
+    loginWithToken,
     logout,
   } = useGitHubAuth();
 
+  const { settings } = useSettings();
+
   const {
+    view,
     prs,
     loading: prsLoading,
     error: prsError,
     refresh,
-  } = useGitHubPRs(authStatus?.authenticated ?? false);
+  } = useGitHubPRs(
+    authStatus?.authenticated ?? false,
+    settings?.pull_requests.refresh_interval_secs
+  );
+
+  const { selectedPr } = useUiAutomation();
 
   return (
     <div className="app">
-      <Auth
-        authStatus={authStatus}
-        loading={authLoading}
-        error={authError}
-        deviceFlow={deviceFlow}
-        onStartLogin={startLogin}
-        onPollLogin={pollLogin}
-        onLogout={logout}
-      />
+      <div data-view="account">
+        <Auth
+          authStatus={authStatus}
+          loading={authLoading}
+          error={authError}
+          deviceFlow={deviceFlow}
+          onStartLogin={startLogin}
+          onCancelLogin={cancelLogin}
+          onLoginWithToken={loginWithToken}
+          onLogout={logout}
+        />
+        {authStatus?.authenticated && <AuditLog />}
+      </div>
       {authStatus?.authenticated && (
-        <main className="main-content">
+        <main className="main-content" data-view="pull_requests">
           <div className="main-header">
             <h1>Pull Requests</h1>
             <button
@@ -43,7 +61,13 @@ function App() {
               {prsLoading ? "Refreshing..." : "Refresh"}
             </button>
           </div>
-          <PrList prs={prs} loading={prsLoading} error={prsError} />
+          <PrList
+            prs={prs}
+            loading={prsLoading}
+            error={prsError}
+            selectedPr={selectedPr}
+            groups={view?.group_by === "none" ? undefined : view?.groups}
+          />
         </main>
       )}
     </div>
@@ -0,0 +1,76 @@
+import { render, screen, waitFor } from "@testing-library/react";
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { AuditLog } from "./AuditLog";
+import type { AuditEntry } from "../types";
+
+const mockInvoke = vi.fn();
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: (...args: unknown[]) => mockInvoke(...args),
+}));
+
+const entries: AuditEntry[] = [
+  {
+    timestamp: "2026-10-17T12:00:00Z",
+    source: "mcp",
+    action: {
+      action: "add_comment",
+      owner: "octo-org",
+      repo: "api",
+      number: 1,
+      body: "Looks good",
+    },
+    outcome: "performed",
+  },
+  {
+    timestamp: "2026-10-17T11:00:00Z",
+    source: "mcp",
+    action: {
+      action: "rerun_failed_checks",
+      owner: "octo-org",
+      repo: "web",
+      number: 2,
+    },
+    outcome: "failed",
+    error: "GitHub API error: 403",
+  },
+];
+
+describe("AuditLog", () => {
+  beforeEach(() => {
+    mockInvoke.mockReset();
+  });
+
+  it("requests the latest entries", async () => {
+    mockInvoke.mockResolvedValue([]);
+    render(<AuditLog />);
+
+    await waitFor(() => {
+      expect(screen.getByText(/no actions/i)).toBeInTheDocument();
+    });
+    expect(mockInvoke).toHaveBeenCalledWith("get_audit_log", { limit: 20 });
+  });
+
+  it("lists actions with their outcome", async () => {
+    mockInvoke.mockResolvedValue(entries);
+    render(<AuditLog />);
+
+    await waitFor(() => {
+      expect(screen.getByText("Comment on octo-org/api#1")).toBeInTheDocument();
+    });
+    expect(
+      screen.getByText("Re-run failed checks on octo-org/web#2")
+    ).toBeInTheDocument();
+    expect(screen.getByText("Performed")).toBeInTheDocument();
+    expect(screen.getByText("Failed")).toBeInTheDocument();
+    expect(screen.getByText("GitHub API error: 403")).toBeInTheDocument();
+  });
+
+  it("shows an error when the log can't be read", async () => {
+    mockInvoke.mockRejectedValue("Failed to read audit log");
+    render(<AuditLog />);
+
+    await waitFor(() => {
+      expect(screen.getByText("Failed to read audit log")).toBeInTheDocument();
+    });
+  });
+});
@@ -0,0 +1,85 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { AuditEntry, AuditOutcome } from "../types";
+import "./AuditLog.css";
+
+// Only the latest entries; the full log is in audit.jsonl
+const AUDIT_LOG_LIMIT = 20;
+
+const OUTCOME_LABELS: Record<AuditOutcome, string> = {
+  performed: "Performed",
+  dry_run: "Dry run",
+  refused: "Refused",
+  failed: "Failed",
+};
+
+function describeAction({ action }: AuditEntry): string {
+  const target = `${action.owner}/${action.repo}#${action.number}`;
+  switch (action.action) {
+    case "submit_review": {
+      const event = action.event.toLowerCase().replace("_", " ");
+      return `Review (${event}) on ${target}`;
+    }
+    case "add_comment":
+      return `Comment on ${target}`;
+    case "rerun_failed_checks":
+      return `Re-run failed checks on ${target}`;
+  }
+}
+
+export function AuditLog() {
+  const [entries, setEntries] = useState<AuditEntry[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<AuditEntry[]>("get_audit_log", { limit: AUDIT_LOG_LIMIT })
+      .then(setEntries)
+      .catch((err) =>
+        setError(err instanceof Error ? err.message : String(err))
+      )
+      .finally(() => setLoading(false));
+  }, []);
+
+  if (loading) {
+    return <div className="audit-log-status">Loading activity...</div>;
+  }
+
+  if (error) {
+    return <div className="audit-log-status audit-log-error">{error}</div>;
+  }
+
+  return (
+    <section className="audit-log">
+      <h3>Recent activity</h3>
+      {entries.length === 0 ? (
+        <div className="audit-log-status">
+          No actions have been taken through ghview
+        </div>
+      ) : (
+        <ul>
+          {entries.map((entry, index) => (
+            <li
+              key={`${entry.timestamp}-${index}`}
+              className="audit-log-entry"
+            >
+              <span
+                className={`audit-outcome audit-outcome-${entry.outcome}`}
+              >
+                {OUTCOME_LABELS[entry.outcome]}
+              </span>
+              <span className="audit-description">{describeAction(entry)}</span>
+              <span className="audit-meta">
+                {entry.source} ·{" "}
+                <time dateTime={entry.timestamp}>
+                  {new Date(entry.timestamp).toLocaleString()}
+                </time>
+              </span>
+              {entry.error && <div className="audit-error">{entry.error}</div>}
+            </li>
+          ))}
+        </ul>
+      )}
+    </section>
+  );
+}
@@ -9,7 +9,7 @@ describe("Auth", () => {
     error: null,
     deviceFlow: null,
     onStartLogin: vi.fn(),
-    onPollLogin: vi.fn(),
+    onCancelLogin: vi.fn(),
     onLogout: vi.fn(),
   };
 
@@ -76,6 +76,21 @@ describe("Auth", () => {
 
       expect(writeText).toHaveBeenCalledWith("ABCD-1234");
     });
+
+    it("calls onCancelLogin when cancel is clicked", () => {
+      const onCancelLogin = vi.fn();
+      render(
+        <Auth
+          {...defaultProps}
+          deviceFlow={deviceFlowProps}
+          onCancelLogin={onCancelLogin}
+        />
+      );
+
+      fireEvent.click(screen.getByRole("button", { name: /cancel/i }));
+
+      expect(onCancelLogin).toHaveBeenCalled();
+    });
   });
 
   describe("when authenticated", () => {
@@ -1,4 +1,4 @@
-import { useEffect, useRef } from "react";
+import { useState } from "react";
 import type { DeviceFlowInit, AuthStatus } from "../types";
 import "./Auth.css";
 
@@ -8,7 +8,8 @@ interface AuthProps {
   error: string | null;
   deviceFlow: DeviceFlowInit | null;
   onStartLogin: () => void;
-  onPollLogin: () => void;
+  onCancelLogin?: () => void;
+  onLoginWithToken?: (token: string) => void;
   onLogout: () => void;
 }
 
@@ -18,28 +19,11 @@ export function Auth({
   error,
   deviceFlow,
   onStartLogin,
-  onPollLogin,
+  onCancelLogin,
+  onLoginWithToken,
   onLogout,
 }: AuthProps) {
-  const pollIntervalRef = useRef<number | null>(null);
-
-  useEffect(() => {
-    if (deviceFlow && !authStatus?.authenticated) {
-      pollIntervalRef.current = window.setInterval(
-        () => {
-          onPollLogin();
-        },
-        (deviceFlow.interval + 1) * 1000
-      );
-    }
-
-    return () => {
-      if (pollIntervalRef.current) {
-        clearInterval(pollIntervalRef.current);
-        pollIntervalRef.current = null;
-      }
-    };
-  }, [deviceFlow, authStatus?.authenticated, onPollLogin]);
+  const [token, setToken] = useState("");
 
   if (loading) {
     return (
@@ -55,6 +39,11 @@ export function Auth({
         <span className="auth-user">
           Logged in as <strong>{authStatus.username}</strong>
         </span>
+        {authStatus.missing_scopes && authStatus.missing_scopes.length > 0 && (
+          <span className="auth-scope-warning">
+            Token is missing scopes: {authStatus.missing_scopes.join(", ")}
+          </span>
+        )}
         <button className="auth-logout-btn" onClick={onLogout}>
           Logout
         </button>
@@ -98,6 +87,11 @@ export function Auth({
             </button>
           </div>
           <p className="auth-waiting">Waiting for authorization...</p>
+          {onCancelLogin && (
+            <button className="auth-cancel-btn" onClick={onCancelLogin}>
+              Cancel
+            </button>
+          )}
         </div>
       </div>
     );
@@ -112,6 +106,27 @@ export function Auth({
         <button className="auth-login-btn" onClick={onStartLogin}>
           Sign in with GitHub
         </button>
+        {onLoginWithToken && (
+          <form
+            className="auth-token-form"
+            onSubmit={(e) => {
+              e.preventDefault();
+              if (token.trim()) onLoginWithToken(token.trim());
+            }}
+          >
+            <input
+              type="password"
+              className="auth-token-input"
+              placeholder="Or paste a personal access token"
+              aria-label="Personal access token"
+              value={token}
+              onChange={(e) => setToken(e.target.value)}
+            />
+            <button type="submit" className="auth-token-btn">
+              Use token
+            </button>
+          </form>
+        )}
       </div>
     </div>
   );
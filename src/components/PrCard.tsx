@@ -1,8 +1,10 @@
+import { useEffect, useRef } from "react";
 import type { PullRequestWithChecks, CheckState } from "../types";
 import "./PrCard.css";
 
 interface PrCardProps {
   prWithChecks: PullRequestWithChecks;
+  selected?: boolean;
 }
 
 function formatRelativeTime(dateString: string): string {
@@ -51,11 +53,22 @@ function CheckStatusIndicator({ state }: { state: CheckState }) {
   );
 }
 
-export function PrCard({ prWithChecks }: PrCardProps) {
+export function PrCard({ prWithChecks, selected = false }: PrCardProps) {
   const { pull_request: pr, check_status } = prWithChecks;
+  const ref = useRef<HTMLElement>(null);
+
+  useEffect(() => {
+    if (selected) {
+      ref.current?.scrollIntoView?.({ block: "nearest" });
+    }
+  }, [selected]);
 
   return (
-    <article className="pr-card">
+    <article
+      ref={ref}
+      className={selected ? "pr-card pr-card-selected" : "pr-card"}
+      aria-current={selected || undefined}
+    >
       <div className="pr-card-header">
         <div className="pr-card-title-row">
           {check_status && <CheckStatusIndicator state={check_status.state} />}
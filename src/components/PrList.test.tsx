@@ -119,4 +119,46 @@ describe("PrList", () => {
     render(<PrList prs={[mockPrs[0]]} loading={false} error={null} />);
     expect(screen.getByText(/1 pull request[^s]/i)).toBeInTheDocument();
   });
+
+  it("renders a heading per group", () => {
+    render(
+      <PrList
+        prs={mockPrs}
+        loading={false}
+        error={null}
+        groups={[
+          {
+            key: "owner/repo1",
+            label: "owner/repo1",
+            count: 1,
+            pull_requests: [mockPrs[0]],
+          },
+          {
+            key: "owner/repo2",
+            label: "owner/repo2",
+            count: 1,
+            pull_requests: [mockPrs[1]],
+          },
+        ]}
+      />
+    );
+    expect(screen.getByText("owner/repo1 (1)")).toBeInTheDocument();
+    expect(screen.getByText("owner/repo2 (1)")).toBeInTheDocument();
+    expect(screen.getAllByRole("article")).toHaveLength(2);
+  });
+
+  it("highlights the selected PR", () => {
+    render(
+      <PrList
+        prs={mockPrs}
+        loading={false}
+        error={null}
+        selectedPr={{ owner: "Owner", repo: "repo2", number: 456 }}
+      />
+    );
+    const [first, second] = screen.getAllByRole("article");
+    expect(first).not.toHaveClass("pr-card-selected");
+    expect(second).toHaveClass("pr-card-selected");
+    expect(second).toHaveAttribute("aria-current", "true");
+  });
 });
@@ -1,4 +1,8 @@
-import type { PullRequestWithChecks } from "../types";
+import type {
+  PrSelection,
+  PullRequestGroup,
+  PullRequestWithChecks,
+} from "../types";
 import { PrCard } from "./PrCard";
 import "./PrList.css";
 
@@ -6,9 +10,30 @@ interface PrListProps {
   prs: PullRequestWithChecks[];
   loading: boolean;
   error: string | null;
+  selectedPr?: PrSelection | null;
+  /** Sections with headings, when the view is grouped */
+  groups?: PullRequestGroup[];
 }
 
-export function PrList({ prs, loading, error }: PrListProps) {
+function isSelected(
+  { pull_request: pr }: PullRequestWithChecks,
+  selection: PrSelection | null | undefined
+): boolean {
+  if (!selection) return false;
+  const fullName = `${selection.owner}/${selection.repo}`.toLowerCase();
+  return (
+    pr.number === selection.number &&
+    pr.repository.full_name.toLowerCase() === fullName
+  );
+}
+
+export function PrList({
+  prs,
+  loading,
+  error,
+  selectedPr,
+  groups,
+}: PrListProps) {
   if (loading) {
     return (
       <div className="pr-list-status">
@@ -42,6 +67,18 @@ export function PrList({ prs, loading, error }: PrListProps) {
     );
   }
 
+  const renderCards = (items: PullRequestWithChecks[]) => (
+    <div className="pr-list-items">
+      {items.map((prWithChecks) => (
+        <PrCard
+          key={prWithChecks.pull_request.id}
+          prWithChecks={prWithChecks}
+          selected={isSelected(prWithChecks, selectedPr)}
+        />
+      ))}
+    </div>
+  );
+
   const prCount = prs.length;
   const prLabel = prCount === 1 ? "pull request" : "pull requests";
 
@@ -52,14 +89,18 @@ export function PrList({ prs, loading, error }: PrListProps) {
           {prCount} {prLabel} awaiting review
         </h2>
       </header>
-      <div className="pr-list-items">
-        {prs.map((prWithChecks) => (
-          <PrCard
-            key={prWithChecks.pull_request.id}
-            prWithChecks={prWithChecks}
-          />
-        ))}
-      </div>
+      {groups ? (
+        groups.map((group) => (
+          <section key={group.key} className="pr-list-group">
+            <h3 className="pr-list-group-header">
+              {group.label} ({group.count})
+            </h3>
+            {renderCards(group.pull_requests)}
+          </section>
+        ))
+      ) : (
+        renderCards(prs)
+      )}
     </div>
   );
 }
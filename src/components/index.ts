@@ -1,3 +1,4 @@
+export { AuditLog } from "./AuditLog";
 export { Auth } from "./Auth";
 export { PrCard } from "./PrCard";
 export { PrList } from "./PrList";
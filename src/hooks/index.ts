@@ -1,2 +1,4 @@
 export { useGitHubAuth } from "./useGitHubAuth";
 export { useGitHubPRs } from "./useGitHubPRs";
+export { useSettings } from "./useSettings";
+export { useUiAutomation } from "./useUiAutomation";
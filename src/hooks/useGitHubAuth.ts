@@ -1,6 +1,7 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { AuthStatus, DeviceFlowInit } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import type { AuthStatus, DeviceFlowInit, DeviceFlowResult } from "../types";
 
 interface UseGitHubAuthReturn {
   authStatus: AuthStatus | null;
@@ -8,7 +9,8 @@ interface UseGitHubAuthReturn {
   error: string | null;
   deviceFlow: DeviceFlowInit | null;
   startLogin: () => Promise<void>;
-  pollLogin: () => Promise<void>;
+  cancelLogin: () => Promise<void>;
+  loginWithToken: (token: string) => Promise<void>;
   logout: () => Promise<void>;
 }
 
@@ -46,25 +48,55 @@ export function useGitHubAuth(): UseGitHubAuthReturn {
     }
   }, []);
 
-  const pollLogin = useCallback(async () => {
+  // The backend polls for the token and reports how the device flow ended
+  useEffect(() => {
     if (!deviceFlow) return;
 
-    try {
-      setError(null);
-      const status = await invoke<AuthStatus>("poll_device_flow", {
-        deviceCode: deviceFlow.device_code,
-      });
-      if (status.authenticated) {
-        setAuthStatus(status);
+    const unlisten = listen<DeviceFlowResult>(
+      "device-flow-complete",
+      (event) => {
+        const result = event.payload;
         setDeviceFlow(null);
+        switch (result.outcome) {
+          case "authorized":
+            setAuthStatus(result.status);
+            break;
+          case "expired":
+            setError("The sign-in code expired. Please try again.");
+            break;
+          case "denied":
+            setError("Authorization was denied on GitHub.");
+            break;
+          case "error":
+            setError(result.message);
+            break;
+        }
       }
+    );
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [deviceFlow]);
+
+  const cancelLogin = useCallback(async () => {
+    try {
+      await invoke("cancel_device_flow");
+    } finally {
+      setDeviceFlow(null);
+    }
+  }, []);
+
+  const loginWithToken = useCallback(async (token: string) => {
+    try {
+      setError(null);
+      const status = await invoke<AuthStatus>("login_with_token", { token });
+      setAuthStatus(status);
+      setDeviceFlow(null);
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : String(err);
-      if (!errorMessage.includes("authorization_pending")) {
-        setError(errorMessage);
-      }
+      setError(err instanceof Error ? err.message : String(err));
     }
-  }, [deviceFlow]);
+  }, []);
 
   const logout = useCallback(async () => {
     try {
@@ -82,7 +114,8 @@ export function useGitHubAuth(): UseGitHubAuthReturn {
     error,
     deviceFlow,
     startLogin,
-    pollLogin,
+    cancelLogin,
+    loginWithToken,
     logout,
   };
 }
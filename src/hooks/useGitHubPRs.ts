@@ -1,35 +1,39 @@
-import { useState, useEffect, useCallback } from "react";
+import { useState, useEffect, useCallback, useMemo } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { PullRequestWithChecks } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import type { PullRequestView, PullRequestWithChecks } from "../types";
 
 interface UseGitHubPRsReturn {
+  view: PullRequestView | null;
   prs: PullRequestWithChecks[];
   loading: boolean;
   error: string | null;
   refresh: () => Promise<void>;
 }
 
-export function useGitHubPRs(authenticated: boolean): UseGitHubPRsReturn {
-  const [prs, setPrs] = useState<PullRequestWithChecks[]>([]);
+export function useGitHubPRs(
+  authenticated: boolean,
+  refreshIntervalSecs = 0
+): UseGitHubPRsReturn {
+  const [view, setView] = useState<PullRequestView | null>(null);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
+  // Grouped and sorted by [view] in config.toml
   const fetchPRs = useCallback(async () => {
     if (!authenticated) {
-      setPrs([]);
+      setView(null);
       return;
     }
 
     try {
       setLoading(true);
       setError(null);
-      const result = await invoke<PullRequestWithChecks[]>(
-        "fetch_review_requested_prs"
-      );
-      setPrs(result);
+      const result = await invoke<PullRequestView>("fetch_pr_view");
+      setView(result);
     } catch (err) {
       setError(err instanceof Error ? err.message : String(err));
-      setPrs([]);
+      setView(null);
     } finally {
       setLoading(false);
     }
@@ -39,7 +43,34 @@ export function useGitHubPRs(authenticated: boolean): UseGitHubPRsReturn {
     fetchPRs();
   }, [fetchPRs]);
 
+  useEffect(() => {
+    if (!authenticated || refreshIntervalSecs <= 0) return;
+
+    const timer = setInterval(fetchPRs, refreshIntervalSecs * 1000);
+    return () => clearInterval(timer);
+  }, [authenticated, refreshIntervalSecs, fetchPRs]);
+
+  // The backend pushes a fresh view when a refresh is triggered externally (e.g. via MCP)
+  useEffect(() => {
+    if (!authenticated) return;
+
+    const unlisten = listen<PullRequestView>("prs-updated", (event) => {
+      setView(event.payload);
+      setError(null);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [authenticated]);
+
+  const prs = useMemo(
+    () => view?.groups.flatMap((group) => group.pull_requests) ?? [],
+    [view]
+  );
+
   return {
+    view,
     prs,
     loading,
     error,
@@ -0,0 +1,52 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { Settings } from "../types";
+
+interface UseSettingsReturn {
+  settings: Settings | null;
+  error: string | null;
+  updateSettings: (settings: Settings) => Promise<void>;
+}
+
+export function useSettings(): UseSettingsReturn {
+  const [settings, setSettings] = useState<Settings | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<Settings>("get_settings")
+      .then(setSettings)
+      .catch((err) => setError(String(err)));
+  }, []);
+
+  // The backend reloads config.toml when it changes on disk
+  useEffect(() => {
+    const unlistenChanged = listen<Settings>("settings-changed", (event) => {
+      setSettings(event.payload);
+      setError(null);
+    });
+    const unlistenError = listen<string>("settings-error", (event) => {
+      setError(event.payload);
+    });
+
+    return () => {
+      unlistenChanged.then((fn) => fn());
+      unlistenError.then((fn) => fn());
+    };
+  }, []);
+
+  const updateSettings = useCallback(async (next: Settings) => {
+    try {
+      const saved = await invoke<Settings>("update_settings", {
+        settings: next,
+      });
+      setSettings(saved);
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, []);
+
+  return { settings, error, updateSettings };
+}
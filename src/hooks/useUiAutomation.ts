@@ -0,0 +1,31 @@
+import { useState, useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import type { PrSelection } from "../types";
+
+interface UseUiAutomationReturn {
+  selectedPr: PrSelection | null;
+}
+
+// Follows navigation and selection requests sent over IPC (e.g. by the MCP server),
+// so visual checks can set up the window before taking a screenshot
+export function useUiAutomation(): UseUiAutomationReturn {
+  const [selectedPr, setSelectedPr] = useState<PrSelection | null>(null);
+
+  useEffect(() => {
+    const unlistenNavigate = listen<string>("ui-navigate", (event) => {
+      document
+        .querySelector(`[data-view="${event.payload}"]`)
+        ?.scrollIntoView?.({ block: "start" });
+    });
+    const unlistenSelect = listen<PrSelection>("ui-select-pr", (event) => {
+      setSelectedPr(event.payload);
+    });
+
+    return () => {
+      unlistenNavigate.then((fn) => fn());
+      unlistenSelect.then((fn) => fn());
+    };
+  }, []);
+
+  return { selectedPr };
+}
@@ -1,3 +1,5 @@
+import type { GroupBy, SortKey } from "./settings";
+
 export interface User {
   id: number;
   login: string;
@@ -53,16 +55,76 @@ export interface CheckStatus {
   statuses: StatusCheck[];
 }
 
+export type SlaStatus = "ok" | "warning" | "breached";
+
+export interface ReviewSla {
+  requested_at: string;
+  waiting_secs: number;
+  status: SlaStatus;
+}
+
 export interface PullRequestWithChecks {
   pull_request: PullRequest;
   check_status: CheckStatus | null;
+  review_sla?: ReviewSla | null;
+}
+
+export interface RepositorySla {
+  repository: string;
+  ok: number;
+  warning: number;
+  breached: number;
+}
+
+export interface SlaSummary {
+  warning_hours: number;
+  breach_hours: number;
+  ok: number;
+  warning: number;
+  breached: number;
+  untracked: number;
+  repositories: RepositorySla[];
+}
+
+export interface PullRequestGroup {
+  key: string;
+  label: string;
+  count: number;
+  pull_requests: PullRequestWithChecks[];
 }
 
+export interface PullRequestView {
+  group_by: GroupBy;
+  sort: SortKey[];
+  total: number;
+  groups: PullRequestGroup[];
+}
+
+export type ExportFormat = "markdown" | "csv" | "json";
+
+export type AuthSource = "environment" | "stored" | "gh_cli";
+
 export interface AuthStatus {
   authenticated: boolean;
   username: string | null;
+  source?: AuthSource | null;
+  scopes?: string[] | null;
+  missing_scopes?: string[];
 }
 
+export interface DeviceFlowProgress {
+  state: "pending" | "slow_down";
+  interval_secs: number;
+  remaining_secs: number;
+}
+
+export type DeviceFlowResult =
+  | { outcome: "authorized"; status: AuthStatus }
+  | { outcome: "expired" }
+  | { outcome: "denied" }
+  | { outcome: "cancelled" }
+  | { outcome: "error"; message: string };
+
 export interface DeviceFlowInit {
   user_code: string;
   verification_uri: string;
@@ -70,3 +132,68 @@ export interface DeviceFlowInit {
   expires_in: number;
   interval: number;
 }
+
+export interface ReviewRecord {
+  submitted_at: string;
+  repository: string;
+  number: number;
+  title: string;
+  html_url: string;
+  state: "APPROVED" | "CHANGES_REQUESTED" | "COMMENTED" | "DISMISSED";
+  time_to_first_review_secs: number | null;
+}
+
+export interface ReviewStats {
+  login: string;
+  range: { from: string; to: string };
+  reviews_submitted: number;
+  pull_requests_reviewed: number;
+  pull_requests_approved: number;
+  pull_requests_changes_requested: number;
+  median_time_to_first_review_secs: number | null;
+  busiest_repositories: { repository: string; reviews: number }[];
+  weeks: { week_start: string; reviews: number; pull_requests: number }[];
+  reviews: ReviewRecord[];
+}
+
+export type ReviewEvent = "APPROVE" | "COMMENT" | "REQUEST_CHANGES";
+
+interface ActionTarget {
+  owner: string;
+  repo: string;
+  number: number;
+}
+
+export type Action =
+  | ({
+      action: "submit_review";
+      event: ReviewEvent;
+      body?: string;
+    } & ActionTarget)
+  | ({ action: "add_comment"; body: string } & ActionTarget)
+  | ({ action: "rerun_failed_checks" } & ActionTarget);
+
+export type AuditOutcome = "performed" | "dry_run" | "refused" | "failed";
+
+export interface PlannedRequest {
+  description: string;
+  method: string;
+  url: string;
+  body?: unknown;
+}
+
+export interface AuditEntry {
+  timestamp: string;
+  source: string;
+  action: Action;
+  outcome: AuditOutcome;
+  requests?: PlannedRequest[];
+  error?: string;
+}
+
+// Payload of the ui-select-pr event
+export interface PrSelection {
+  owner: string;
+  repo: string;
+  number: number;
+}
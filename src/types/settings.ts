@@ -0,0 +1,117 @@
+export interface GitHubSettings {
+  api_url: string;
+  oauth_url: string;
+}
+
+export interface PullRequestSettings {
+  refresh_interval_secs: number;
+  queries: string[];
+}
+
+export interface IpcSettings {
+  transport: "unix" | "tcp";
+  socket_path: string;
+  tcp_addr: string;
+}
+
+export interface AuthSettings {
+  credential_store: "plaintext" | "keychain" | "encrypted";
+}
+
+export interface LoggingSettings {
+  filter: string;
+}
+
+export interface HttpSettings {
+  mode: "live" | "record" | "replay";
+  fixtures_dir?: string;
+}
+
+export type GroupBy =
+  | "none"
+  | "repository"
+  | "check_state"
+  | "review_status"
+  | "age";
+
+export type SortField =
+  | "updated"
+  | "created"
+  | "checks"
+  | "review"
+  | "requested"
+  | "repository"
+  | "title";
+
+/** A field to sort by, prefixed with "-" for descending */
+export type SortKey = SortField | `-${SortField}`;
+
+export interface ViewSettings {
+  group_by: GroupBy;
+  /** Sort keys applied in order, e.g. ["checks", "-updated"] */
+  sort: SortKey[];
+}
+
+export interface SlaSettings {
+  enabled: boolean;
+  warning_hours: number;
+  breach_hours: number;
+}
+
+export type McpWriteTool =
+  | "submit_review"
+  | "add_comment"
+  | "rerun_failed_checks";
+
+export interface McpSettings {
+  /** Write tools MCP clients may call; none by default */
+  enabled_tools: McpWriteTool[];
+  /** Only return what write tools would send */
+  dry_run: boolean;
+}
+
+export interface FilterRule {
+  name?: string;
+  action: "include" | "exclude";
+  repo?: string;
+  org?: string;
+  author?: string;
+  label?: string;
+  draft?: boolean;
+  older_than_days?: number;
+}
+
+export interface PullRequestRef {
+  repository: string;
+  number: number;
+  title: string;
+  author: string;
+}
+
+export interface RulePreview {
+  index: number;
+  description: string;
+  action: FilterRule["action"];
+  matches: PullRequestRef[];
+}
+
+export interface FilterPreview {
+  total: number;
+  shown: number;
+  rules: RulePreview[];
+  not_included: PullRequestRef[];
+}
+
+export interface Settings {
+  version: number;
+  github: GitHubSettings;
+  pull_requests: PullRequestSettings;
+  ipc: IpcSettings;
+  auth: AuthSettings;
+  logging: LoggingSettings;
+  http: HttpSettings;
+  view: ViewSettings;
+  sla: SlaSettings;
+  mcp: McpSettings;
+  filters: FilterRule[];
+}